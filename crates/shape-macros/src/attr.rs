@@ -1,9 +1,70 @@
 use darling::{FromAttributes, FromMeta};
-use syn::Type;
+use proc_macro2::{Ident, TokenTree};
+use syn::{punctuated::Punctuated, Token, Type};
+
+/// `#[shape(...)]` keys that have a different meaning than the `#[serde(...)]` key with the same name,
+/// they are only read by [`ShapeFieldAttrs`]
+const SHAPE_ONLY: &[&str] = &["with", "custom"];
+
+/// serde keys that don't change the shape of a type
+pub const IGNORED: &[&str] = &["crate", "expecting", "deny_unknown_fields", "borrow", "getter"];
+
+/// serde keys that change the shape of a type in a way the derive can't describe, see [`crate::lint`]
+pub const UNSUPPORTED: &[&str] = &["with", "serialize_with", "deserialize_with", "variant_identifier", "field_identifier"];
+
+/// Parse attributes merged from `#[serde(...)]` and `#[shape(...)]`,
+/// leaving out the [`SHAPE_ONLY`], [`IGNORED`] and [`UNSUPPORTED`] keys
+pub fn parse<T: FromAttributes>(attrs: &[syn::Attribute]) -> Result<T, darling::Error> {
+  let mut filtered = Vec::with_capacity(attrs.len());
+  for attr in &keywords(attrs) {
+    let skip: &[&[&str]] = if attr.path().is_ident("shape") {
+      &[SHAPE_ONLY, IGNORED, UNSUPPORTED]
+    } else if attr.path().is_ident("serde") {
+      &[IGNORED, UNSUPPORTED]
+    } else {
+      filtered.push(attr.clone());
+      continue;
+    };
+
+    let path = attr.path();
+    let metas = attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)?;
+    let metas = metas.into_iter()
+      .filter(|meta| !skip.iter().any(|keys| keys.iter().any(|key| meta.path().is_ident(key))))
+      .collect::<Vec<_>>();
+
+    if !metas.is_empty() {
+      filtered.push(syn::parse_quote!{ #[#path(#(#metas),*)] });
+    }
+  }
+
+  T::from_attributes(&filtered)
+}
+
+/// `type` is a keyword that `syn::Meta` can't parse, `#[shape(type = ...)]` is read as `#[shape(custom = ...)]`
+fn keywords(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
+  attrs.iter().map(|attr| {
+    let mut attr = attr.clone();
+    if let syn::Meta::List(list) = &mut attr.meta {
+      if list.path.is_ident("shape") {
+        list.tokens = list.tokens.clone().into_iter().map(|token| match token {
+          TokenTree::Ident(ident) if ident == "type" => TokenTree::Ident(Ident::new("custom", ident.span())),
+          token => token,
+        }).collect();
+      }
+    }
+    attr
+  }).collect()
+}
+
+/// Parse the [`ShapeFieldAttrs`] of a field
+pub fn shape_field(attrs: &[syn::Attribute]) -> Result<ShapeFieldAttrs, darling::Error> {
+  ShapeFieldAttrs::from_attributes(&keywords(attrs))
+}
 
 #[derive(Debug, Default,FromAttributes)]
 #[darling(attributes(serde, shape))]
 pub struct ContainerAttrs {
+  pub rename: Option<Complex<String>>,
   pub rename_all: Option<Complex<Inflection>>,
   pub rename_all_fields: Option<Complex<Inflection>>,
   pub tag: Option<String>,
@@ -15,15 +76,11 @@ pub struct ContainerAttrs {
   pub into: Option<Type>,
   pub try_into: Option<Type>,
   pub default: Option<UnitOr<String>>,
-  
-  // unused
-  // pub rename: Option<Complex<String>>,
-  // pub deny_unknown_fields: Option<()>,
-  // pub remote: Option<Type>,
-  // #[darling(rename = "crate")]
-  // pub serde_crate: Option<String>, 
-  // pub expecting: Option<String>,
-  // pub bound: Option<Complex<String>>,
+  pub bound: Option<Complex<String>>,
+  /// the declaration is named after the remote type, fields of the remote type use `#[shape(with = "Mirror")]`
+  pub remote: Option<syn::Path>,
+  /// don't warn about the [`UNSUPPORTED`] serde attributes, only for `#[shape(...)]`
+  pub allow_unsupported: Option<()>,
 }
 
 #[derive(Debug, Default,FromAttributes)]
@@ -35,15 +92,11 @@ pub struct VariantAttrs {
   pub skip_serializing: Option<()>,
   pub skip_deserializing: Option<()>,
   pub untagged: Option<()>,
-
-  // unused
-  // pub alias: Option<String>,
-  // pub with: Option<String>,
-  // pub serialize_with: Option<String>,
-  // pub deserialize_with: Option<String>,
-  // pub bound: Option<Complex<String>>,
-  // pub borrow: Option<UnitOr<String>>,
-  // pub other: Option<()>,
+  pub bound: Option<Complex<String>>,
+  pub other: Option<()>,
+  /// other names the variant is deserialized from
+  #[darling(multiple)]
+  pub alias: Vec<String>,
 }
 
 #[derive(Debug, Default, FromAttributes)]
@@ -56,17 +109,31 @@ pub struct FieldAttrs {
   pub skip_serializing: Option<()>,
   pub skip_deserializing: Option<()>,
   pub skip_serializing_if: Option<String>,
-  
-  // unused
-  // pub alias: Option<String>,
-  // pub with: Option<String>,
-  // pub serialize_with: Option<String>,
-  // pub deserialize_with: Option<String>,
-  // pub borrow: Option<UnitOr<String>>, 
-  // pub bound: Option<Complex<String>>,
-  // pub getter: Option<String>,
+  pub bound: Option<Complex<String>>,
+  /// other names the field is deserialized from
+  #[darling(multiple)]
+  pub alias: Vec<String>,
+}
+
+/// Field attributes that are only read from `#[shape(...)]`
+#[derive(Debug, Default, FromAttributes)]
+#[darling(attributes(shape), allow_unknown_fields)]
+pub struct ShapeFieldAttrs {
+  /// the type whose shape is used for the field, Eg: the mirror type of a remote derive
+  pub with: Option<Type>,
+  /// `#[shape(type = ...)]`, a custom type for the field, a name Eg: `"Date"` or an expression that implements `CustomType`
+  pub custom: Option<CustomExpr>,
 }
 
+/// The expression of `#[shape(type = ...)]` as it is written, darling would parse the content of a string
+#[derive(Debug)]
+pub struct CustomExpr(pub syn::Expr);
+
+impl FromMeta for CustomExpr {
+  fn from_expr(expr: &syn::Expr) -> Result<Self, darling::Error> {
+    Ok(Self(expr.clone()))
+  }
+}
 
 #[derive(Debug, FromMeta)]
 pub enum Inflection {
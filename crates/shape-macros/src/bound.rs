@@ -0,0 +1,233 @@
+use std::collections::HashSet;
+
+use syn::{punctuated::Punctuated, Token, WherePredicate};
+
+use crate::attr::{self, Complex, ContainerAttrs, FieldAttrs, VariantAttrs};
+
+/// The where predicates added to the `Shape` impl of a derived type
+pub struct Bounds {
+  pub predicates: Vec<WherePredicate>,
+  /// the type parameters that appear directly in the shape of the type and got a `T: Shape` bound
+  pub used: Vec<syn::Ident>,
+  /// true if the predicates come from a `bound` attribute instead of being inferred
+  pub custom: bool,
+}
+
+/// Compute the bounds of the `Shape` impl in the same way serde does for `Serialize` and `Deserialize`
+///
+/// A type parameter gets a `T: Shape` bound only if it appears in a field that is not skipped,
+/// outside of a `PhantomData`, and associated types like `T::Item` get a `T::Item: Shape` bound.
+///
+/// `#[serde(bound = "...")]` in the container replaces all the inferred bounds,
+/// in a variant or a field it replaces the bounds inferred for its fields
+pub fn bounds(container_attrs: &ContainerAttrs, generics: &syn::Generics, data: &syn::Data) -> Result<Bounds, darling::Error> {
+
+  let params = generics.type_params().map(|param| param.ident.clone()).collect::<HashSet<_>>();
+
+  let mut find = FindParams {
+    params: &params,
+    used: HashSet::new(),
+    projections: vec![],
+  };
+
+  let mut predicates = vec![];
+  let mut custom = false;
+
+  if let Some(bound) = &container_attrs.bound {
+    predicates.extend(parse_bound(bound)?);
+    custom = true;
+  }
+
+  match data {
+    syn::Data::Struct(data) => visit_fields(container_attrs, &data.fields, &mut find, &mut predicates, &mut custom)?,
+    syn::Data::Enum(data) => {
+      for variant in &data.variants {
+        let variant_attrs = attr::parse::<VariantAttrs>(&variant.attrs)?;
+        if variant_attrs.skip.is_some() {
+          continue;
+        }
+
+        match &variant_attrs.bound {
+          Some(bound) => {
+            custom = true;
+            if container_attrs.bound.is_none() {
+              predicates.extend(parse_bound(bound)?);
+            }
+          },
+          None => visit_fields(container_attrs, &variant.fields, &mut find, &mut predicates, &mut custom)?,
+        }
+      }
+    },
+    syn::Data::Union(_) => {},
+  }
+
+  // keep the order of declaration of the type parameters
+  let used = generics.type_params()
+    .map(|param| param.ident.clone())
+    .filter(|ident| find.used.contains(ident))
+    .collect::<Vec<_>>();
+
+  if container_attrs.bound.is_none() {
+    for ident in &used {
+      predicates.push(syn::parse_quote!{ #ident: ::shape::Shape });
+    }
+
+    for ty in &find.projections {
+      predicates.push(syn::parse_quote!{ #ty: ::shape::Shape });
+    }
+  }
+
+  Ok(Bounds {
+    predicates,
+    used,
+    custom,
+  })
+}
+
+fn visit_fields(
+  container_attrs: &ContainerAttrs,
+  fields: &syn::Fields,
+  find: &mut FindParams,
+  predicates: &mut Vec<WherePredicate>,
+  custom: &mut bool,
+) -> Result<(), darling::Error> {
+  for field in fields {
+    let field_attrs = attr::parse::<FieldAttrs>(&field.attrs)?;
+    if field_attrs.skip.is_some() {
+      continue;
+    }
+
+    match &field_attrs.bound {
+      Some(bound) => {
+        *custom = true;
+        if container_attrs.bound.is_none() {
+          predicates.extend(parse_bound(bound)?);
+        }
+      }
+      None => if let Some(ty) = crate::shape_type(field)? {
+        find.visit_type(&ty);
+      },
+    }
+  }
+  Ok(())
+}
+
+fn parse_bound(bound: &Complex<String>) -> Result<Vec<WherePredicate>, darling::Error> {
+  // a single Shape impl is used for both directions, so both bounds must hold
+  let sources = match bound {
+    Complex::Single(bound) => vec![bound],
+    Complex::Complex { serialize, deserialize } => [serialize, deserialize].into_iter().flatten().collect(),
+  };
+
+  let mut predicates = vec![];
+  for source in sources {
+    let parsed = syn::parse::Parser::parse_str(Punctuated::<WherePredicate, Token![,]>::parse_terminated, source)
+      .map_err(|e| darling::Error::custom(format!("invalid bound `{source}`: {e}")))?;
+    predicates.extend(parsed);
+  }
+
+  Ok(predicates)
+}
+
+struct FindParams<'a> {
+  params: &'a HashSet<syn::Ident>,
+  used: HashSet<syn::Ident>,
+  projections: Vec<syn::Type>,
+}
+
+impl FindParams<'_> {
+  fn visit_type(&mut self, ty: &syn::Type) {
+    match ty {
+      syn::Type::Path(path) => {
+        if let Some(qself) = &path.qself {
+          // <T as Trait>::Assoc
+          if self.mentions_param(&qself.ty) {
+            self.projections.push(ty.clone());
+          }
+          return;
+        }
+
+        let segments = &path.path.segments;
+
+        if path.path.leading_colon.is_none() {
+          if let Some(first) = segments.first() {
+            if self.params.contains(&first.ident) {
+              if segments.len() == 1 {
+                self.used.insert(first.ident.clone());
+              } else {
+                // T::Assoc
+                self.projections.push(ty.clone());
+              }
+              return;
+            }
+          }
+        }
+
+        if let Some(last) = segments.last() {
+          // serde never requires a bound for the parameters of PhantomData
+          if last.ident == "PhantomData" {
+            return;
+          }
+        }
+
+        for segment in segments {
+          self.visit_arguments(&segment.arguments);
+        }
+      },
+      syn::Type::Reference(reference) => self.visit_type(&reference.elem),
+      syn::Type::Slice(slice) => self.visit_type(&slice.elem),
+      syn::Type::Array(array) => self.visit_type(&array.elem),
+      syn::Type::Ptr(ptr) => self.visit_type(&ptr.elem),
+      syn::Type::Paren(paren) => self.visit_type(&paren.elem),
+      syn::Type::Group(group) => self.visit_type(&group.elem),
+      syn::Type::Tuple(tuple) => {
+        for elem in &tuple.elems {
+          self.visit_type(elem);
+        }
+      },
+      syn::Type::TraitObject(object) => {
+        for bound in &object.bounds {
+          if let syn::TypeParamBound::Trait(bound) = bound {
+            for segment in &bound.path.segments {
+              self.visit_arguments(&segment.arguments);
+            }
+          }
+        }
+      },
+      _ => {},
+    }
+  }
+
+  fn visit_arguments(&mut self, arguments: &syn::PathArguments) {
+    match arguments {
+      syn::PathArguments::AngleBracketed(arguments) => {
+        for arg in &arguments.args {
+          match arg {
+            syn::GenericArgument::Type(ty) => self.visit_type(ty),
+            syn::GenericArgument::AssocType(assoc) => self.visit_type(&assoc.ty),
+            _ => {},
+          }
+        }
+      },
+      syn::PathArguments::Parenthesized(arguments) => {
+        for input in &arguments.inputs {
+          self.visit_type(input);
+        }
+        if let syn::ReturnType::Type(_, ty) = &arguments.output {
+          self.visit_type(ty);
+        }
+      },
+      syn::PathArguments::None => {},
+    }
+  }
+
+  fn mentions_param(&self, ty: &syn::Type) -> bool {
+    let mut find = FindParams {
+      params: self.params,
+      used: HashSet::new(),
+      projections: vec![],
+    };
+    find.visit_type(ty);
+    !find.used.is_empty() || !find.projections.is_empty()
+  }
+}
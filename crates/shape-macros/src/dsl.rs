@@ -0,0 +1,312 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+  ext::IdentExt,
+  parse::{Parse, ParseStream},
+  token, Ident, LitBool, LitFloat, LitInt, LitStr, Token,
+};
+
+/// The construction of the `shape::Type` written in the `shape!` macro
+///
+/// ```text
+/// type    = "|"? and ("|" and)*
+/// and     = postfix ("&" postfix)*
+/// postfix = primary ("[" "]")*
+/// primary = keyword | literal | object | tuple | "(" type ")" | name ("<" type ("," type)* ">")? | "#" ident | "#" "(" expr ")"
+/// object  = "{" (member (("," | ";") member)*)? "}"
+/// member  = "readonly"? key "?"? ":" type | "[" ident ":" type "]" ":" type
+/// tuple   = "[" (type ("," type)*)? ("," "..." postfix)? "]"
+/// ```
+pub struct Dsl(pub TokenStream);
+
+impl Parse for Dsl {
+  fn parse(input: ParseStream) -> syn::Result<Self> {
+    let ty = union(input)?;
+    if !input.is_empty() {
+      return Err(input.error("expected `|`, `&` or the end of the type"));
+    }
+    Ok(Self(ty))
+  }
+}
+
+fn union(input: ParseStream) -> syn::Result<TokenStream> {
+  // TypeScript allows a leading `|` in multiline unions
+  if input.peek(Token![|]) {
+    input.parse::<Token![|]>()?;
+  }
+
+  let mut items = vec![intersection(input)?];
+  while input.peek(Token![|]) {
+    input.parse::<Token![|]>()?;
+    items.push(intersection(input)?);
+  }
+
+  Ok(match items.len() {
+    1 => items.remove(0),
+    _ => quote!{ ::shape::Type::or([#(#items),*]) },
+  })
+}
+
+fn intersection(input: ParseStream) -> syn::Result<TokenStream> {
+  let mut items = vec![postfix(input)?];
+  while input.peek(Token![&]) {
+    input.parse::<Token![&]>()?;
+    items.push(postfix(input)?);
+  }
+
+  Ok(match items.len() {
+    1 => items.remove(0),
+    _ => quote!{ ::shape::Type::and([#(#items),*]) },
+  })
+}
+
+fn postfix(input: ParseStream) -> syn::Result<TokenStream> {
+  let mut ty = primary(input)?;
+  while input.peek(token::Bracket) && is_empty_brackets(input) {
+    let _content;
+    syn::bracketed!(_content in input);
+    ty = array(ty);
+  }
+  Ok(ty)
+}
+
+fn is_empty_brackets(input: ParseStream) -> bool {
+  matches!(input.cursor().token_tree(), Some((TokenTree::Group(group), _)) if group.delimiter() == Delimiter::Bracket && group.stream().is_empty())
+}
+
+fn array(item: TokenStream) -> TokenStream {
+  quote!{ ::shape::Type::Array(::shape::Array { item: ::std::sync::Arc::new(#item) }) }
+}
+
+fn primary(input: ParseStream) -> syn::Result<TokenStream> {
+  if input.peek(token::Brace) {
+    return object(input);
+  }
+
+  if input.peek(token::Bracket) {
+    return tuple(input);
+  }
+
+  if input.peek(token::Paren) {
+    let content;
+    syn::parenthesized!(content in input);
+    let ty = union(&content)?;
+    if !content.is_empty() {
+      return Err(content.error("expected `)`"));
+    }
+    return Ok(ty);
+  }
+
+  if input.peek(Token![#]) {
+    input.parse::<Token![#]>()?;
+    let expr = if input.peek(token::Paren) {
+      let content;
+      syn::parenthesized!(content in input);
+      content.parse::<TokenStream>()?
+    } else {
+      let ident = input.call(Ident::parse_any)?;
+      quote!{ #ident }
+    };
+    return Ok(quote!{ ::core::convert::Into::<::shape::Type>::into(#expr) });
+  }
+
+  if input.peek(LitBool) {
+    let value = input.parse::<LitBool>()?;
+    return Ok(quote!{ ::shape::Type::Literal(::shape::Literal::Boolean(#value)) });
+  }
+
+  if input.peek(LitStr) {
+    let value = input.parse::<LitStr>()?;
+    return Ok(quote!{ ::shape::Type::Literal(::shape::Literal::String(::std::borrow::Cow::Borrowed(#value))) });
+  }
+
+  if input.peek(Token![-]) || input.peek(LitInt) || input.peek(LitFloat) {
+    return number(input);
+  }
+
+  let ident = input.call(Ident::parse_any)?;
+  let keyword = match ident.to_string().as_str() {
+    "null" => Some(quote!{ Null }),
+    "undefined" => Some(quote!{ Undefined }),
+    "never" => Some(quote!{ Never }),
+    "unknown" | "any" => Some(quote!{ Unknown }),
+    "boolean" => Some(quote!{ Boolean }),
+    "number" => Some(quote!{ Number }),
+    "string" => Some(quote!{ String }),
+    "Uint8Array" => Some(quote!{ Bytes }),
+    _ => None,
+  };
+
+  if let Some(keyword) = keyword {
+    return Ok(quote!{ ::shape::Type::#keyword });
+  }
+
+  let args = if input.peek(Token![<]) {
+    input.parse::<Token![<]>()?;
+    let mut args = vec![union(input)?];
+    while input.peek(Token![,]) {
+      input.parse::<Token![,]>()?;
+      args.push(union(input)?);
+    }
+    input.parse::<Token![>]>()?;
+    args
+  } else {
+    vec![]
+  };
+
+  match (ident.to_string().as_str(), args.as_slice()) {
+    ("Array", [item]) => Ok(array(item.clone())),
+    ("Record", [key, value]) => Ok(record(key, value)),
+    ("Array" | "Record", _) => Err(syn::Error::new(ident.span(), format!("wrong number of type arguments for `{ident}`"))),
+    (name, args) => Ok(quote!{
+      ::shape::Type::Apply(::shape::Apply { name: ::std::borrow::Cow::Borrowed(#name), args: vec![#(::std::sync::Arc::new(#args)),*] })
+    }),
+  }
+}
+
+fn number(input: ParseStream) -> syn::Result<TokenStream> {
+  let negative = input.peek(Token![-]);
+  if negative {
+    input.parse::<Token![-]>()?;
+  }
+
+  let value = if input.peek(LitInt) {
+    input.parse::<LitInt>()?.base10_parse::<f64>()?
+  } else {
+    input.parse::<LitFloat>()?.base10_parse::<f64>()?
+  };
+
+  let value = proc_macro2::Literal::f64_suffixed(value);
+  let sign = negative.then(|| quote!{ - });
+  Ok(quote!{ ::shape::Type::Literal(::shape::Literal::Number(#sign #value)) })
+}
+
+fn record(key: &TokenStream, value: &TokenStream) -> TokenStream {
+  quote!{
+    ::shape::Type::Record(::shape::Record {
+      optional: false,
+      readonly: false,
+      key: ::shape::RecordKey::from(#key),
+      value: ::std::sync::Arc::new(#value),
+    })
+  }
+}
+
+fn object(input: ParseStream) -> syn::Result<TokenStream> {
+  let content;
+  syn::braced!(content in input);
+
+  let mut properties = vec![];
+  let mut index = None;
+  while !content.is_empty() {
+    if content.peek(token::Bracket) {
+      let signature;
+      let span = content.span();
+      syn::bracketed!(signature in content);
+      signature.call(Ident::parse_any)?;
+      signature.parse::<Token![:]>()?;
+      let key = union(&signature)?;
+      content.parse::<Token![:]>()?;
+      let value = union(&content)?;
+      if index.replace(record(&key, &value)).is_some() {
+        return Err(syn::Error::new(span, "an object can only have one index signature"));
+      }
+    } else {
+      let fork = content.fork();
+      let readonly = fork.call(Ident::parse_any).is_ok_and(|ident| ident == "readonly") && !fork.peek(Token![:]) && !fork.peek(Token![?]);
+      if readonly {
+        content.call(Ident::parse_any)?;
+      }
+
+      let key = if content.peek(LitStr) {
+        content.parse::<LitStr>()?.value()
+      } else {
+        content.call(Ident::parse_any)?.unraw().to_string()
+      };
+
+      let optional = content.peek(Token![?]);
+      if optional {
+        content.parse::<Token![?]>()?;
+      }
+
+      content.parse::<Token![:]>()?;
+      let ty = union(&content)?;
+      properties.push(quote!{
+        (
+          ::std::borrow::Cow::Borrowed(#key),
+          ::shape::Property { optional: #optional, readonly: #readonly, ty: ::std::sync::Arc::new(#ty) },
+        )
+      });
+    }
+
+    if content.is_empty() {
+      break;
+    }
+
+    if content.peek(Token![;]) {
+      content.parse::<Token![;]>()?;
+    } else {
+      content.parse::<Token![,]>()?;
+    }
+  }
+
+  let object = quote!{
+    ::shape::Type::Object(::shape::Object {
+      properties: ::shape::indexmap::IndexMap::from([#(#properties),*]),
+    })
+  };
+
+  Ok(match index {
+    None => object,
+    Some(record) if properties.is_empty() => record,
+    Some(record) => quote!{ ::shape::Type::and([#object, #record]) },
+  })
+}
+
+fn tuple(input: ParseStream) -> syn::Result<TokenStream> {
+  let content;
+  syn::bracketed!(content in input);
+
+  let mut items = vec![];
+  let mut rest = None;
+  while !content.is_empty() {
+    if content.peek(Token![...]) {
+      content.parse::<Token![...]>()?;
+      let span = content.span();
+      let ty = primary(&content)?;
+      if !is_empty_brackets(&content) {
+        return Err(syn::Error::new(span, "the rest of a tuple must be an array Eg: `...string[]`"));
+      }
+
+      let _brackets;
+      syn::bracketed!(_brackets in content);
+      let mut ty = ty;
+      while is_empty_brackets(&content) {
+        let _brackets;
+        syn::bracketed!(_brackets in content);
+        ty = array(ty);
+      }
+      rest = Some(ty);
+
+      if !content.is_empty() {
+        return Err(content.error("the rest must be the last item of a tuple"));
+      }
+      break;
+    }
+
+    items.push(union(&content)?);
+    if content.is_empty() {
+      break;
+    }
+    content.parse::<Token![,]>()?;
+  }
+
+  let rest = match rest {
+    Some(rest) => quote!{ ::std::option::Option::Some(::std::sync::Arc::new(#rest)) },
+    None => quote!{ ::std::option::Option::None },
+  };
+
+  Ok(quote!{
+    ::shape::Type::Tuple(::shape::Tuple { items: vec![#(::std::sync::Arc::new(#items)),*], rest: #rest })
+  })
+}
@@ -1,12 +1,14 @@
 mod attr;
+mod bound;
+mod dsl;
+mod lint;
 
 use attr::{Complex, ContainerAttrs, FieldAttrs, VariantAttrs};
-use darling::{ast::GenericParamExt, FromAttributes}; 
 use syn::{spanned::Spanned, DeriveInput, GenericArgument, LitStr, Variant};
 use quote::quote;
 
 #[proc_macro_derive(Shape, attributes(serde, shape))]
-pub fn shape(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn derive_shape(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
   let input = syn::parse_macro_input!(input as syn::DeriveInput);
   match shape_inner(input) {
     Ok(ts) => ts.into(),
@@ -14,11 +16,20 @@ pub fn shape(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
   }
 }
 
+/// A `shape::Type` written in a TypeScript like syntax, Eg: `shape!{ { id: number, tags?: string[] } | null }`,
+/// `#ident` and `#(expr)` insert an expression that is `Into<shape::Type>`
+#[proc_macro]
+pub fn shape(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let dsl = syn::parse_macro_input!(input as dsl::Dsl);
+  dsl.0.into()
+}
+
 fn shape_inner(input: DeriveInput) -> Result<proc_macro2::TokenStream, darling::Error> {
 
   let ident = &input.ident;
   
-  let container_attrs = ContainerAttrs::from_attributes(&input.attrs)?;
+  let container_attrs = attr::parse::<ContainerAttrs>(&input.attrs)?;
+  let warnings = lint::lint(&input, &container_attrs)?;
   
   let mut early = quote!{};
 
@@ -41,18 +52,24 @@ fn shape_inner(input: DeriveInput) -> Result<proc_macro2::TokenStream, darling::
   }
 
 
+  let referenced = referenced_types(&container_attrs, &input.data)?;
+  let bounds = bound::bounds(&container_attrs, &input.generics, &input.data)?;
+
   let ty;
   match input.data {
     syn::Data::Struct(data) => {
       match data.fields {
+        _ if container_attrs.transparent.is_some() => {
+          ty = transparent(&data.fields)?;
+        },
         syn::Fields::Unit  => {
-          ty = quote!{ ::shape::Type::Null };  
+          ty = quote!{ options.unit() };  
         },
         syn::Fields::Unnamed(fields) => {
           ty = fields_unnamed(&container_attrs, None, &fields)?;
         },
         syn::Fields::Named(fields) => {
-          ty = fields_named(&container_attrs, None, &fields)?;
+          ty = fields_named(&container_attrs, None, Some(ident), &fields)?;
         }
       }
     },
@@ -60,7 +77,7 @@ fn shape_inner(input: DeriveInput) -> Result<proc_macro2::TokenStream, darling::
       let mut variants = vec![];
       for variant in data.variants {
 
-        let variant_attrs = VariantAttrs::from_attributes(&variant.attrs)?;
+        let variant_attrs = attr::parse::<VariantAttrs>(&variant.attrs)?;
 
         if variant_attrs.skip.is_some() {
           continue;
@@ -146,66 +163,131 @@ fn shape_inner(input: DeriveInput) -> Result<proc_macro2::TokenStream, darling::
           }
         };
 
-        let variant_ty = match &variant.fields {
-          syn::Fields::Unit => {
-            if variant_attrs.untagged.is_some() || container_attrs.untagged.is_some() {
-              quote! { ::shape::Type::Null }
-            } else {
-              match &container_attrs.tag {
-                Some(tag) => {
-                  let tag = LitStr::new(tag, variant.span());
-                  quote!{ ::shape::Type::Object(
-                    ::shape::Object {
-                      properties: ::shape::indexmap::IndexMap::from([
-                        (
-                          String::from(#tag),
-                          ::shape::Property {
-                            optional: false,
-                            readonly: false,
-                            ty: ::shape::Type::Literal(::shape::Literal::String(String::from(#get_name)))
-                          }
-                        )
-                      ])
-                    })
+        // the shape of the variant when it is named `get_name`
+        let named_variant_ty = |get_name: proc_macro2::TokenStream| -> Result<proc_macro2::TokenStream, darling::Error> {
+          Ok(match &variant.fields {
+            syn::Fields::Unit => {
+              if variant_attrs.untagged.is_some() || container_attrs.untagged.is_some() {
+                quote! { options.unit() }
+              } else {
+                match &container_attrs.tag {
+                  Some(tag) => {
+                    let tag = LitStr::new(tag, variant.span());
+                    quote!{ ::shape::Type::Object(
+                      ::shape::Object {
+                        properties: ::shape::indexmap::IndexMap::from([
+                          (
+                            ::std::borrow::Cow::Borrowed(#tag),
+                            ::shape::Property {
+                              optional: false,
+                              readonly: false,
+                              ty: ::std::sync::Arc::new(::shape::Type::Literal(::shape::Literal::String(::std::borrow::Cow::Borrowed(#get_name))))
+                            }
+                          )
+                        ])
+                      })
+                    }
                   }
-                }
 
-                None => {
-                  quote! {
-                    ::shape::Type::Literal(
-                      ::shape::Literal::String(
-                        String::from(#get_name)
+                  None => {
+                    quote! {
+                      ::shape::Type::Literal(
+                        ::shape::Literal::String(
+                          ::std::borrow::Cow::Borrowed(#get_name)
+                        )
                       )
-                    )
+                    }
                   }
                 }
               }
             }
-          }
 
-          syn::Fields::Unnamed(fields) => {
-            let fields = fields_unnamed(&container_attrs, Some(&variant_attrs), fields)?;
-            join_enum_fields(fields, get_name, &variant, &variant_attrs, &container_attrs)
+            syn::Fields::Unnamed(fields) => {
+              let fields = fields_unnamed(&container_attrs, Some(&variant_attrs), fields)?;
+              join_enum_fields(fields, get_name, &variant, &variant_attrs, &container_attrs)
+            }
+
+            syn::Fields::Named(fields) => {
+              let fields = fields_named(&container_attrs, Some(&variant_attrs), None, fields)?;
+              join_enum_fields(fields, get_name, &variant, &variant_attrs, &container_attrs)
+            }
+          })
+        };
+
+        let variant_ty = named_variant_ty(get_name)?;
+
+        // serde deserializes the variant from its aliases too
+        let mut aliases = vec![];
+        if variant_attrs.untagged.is_none() && container_attrs.untagged.is_none() {
+          for alias in &variant_attrs.alias {
+            let alias = LitStr::new(alias, variant.ident.span());
+            aliases.push(named_variant_ty(quote! { #alias })?);
           }
+        }
+
+        // an `other` variant is deserialized from any tag, but it is still serialized with its own name
+        let de_variant_ty = match variant_attrs.other {
+          None => None,
+          Some(()) => {
+            if !matches!(variant.fields, syn::Fields::Unit) {
+              return Err(darling::Error::custom("#[serde(other)] must be on a unit variant").with_span(&variant));
+            }
 
-          syn::Fields::Named(fields) => {
-            let fields = fields_named(&container_attrs, Some(&variant_attrs), fields)?;
-            join_enum_fields(fields, get_name, &variant, &variant_attrs, &container_attrs)
+            if variant_attrs.untagged.is_some() || container_attrs.untagged.is_some() {
+              return Err(darling::Error::custom("#[serde(other)] cannot appear on untagged enum").with_span(&variant));
+            }
+
+            Some(match &container_attrs.tag {
+              Some(tag) => {
+                let tag = LitStr::new(tag, variant.span());
+                quote! {
+                  ::shape::Type::Object(::shape::Object {
+                    properties: ::shape::indexmap::IndexMap::from([
+                      (
+                        ::std::borrow::Cow::Borrowed(#tag),
+                        ::shape::Property {
+                          optional: false,
+                          readonly: false,
+                          ty: ::std::sync::Arc::new(::shape::Type::String),
+                        }
+                      )
+                    ])
+                  })
+                }
+              },
+              None => quote! { ::shape::Type::String },
+            })
           }
         };
 
         let skip_serializing = variant_attrs.skip_serializing.is_some();
         let skip_deserializing = variant_attrs.skip_deserializing.is_some();
       
-        variants.push(quote! {
-          if options.is_serialize() && !#skip_serializing {
-            variants.push(#variant_ty);
-          }
+        // the variant is generated once, unless it is serialized and deserialized differently
+        variants.push(match de_variant_ty {
+          None => quote! {
+            if (options.is_serialize() && !#skip_serializing) || (options.is_deserialize() && !#skip_deserializing) {
+              variants.push(#variant_ty);
+            }
+          },
+          Some(de_variant_ty) => quote! {
+            if options.is_serialize() && !#skip_serializing {
+              variants.push(#variant_ty);
+            }
 
-          if options.is_deserialize() && !#skip_deserializing {
-            variants.push(#variant_ty);  
-          }
+            if options.is_deserialize() && !#skip_deserializing {
+              variants.push(#de_variant_ty);
+            }
+          },
         });
+
+        if !aliases.is_empty() {
+          variants.push(quote! {
+            if options.is_deserialize() && !#skip_deserializing {
+              #(variants.push(#aliases);)*
+            }
+          });
+        }
       };
 
       ty = quote! {
@@ -214,76 +296,259 @@ fn shape_inner(input: DeriveInput) -> Result<proc_macro2::TokenStream, darling::
         if variants.is_empty() {
           ::shape::Type::Never
         } else {
-          ::shape::Type::Or(variants)
+          ::shape::Type::or(variants)
         }
       }
     },
-    syn::Data::Union(_) => {
-      return Err(darling::Error::custom("Unions are not yet supported"))
+    syn::Data::Union(data) => {
+      return Err(
+        darling::Error::custom("Shape can not be derived for unions because serde does not support them, implement Shape manually instead")
+          .with_span(&data.union_token)
+      )
     }
   }
 
   let generics = &input.generics;
   
   let (impl_generics, type_generics, where_clause ) = generics.split_for_impl();
-  let shape_where =  {
-    generics.type_params()
-      .filter_map(|param| {
-        let ty = param.as_type_param()?;
-        Some(
-          quote! {
-            #ty: ::shape::Shape
-          }
-        )
-      })
-      .collect::<Vec<proc_macro2::TokenStream>>()
-  };
 
+  let predicates = &bounds.predicates;
   let where_clause = match where_clause {
     None => {
-      if shape_where.is_empty() {
+      if predicates.is_empty() {
         quote!{}  
       } else {
         quote! {
-          where #(#shape_where),*
+          where #(#predicates),*
         }
       }
     },
     Some(original) => {
       let mut clause = quote! { #original };
-      if !shape_where.is_empty() {
+      if !predicates.is_empty() {
+        if !original.predicates.empty_or_trailing() {
+          clause = quote! { #clause, };
+        }
         clause = quote! {
-          #clause, #(#shape_where),*
+          #clause #(#predicates),*
         }
       }
       clause
     }
   };
 
-  let implementation = quote! {
-    
-    impl #impl_generics ::shape::Shape for #ident #type_generics #where_clause {
+  let declare_referenced = quote! {
+    #(<#referenced as ::shape::Shape>::declare(options, declarations);)*
+  };
+
+  let shape_impl = match declaration_params(generics, &bounds) {
+    // the type can't be instantiated with placeholder parameters, so it is always inlined
+    None => quote! {
       fn shape(options: &::shape::ShapeOptions) -> ::shape::Type {
+        Self::__shape_inline(options)
+      }
+
+      fn declare(options: &::shape::ShapeOptions, declarations: &mut ::shape::Declarations) {
+        Self::__shape_declare_referenced(options, declarations);
+      }
+    },
+
+    Some(params) => {
+      // the serde name of the container, a remote derive describes the remote type so it is named after it
+      let name = match container_attrs.remote.as_ref().and_then(|remote| remote.segments.last()) {
+        Some(remote) if container_attrs.rename.is_none() => container_name(&remote.ident, &container_attrs),
+        _ => container_name(ident, &container_attrs),
+      };
+      
+      let param_names = params.iter().map(|(name, _)| name).collect::<Vec<_>>();
+      let param_idents = params.iter().map(|(_, ident)| ident).collect::<Vec<_>>();
+      
+      let placeholders = quote! {
+        #(
+          struct #param_idents;
+          impl ::shape::Shape for #param_idents {
+            fn shape(_: &::shape::ShapeOptions) -> ::shape::Type {
+              ::shape::Type::Param(::std::borrow::Cow::Borrowed(#param_names))
+            }
+          }
+        )*
+      };
+
+      let placeholder_args = generics.params.iter().map(|param| {
+        match param {
+          syn::GenericParam::Lifetime(_) => quote!{ 'static },
+          syn::GenericParam::Type(ty) => {
+            let (_, ident) = params.iter().find(|(name, _)| ty.ident == name).unwrap();
+            quote!{ #ident }
+          },
+          // const params are never declarable
+          syn::GenericParam::Const(_) => unreachable!(),
+        }
+      });
+
+      let placeholder_ty = quote! {
+        #ident::<#(#placeholder_args),*>
+      };
+
+      let type_params = &bounds.used;
+      let declaration_params = type_params.iter().map(|ident| LitStr::new(&ident.to_string(), ident.span()));
+
+      quote! {
+        fn shape(options: &::shape::ShapeOptions) -> ::shape::Type {
+          if options.use_declarations {
+            ::shape::Type::Apply(::shape::Apply {
+              name: ::std::borrow::Cow::Borrowed(#name),
+              args: vec![
+                #(::std::sync::Arc::new(<#type_params as ::shape::Shape>::shape(options))),*
+              ],
+            })
+          } else {
+            Self::__shape_inline(options)
+          }
+        }
+
+        fn declaration(options: &::shape::ShapeOptions) -> Option<::shape::Declaration> {
+          #placeholders
+          let options = ::shape::ShapeOptions {
+            use_declarations: true,
+            ..options.clone()
+          };
+          Some(::shape::Declaration {
+            name: ::std::borrow::Cow::Borrowed(#name),
+            params: vec![#(String::from(#declaration_params)),*],
+            ty: #placeholder_ty::__shape_inline(&options),
+          })
+        }
+
+        fn declare(options: &::shape::ShapeOptions, declarations: &mut ::shape::Declarations) {
+          if declarations.reserve(#name, ::std::concat!(::std::module_path!(), "::", ::std::stringify!(#ident))) {
+            if let Some(declaration) = <Self as ::shape::Shape>::declaration(options) {
+              declarations.insert(declaration);
+            }
+            #placeholders
+            #placeholder_ty::__shape_declare_referenced(options, declarations);
+          }
+          #(<#type_params as ::shape::Shape>::declare(options, declarations);)*
+        }
+      }
+    }
+  };
+
+  Ok(quote! {
+    impl #impl_generics #ident #type_generics #where_clause {
+      #[doc(hidden)]
+      #[allow(unused_assignments)]
+      fn __shape_inline(options: &::shape::ShapeOptions) -> ::shape::Type {
         #early
         #ty
       }
+
+      #[doc(hidden)]
+      #[allow(unused_variables)]
+      fn __shape_declare_referenced(options: &::shape::ShapeOptions, declarations: &mut ::shape::Declarations) {
+        #declare_referenced
+      }
+    }
+
+    impl #impl_generics ::shape::Shape for #ident #type_generics #where_clause {
+      #shape_impl
+    }
+
+    #(#warnings)*
+  })
+}
+
+/// The names of the type parameters and the placeholder type used for each one of them
+/// when the type is instantiated to describe its generic declaration.
+/// 
+/// Only the parameters used in the shape of the type (see [`bound::Bounds::used`]) are parameters
+/// of the declaration, Eg: a `PhantomData<T>` marker does not make `T` a parameter.
+/// 
+/// Returns `None` if the type can't be instantiated with placeholders, that is when
+/// its parameters have bounds, it has const parameters or its bounds are set with a `bound` attribute
+fn declaration_params(generics: &syn::Generics, bounds: &bound::Bounds) -> Option<Vec<(String, proc_macro2::Ident)>> {
+  if bounds.custom {
+    return None;
+  }
+
+  if let Some(where_clause) = &generics.where_clause {
+    if !where_clause.predicates.is_empty() {
+      return None;
+    }
+  }
+
+  let mut params = vec![];
+  for param in &generics.params {
+    match param {
+      syn::GenericParam::Lifetime(_) => {},
+      syn::GenericParam::Const(_) => return None,
+      syn::GenericParam::Type(ty) => {
+        if !ty.bounds.is_empty() || ty.default.is_some() {
+          return None;
+        }
+        let placeholder = proc_macro2::Ident::new(&format!("__ShapeParam{}", ty.ident), ty.ident.span());
+        params.push((ty.ident.to_string(), placeholder));
+      }
     }
+  }
+
+  Some(params)
+}
+
+/// The types that the shape of this type references, for which their declarations must be added
+fn referenced_types(container_attrs: &ContainerAttrs, data: &syn::Data) -> Result<Vec<syn::Type>, darling::Error> {
+  let mut types = vec![];
+  
+  for ty in [&container_attrs.into, &container_attrs.try_into, &container_attrs.from, &container_attrs.try_from].into_iter().flatten() {
+    types.push(ty.clone());
+  }
+
+  let mut push_fields = |fields: &syn::Fields| -> Result<(), darling::Error> {
+    for field in fields {
+      let field_attrs = attr::parse::<FieldAttrs>(&field.attrs)?;
+      if field_attrs.skip.is_none() {
+        types.extend(shape_type(field)?);
+      }
+    }
+    Ok(())
   };
 
-  Ok(implementation)
+  match data {
+    syn::Data::Struct(data) => push_fields(&data.fields)?,
+    syn::Data::Enum(data) => {
+      for variant in &data.variants {
+        let variant_attrs = attr::parse::<VariantAttrs>(&variant.attrs)?;
+        if variant_attrs.skip.is_none() {
+          push_fields(&variant.fields)?;
+        }
+      }
+    },
+    syn::Data::Union(_) => {},
+  }
+
+  Ok(types)
+}
+
+/// The shape of a transparent struct is the shape of its only field that is not skipped
+fn transparent(fields: &syn::Fields) -> Result<proc_macro2::TokenStream, darling::Error> {
+  for field in fields {
+    let field_attrs = attr::parse::<FieldAttrs>(&field.attrs)?;
+    if field_attrs.skip.is_none() {
+      return field_shape(field);
+    }
+  }
+
+  // checked in lint
+  unreachable!()
 }
 
 fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&VariantAttrs>, fields: &syn::FieldsUnnamed) -> Result<proc_macro2::TokenStream, darling::Error> {
 
   if fields.unnamed.len() == 1 {
     let inner = fields.unnamed.first().unwrap();
-    let ty = &inner.ty;
-
-    let field_attrs = FieldAttrs::from_attributes(&inner.attrs)?;
+    let field_attrs = attr::parse::<FieldAttrs>(&inner.attrs)?;
 
-    let inner = quote!{
-      <#ty as ::shape::Shape>::shape(options)
-    };
+    let inner = field_shape(inner)?;
 
     let field_skip_serializing = field_attrs.skip_serializing.is_some();
     let field_skip_serializing_if = field_attrs.skip_serializing_if.is_some();
@@ -296,7 +561,7 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
       if field_attrs.skip.is_some() {
         quote!{
           // empty tuple
-          ::shape::Type::Tuple(Tuple {
+          ::shape::Type::Tuple(::shape::Tuple {
             items: vec![],
             rest: None,
           })
@@ -307,15 +572,15 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
             if #field_skip_serializing {
               ::shape::Type::Null
             } else if #field_skip_serializing_if {
-              ::shape::Type::Or(vec![ #inner, ::shape::Type::Undefined ])
+              ::shape::Type::or([ #inner, ::shape::Type::Undefined ])
             } else {
               #inner
             }
           } else {
             if #field_skip_deserializing {
-              ::shape::Type::Or(vec![ ::shape::Type::Undefined, ::shape::Type::Null ])
+              ::shape::Type::or([ ::shape::Type::Undefined, ::shape::Type::Null ])
             } else if #field_has_default || #container_has_default {
-              ::shape::Type::Or(vec![ #inner, ::shape::Type::Undefined ])
+              ::shape::Type::or([ #inner, ::shape::Type::Undefined ])
             } else {
               #inner
             }
@@ -333,7 +598,7 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
     let mut prev_has_default = None;
 
     for field in &fields.unnamed { 
-      let field_attrs = FieldAttrs::from_attributes(&field.attrs)?;
+      let field_attrs = attr::parse::<FieldAttrs>(&field.attrs)?;
 
       if field_attrs.skip.is_some() {
         continue;
@@ -354,10 +619,10 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
         prev_has_default = Some(true);
       }
 
-      let ty = &field.ty;
+      let shape = field_shape(field)?;
       
       variants.push(quote!{
-        let ty = <#ty as ::shape::Shape>::shape(options);
+        let ty = ::std::sync::Arc::new(#shape);
         if options.is_serialize() {
           if #skip_serializing {
             // do nothing
@@ -389,7 +654,7 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
 
     let shape = quote! {
       {
-        let mut variants: Vec<Vec<::shape::Type>> = vec![vec![]];
+        let mut variants: Vec<Vec<::std::sync::Arc<::shape::Type>>> = vec![vec![]];
         #( { #variants }; )*
         if variants.len() == 1 {
           ::shape::Type::Tuple(::shape::Tuple {
@@ -397,13 +662,13 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
             rest: None,
           })
         } else {
-          ::shape::Type::Or(
+          ::shape::Type::or(
             variants.into_iter().map(|items| {
               ::shape::Type::Tuple(::shape::Tuple {
                 items,
                 rest: None,
               })
-            }).collect::<Vec<::shape::Type>>()
+            })
           )
         }
       }
@@ -413,39 +678,77 @@ fn fields_unnamed(container_attrs: &ContainerAttrs, _variant_attrs: Option<&Vari
   }
 }
 
-fn fields_named(container_attrs: &ContainerAttrs, variant_attrs: Option<&VariantAttrs>, fields: &syn::FieldsNamed) -> Result<proc_macro2::TokenStream, darling::Error> {
+/// The name of the container after applying `rename`, used as the name of its declaration and as the tag of structs with `#[serde(tag = "...")]`
+fn container_name(ident: &syn::Ident, container_attrs: &ContainerAttrs) -> proc_macro2::TokenStream {
+  let name = LitStr::new(&ident.to_string(), ident.span());
+  match &container_attrs.rename {
+    None => quote! { #name },
+    Some(Complex::Single(rename)) => {
+      let rename = LitStr::new(rename, ident.span());
+      quote! { #rename }
+    },
+    Some(Complex::Complex { serialize, deserialize }) => {
+      let serialize = LitStr::new(serialize.as_deref().unwrap_or(&ident.to_string()), ident.span());
+      let deserialize = LitStr::new(deserialize.as_deref().unwrap_or(&ident.to_string()), ident.span());
+      quote! {
+        if options.is_serialize() { #serialize } else { #deserialize }
+      }
+    }
+  }
+}
+
+/// `struct_ident` is `None` for the fields of enum variants
+fn fields_named(
+  container_attrs: &ContainerAttrs,
+  variant_attrs: Option<&VariantAttrs>,
+  struct_ident: Option<&syn::Ident>,
+  fields: &syn::FieldsNamed,
+) -> Result<proc_macro2::TokenStream, darling::Error> {
+
+  // Eg: #[serde(tag = "type")] struct Point { x: u8 } is serialized as { "type": "Point", "x": 0 }
+  let struct_tag = match (struct_ident, &container_attrs.tag) {
+    (Some(ident), Some(tag)) => {
+      let tag = LitStr::new(tag, ident.span());
+      let name = container_name(ident, container_attrs);
+      quote! {
+        properties.insert(
+          ::std::borrow::Cow::Borrowed(#tag),
+          ::shape::Property {
+            readonly: false,
+            optional: false,
+            ty: ::std::sync::Arc::new(::shape::Type::Literal(::shape::Literal::String(::std::borrow::Cow::Borrowed({ #name })))),
+          }
+        );
+      }
+    },
+    _ => quote! {},
+  };
+
+  let declare_properties = quote! {
+    #[allow(unused_mut)]
+    let mut properties = ::shape::indexmap::IndexMap::<::std::borrow::Cow<'static, str>, ::shape::Property>::new();
+    #struct_tag
+  };
   
   let shape = if fields.named.is_empty() {
     quote!{
-      ::shape::Type::Object(::shape::Object {
-        properties: ::shape::indexmap::IndexMap::new(),
-      })
-    }
-  } else {
-    
-    if container_attrs.transparent.is_some() {
-      if fields.named.len() == 1 {
-        let field = fields.named.first().unwrap();
-        let ty = &field.ty;
-        let decl = quote!{
-          <#ty as ::shape::Shape>::shape(options)
-        };
-        return Ok(decl);
-      } else {
-        return Err(darling::Error::custom("transparent structs can only have one field"))
+      {
+        #declare_properties
+        ::shape::Type::Object(::shape::Object {
+          properties,
+        })
       }
     }
-
-    let declare_properties = quote! {
-      let mut properties = ::shape::indexmap::IndexMap::<String, ::shape::Property>::new();
-    };
+  } else {
 
     let mut populate_properties = quote!{};
 
     let mut flattened = vec![];
 
+    let mut has_aliases = false;
+
     for field in &fields.named {
-      let field_attrs = FieldAttrs::from_attributes(&field.attrs)?;
+      let field_attrs = attr::parse::<FieldAttrs>(&field.attrs)?;
 
       if field_attrs.skip.is_some() {
         continue;
@@ -466,7 +769,8 @@ fn fields_named(container_attrs: &ContainerAttrs, variant_attrs: Option<&Variant
       let field_skip_deserializing = field_attrs.skip_deserializing.is_some();
       
       let optional = quote!{
-        if #is_option && options.option_is_optional {
+        // TOML skips the fields that are None
+        if #is_option && (options.option_is_optional || !options.format.has_null()) {
           true
         } else if options.is_serialize() {
           if #field_skip_serializing_if {
@@ -587,31 +891,70 @@ fn fields_named(container_attrs: &ContainerAttrs, variant_attrs: Option<&Variant
         get_name
       };
 
-      let ty = &field.ty;
+      let shape = field_shape(field)?;
 
-      populate_properties = quote! {
-        #populate_properties
-        
-        if (options.is_serialize() && !#field_skip_serializing) || (options.is_deserialize() && !#field_skip_deserializing) {
-          properties.insert(
-            String::from({ #get_name }), 
-            ::shape::Property {
-              readonly: #readonly,
-              optional: #optional,
-              ty: <#ty as ::shape::Shape>::shape(options),
-            }
-          );
-        }        
+      let property = quote! {
+        ::shape::Property {
+          readonly: #readonly,
+          optional: #optional,
+          ty: ::std::sync::Arc::new(#shape),
+        }
+      };
+
+      if field_attrs.alias.is_empty() {
+        populate_properties = quote! {
+          #populate_properties
+          
+          if (options.is_serialize() && !#field_skip_serializing) || (options.is_deserialize() && !#field_skip_deserializing) {
+            properties.insert(::std::borrow::Cow::Borrowed({ #get_name }), #property);
+          }        
+        }
+      } else {
+        // serde deserializes the field from any one of its names, Eg: `{ name: string } | { username: string }`
+        has_aliases = true;
+        let aliases = field_attrs.alias.iter().map(|alias| LitStr::new(alias, ident.span()));
+        populate_properties = quote! {
+          #populate_properties
+
+          if options.is_serialize() && !#field_skip_serializing {
+            properties.insert(::std::borrow::Cow::Borrowed({ #get_name }), #property);
+          } else if options.is_deserialize() && !#field_skip_deserializing {
+            let property = #property;
+            aliased.push(::shape::Type::or(
+              [{ #get_name }, #(#aliases),*].into_iter().map(|name| ::shape::Type::Object(::shape::Object {
+                properties: ::shape::indexmap::IndexMap::from([(::std::borrow::Cow::Borrowed(name), property.clone())]),
+              }))
+            ));
+          }
+        }
       }
     }
 
-    let not_flatten = quote! {
-      {
-        #declare_properties
-        #populate_properties
-        ::shape::Type::Object(::shape::Object {
-          properties,
-        })
+    let not_flatten = if has_aliases {
+      quote! {
+        {
+          #declare_properties
+          let mut aliased = ::std::vec::Vec::<::shape::Type>::new();
+          #populate_properties
+          let object = ::shape::Type::Object(::shape::Object {
+            properties,
+          });
+          if aliased.is_empty() {
+            object
+          } else {
+            ::shape::Type::and(::std::iter::once(object).chain(aliased))
+          }
+        }
+      }
+    } else {
+      quote! {
+        {
+          #declare_properties
+          #populate_properties
+          ::shape::Type::Object(::shape::Object {
+            properties,
+          })
+        }
       }
     };
 
@@ -631,28 +974,28 @@ fn fields_named(container_attrs: &ContainerAttrs, variant_attrs: Option<&Variant
         let container_has_default = container_attrs.default.is_some();
         let field_skip_deserializing = field_attrs.skip_deserializing.is_some();
 
-        let ty = &field.ty;
+        let flat = field_shape(&field)?;
 
         shape = quote! {
           #shape
-          let flat = <#ty as ::shape::Shape>::shape(options);
+          let flat = #flat;
           if options.is_serialize() {
             if #field_skip_serializing {
               // do nothing
             } else if #field_skip_serializing_if {
-              let optional = ::shape::Type::Or(vec![::shape::Type::Undefined, flat]); 
-              shape = ::shape::Type::And(vec![shape, optional]); 
+              let optional = ::shape::Type::or([::shape::Type::Undefined, flat]); 
+              shape = ::shape::Type::and([shape, optional]); 
             } else {
-              shape = ::shape::Type::And(vec![shape, flat]); 
+              shape = ::shape::Type::and([shape, flat]); 
             }
           } else {
             if #field_skip_deserializing {
               // do nothing
             } else if #field_has_default || #container_has_default {
-              let optional = ::shape::Type::Or(vec![::shape::Type::Undefined, flat]); 
-              shape = ::shape::Type::And(vec![shape, optional]); 
+              let optional = ::shape::Type::or([::shape::Type::Undefined, flat]); 
+              shape = ::shape::Type::and([shape, optional]); 
             } else {
-              shape = ::shape::Type::And(vec![shape, flat]); 
+              shape = ::shape::Type::and([shape, flat]); 
             }
           }
         }
@@ -689,23 +1032,23 @@ fn join_enum_fields(
           Some(content) => {
             let content = LitStr::new(content, variant.span());
             quote! {
-              ::shape::Type::Object(Object {
+              ::shape::Type::Object(::shape::Object {
                 properties: ::shape::indexmap::IndexMap::from([
                   (
-                    String::from(#tag),
+                    ::std::borrow::Cow::Borrowed(#tag),
                     ::shape::Property {
                       readonly: false,
                       optional: false,
-                      ty: ::shape::Type::Literal(::shape::Literal::String(String::from(#get_name)))
+                      ty: ::std::sync::Arc::new(::shape::Type::Literal(::shape::Literal::String(::std::borrow::Cow::Borrowed(#get_name))))
                     }
                   ),
 
                   (
-                    String::from(#content),
+                    ::std::borrow::Cow::Borrowed(#content),
                     ::shape::Property {
                       readonly: false,
                       optional: false,
-                      ty: #fields
+                      ty: ::std::sync::Arc::new(#fields)
                     }
                   )
                 ])
@@ -714,23 +1057,32 @@ fn join_enum_fields(
           }
           
           None => {
-            quote! {
-              ::shape::Type::And(vec![
-                ::shape::Type::Object(Object {
-                  properties: ::shape::indexmap::IndexMap::from([
-                    (
-                      String::from(#tag),
-                      ::shape::Property {
-                        readonly: false,
-                        optional: false,
-                        ty: ::shape::Type::Literal(::shape::Literal::String(String::from(#get_name)))
-                      }
-                    )
-                  ])
-                }),
-
-                #fields,
-              ])
+            let tag = quote! {
+              ::shape::Type::Object(::shape::Object {
+                properties: ::shape::indexmap::IndexMap::from([
+                  (
+                    ::std::borrow::Cow::Borrowed(#tag),
+                    ::shape::Property {
+                      readonly: false,
+                      optional: false,
+                      ty: ::std::sync::Arc::new(::shape::Type::Literal(::shape::Literal::String(::std::borrow::Cow::Borrowed(#get_name))))
+                    }
+                  )
+                ])
+              })
+            };
+
+            match &variant.fields {
+              // the content of a newtype variant is only known at runtime, Eg: a type parameter
+              syn::Fields::Unnamed(_) => quote! {
+                ::shape::internal::internally_tagged(#tag, { #fields }, options)
+              },
+              _ => quote! {
+                ::shape::Type::and([
+                  #tag,
+                  #fields,
+                ])
+              },
             }
           }
         }
@@ -741,11 +1093,11 @@ fn join_enum_fields(
           ::shape::Type::Object(::shape::Object {
             properties: ::shape::indexmap::IndexMap::from([
               (
-                String::from(#get_name),
+                ::std::borrow::Cow::Borrowed(#get_name),
                 ::shape::Property {
                   readonly: false,
                   optional: false,
-                  ty: #fields,
+                  ty: ::std::sync::Arc::new(#fields),
                 }
               )
             ])
@@ -756,6 +1108,32 @@ fn join_enum_fields(
   }
 }
 
+/// The type whose shape describes the field, the field type or the one set with `#[shape(with = "...")]`,
+/// none if the field has a custom type
+fn shape_type(field: &syn::Field) -> Result<Option<syn::Type>, darling::Error> {
+  let shape_field_attrs = attr::shape_field(&field.attrs)?;
+  if shape_field_attrs.custom.is_some() {
+    return Ok(None);
+  }
+  Ok(Some(shape_field_attrs.with.unwrap_or_else(|| field.ty.clone())))
+}
+
+/// The expression of the shape of the field
+fn field_shape(field: &syn::Field) -> Result<proc_macro2::TokenStream, darling::Error> {
+  let shape_field_attrs = attr::shape_field(&field.attrs)?;
+  if let Some(custom) = shape_field_attrs.custom {
+    let expr = custom.0;
+    return Ok(quote!{
+      ::shape::Type::Custom(::shape::Custom::new(#expr))
+    });
+  }
+
+  let ty = shape_field_attrs.with.unwrap_or_else(|| field.ty.clone());
+  Ok(quote!{
+    <#ty as ::shape::Shape>::shape(options)
+  })
+}
+
 // TODO: there must be a better way to do this
 fn is_option(ty: &syn::Type) -> bool {
   
@@ -0,0 +1,420 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote_spanned, ToTokens};
+use syn::{punctuated::Punctuated, spanned::Spanned, Token};
+
+use crate::attr::{self, ContainerAttrs, FieldAttrs, ShapeFieldAttrs, VariantAttrs, UNSUPPORTED};
+
+/// A warning spanned to an attribute, proc macros can't emit warnings on stable
+/// so it is emitted as the use of a deprecated item
+pub struct Warning {
+  span: Span,
+  message: String,
+}
+
+impl ToTokens for Warning {
+  fn to_tokens(&self, tokens: &mut TokenStream) {
+    let message = &self.message;
+    tokens.extend(quote_spanned! {self.span=>
+      const _: () = {
+        #[deprecated(note = #message)]
+        #[allow(non_camel_case_types)]
+        struct unsupported_serde_attribute;
+        let _ = unsupported_serde_attribute;
+      };
+    });
+  }
+}
+
+/// Check the serde attributes of a derived type before generating its shape
+///
+/// Combinations that serde rejects too are errors. The [`UNSUPPORTED`] attributes change
+/// the serialized format in a way the shape can't describe, so they are warnings unless the container
+/// has `#[shape(allow_unsupported)]`, in which case they are ignored
+pub fn lint(input: &syn::DeriveInput, container_attrs: &ContainerAttrs) -> Result<Vec<Warning>, darling::Error> {
+  let mut errors = darling::Error::accumulator();
+  let mut warnings = vec![];
+  let allow_unsupported = container_attrs.allow_unsupported.is_some();
+
+  if !allow_unsupported {
+    unsupported(&input.attrs, false, &mut warnings);
+  }
+
+  if container_attrs.content.is_some() && container_attrs.tag.is_none() {
+    errors.push(error(&input.attrs, "content", &input.ident, "#[serde(content = \"...\")] must be used with #[serde(tag = \"...\")]"));
+  }
+
+  if container_attrs.tag.is_some() && container_attrs.untagged.is_some() {
+    errors.push(error(&input.attrs, "untagged", &input.ident, "enum can not be both #[serde(tag = \"...\")] and #[serde(untagged)]"));
+  }
+
+  match &input.data {
+    syn::Data::Struct(data) => {
+      if container_attrs.transparent.is_some() {
+        let mut count = 0;
+        for field in &data.fields {
+          if errors.handle(attr::parse::<FieldAttrs>(&field.attrs)).is_some_and(|attrs| attrs.skip.is_none()) {
+            count += 1;
+          }
+        }
+
+        if count != 1 {
+          errors.push(error(&input.attrs, "transparent", &input.ident, "#[serde(transparent)] requires the struct to have exactly one field that is not skipped"));
+        }
+      }
+
+      if container_attrs.untagged.is_some() {
+        errors.push(error(&input.attrs, "untagged", &input.ident, "#[serde(untagged)] can only be used on enums"));
+      }
+
+      if container_attrs.tag.is_some() && !matches!(data.fields, syn::Fields::Named(_)) {
+        errors.push(error(&input.attrs, "tag", &input.ident, "#[serde(tag = \"...\")] can only be used on enums and structs with named fields"));
+      }
+
+      fields(&data.fields, allow_unsupported, &mut errors, &mut warnings);
+    },
+
+    syn::Data::Enum(data) => {
+      if container_attrs.transparent.is_some() {
+        errors.push(error(&input.attrs, "transparent", &input.ident, "#[serde(transparent)] is not allowed on an enum"));
+      }
+
+      for variant in &data.variants {
+        let Some(variant_attrs) = errors.handle(attr::parse::<VariantAttrs>(&variant.attrs)) else {
+          continue;
+        };
+
+        if variant_attrs.skip.is_some() {
+          continue;
+        }
+
+        if !allow_unsupported {
+          unsupported(&variant.attrs, false, &mut warnings);
+        }
+
+        let internally_tagged = container_attrs.tag.is_some()
+          && container_attrs.content.is_none()
+          && container_attrs.untagged.is_none()
+          && variant_attrs.untagged.is_none();
+
+        if let syn::Fields::Unnamed(unnamed) = &variant.fields {
+          if internally_tagged && unnamed.unnamed.len() != 1 {
+            errors.push(darling::Error::custom("#[serde(tag = \"...\")] cannot be used with tuple variants").with_span(&variant.ident));
+          }
+
+          if internally_tagged && unnamed.unnamed.len() == 1 {
+            newtype_content(&unnamed.unnamed[0], &mut errors);
+          }
+        }
+
+        fields(&variant.fields, allow_unsupported, &mut errors, &mut warnings);
+      }
+    },
+
+    syn::Data::Union(_) => {},
+  }
+
+  errors.finish_with(warnings)
+}
+
+fn fields(fields: &syn::Fields, allow_unsupported: bool, errors: &mut darling::error::Accumulator, warnings: &mut Vec<Warning>) {
+  for field in fields {
+    let Some(field_attrs) = errors.handle(attr::parse::<FieldAttrs>(&field.attrs)) else {
+      continue;
+    };
+
+    if field_attrs.skip.is_some() {
+      continue;
+    }
+
+    if let (syn::Fields::Unnamed(_), Some(())) = (fields, field_attrs.flatten) {
+      errors.push(error(&field.attrs, "flatten", field, "#[serde(flatten)] cannot be used on tuple structs or tuple variants"));
+    }
+
+    let shape_field_attrs = errors.handle(attr::shape_field(&field.attrs));
+    if let Some(ShapeFieldAttrs { with: Some(_), custom: Some(_) }) = &shape_field_attrs {
+      errors.push(error(&field.attrs, "type", field, "#[shape(type = ...)] cannot be used with #[shape(with = \"...\")]"));
+    }
+
+    if !allow_unsupported {
+      let overridden = shape_field_attrs.is_some_and(|attrs| attrs.with.is_some() || attrs.custom.is_some());
+      unsupported(&field.attrs, overridden, warnings);
+    }
+  }
+}
+
+/// serde fails to serialize the newtype variants of internally tagged enums that don't contain a struct, a map or a unit,
+/// the content of the variant is checked here when its type is known, otherwise its shape is `never`
+fn newtype_content(field: &syn::Field, errors: &mut darling::error::Accumulator) {
+  let Some(field_attrs) = errors.handle(attr::parse::<FieldAttrs>(&field.attrs)) else {
+    return;
+  };
+
+  let Some(shape_field_attrs) = errors.handle(attr::shape_field(&field.attrs)) else {
+    return;
+  };
+
+  // the values of a custom type are not known
+  if field_attrs.skip.is_some() || shape_field_attrs.custom.is_some() {
+    return;
+  }
+
+  let ty = shape_field_attrs.with.as_ref().unwrap_or(&field.ty);
+  if let Some(kind) = non_object(ty) {
+    errors.push(
+      darling::Error::custom(format!("#[serde(tag = \"...\")] cannot be used with newtype variants containing {kind}, serde fails to serialize them"))
+        .with_span(ty)
+    );
+  }
+}
+
+/// The kind of the value if the type is statically known to never be serialized as a map
+fn non_object(ty: &syn::Type) -> Option<&'static str> {
+  match ty {
+    syn::Type::Path(path) if path.qself.is_none() => {
+      let last = path.path.segments.last()?;
+      match last.ident.to_string().as_str() {
+        "bool" => Some("a boolean"),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "f32" | "f64" => Some("a number"),
+        "char" | "str" | "String" => Some("a string"),
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" if !last.arguments.is_none() => Some("a sequence"),
+        _ => None,
+      }
+    },
+    syn::Type::Tuple(tuple) if !tuple.elems.is_empty() => Some("a tuple"),
+    syn::Type::Array(_) | syn::Type::Slice(_) => Some("a sequence"),
+    syn::Type::Reference(reference) => non_object(&reference.elem),
+    syn::Type::Paren(paren) => non_object(&paren.elem),
+    syn::Type::Group(group) => non_object(&group.elem),
+    _ => None,
+  }
+}
+
+/// Push a warning for each one of the [`UNSUPPORTED`] keys in the serde attributes,
+/// `overridden` is true if the shape of the field is set with `#[shape(with = "...")]` or `#[shape(type = ...)]`
+fn unsupported(attrs: &[syn::Attribute], overridden: bool, warnings: &mut Vec<Warning>) {
+  for attr in attrs {
+    if !attr.path().is_ident("serde") {
+      continue;
+    }
+
+    let Ok(metas) = attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated) else {
+      continue;
+    };
+
+    for meta in metas {
+      let Some(key) = UNSUPPORTED.iter().find(|key| meta.path().is_ident(key)) else {
+        continue;
+      };
+
+      let message = match *key {
+        "with" | "serialize_with" | "deserialize_with" => {
+          if overridden {
+            continue;
+          }
+          format!("#[serde({key} = \"...\")] changes the format of the value, set a type with the same shape with #[shape(with = \"...\")]")
+        },
+        key => format!("#[serde({key})] is not supported by Shape"),
+      };
+
+      warnings.push(Warning {
+        span: meta.span(),
+        message: format!("{message}, or add #[shape(allow_unsupported)] to the container to ignore it"),
+      });
+    }
+  }
+}
+
+/// An error spanned to the `key` in the `#[serde(...)]` or `#[shape(...)]` attributes, or to `fallback` if it is not found
+fn error(attrs: &[syn::Attribute], key: &str, fallback: &impl quote::ToTokens, message: &str) -> darling::Error {
+  for attr in attrs {
+    if !attr.path().is_ident("serde") && !attr.path().is_ident("shape") {
+      continue;
+    }
+
+    let Ok(metas) = attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated) else {
+      continue;
+    };
+
+    if let Some(meta) = metas.iter().find(|meta| meta.path().is_ident(key)) {
+      return darling::Error::custom(message).with_span(meta);
+    }
+  }
+
+  darling::Error::custom(message).with_span(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn errors(input: syn::DeriveInput) -> Vec<String> {
+    let container_attrs = attr::parse::<ContainerAttrs>(&input.attrs).unwrap();
+    match lint(&input, &container_attrs) {
+      Ok(_) => vec![],
+      Err(e) => e.into_iter().map(|e| e.to_string()).collect(),
+    }
+  }
+
+  fn warnings(input: syn::DeriveInput) -> Vec<String> {
+    let container_attrs = attr::parse::<ContainerAttrs>(&input.attrs).unwrap();
+    lint(&input, &container_attrs).unwrap().into_iter().map(|warning| warning.message).collect()
+  }
+
+  #[test]
+  fn test_ignored() {
+    assert!(errors(syn::parse_quote! {
+      #[serde(deny_unknown_fields, expecting = "a user", crate = "other_serde")]
+      struct User<'a> {
+        #[serde(borrow)]
+        name: &'a str,
+      }
+    }).is_empty());
+  }
+
+  #[test]
+  fn test_unsupported() {
+    let input: syn::DeriveInput = syn::parse_quote! {
+      #[serde(field_identifier)]
+      enum Field {
+        #[serde(alias = "username")]
+        Name,
+        #[serde(with = "date")]
+        CreatedAt(Date),
+        UpdatedAt(
+          #[serde(with = "date")]
+          #[shape(with = "String")]
+          Date
+        ),
+      }
+    };
+
+    // unsupported attributes don't fail the build, the alias is supported
+    assert!(errors(input.clone()).is_empty());
+    let found = warnings(input);
+    assert_eq!(found.len(), 2, "{found:?}");
+    assert!(found[0].starts_with("#[serde(field_identifier)] is not supported"));
+    assert!(found[1].starts_with("#[serde(with = \"...\")] changes the format"));
+  }
+
+  #[test]
+  fn test_custom_type() {
+    let found = errors(syn::parse_quote! {
+      struct User {
+        #[serde(with = "date")]
+        #[shape(type = "Date")]
+        created_at: Date,
+        #[shape(type = Rfc3339, with = "String")]
+        updated_at: Date,
+      }
+    });
+
+    assert_eq!(found.len(), 1, "{found:?}");
+    assert!(found[0].starts_with("#[shape(type = ...)] cannot be used with #[shape(with = \"...\")]"));
+  }
+
+  #[test]
+  fn test_allow_unsupported() {
+    assert!(warnings(syn::parse_quote! {
+      #[shape(allow_unsupported)]
+      enum Event {
+        #[serde(alias = "new")]
+        Created { #[serde(deserialize_with = "id")] id: u64 },
+      }
+    }).is_empty());
+  }
+
+  #[test]
+  fn test_conflicts() {
+    let found = errors(syn::parse_quote! {
+      #[serde(transparent)]
+      #[shape(allow_unsupported)]
+      struct Pair(u8, #[serde(flatten)] Inner);
+    });
+
+    assert_eq!(found, vec![
+      "#[serde(transparent)] requires the struct to have exactly one field that is not skipped",
+      "#[serde(flatten)] cannot be used on tuple structs or tuple variants",
+    ]);
+
+    let found = errors(syn::parse_quote! {
+      #[serde(tag = "type")]
+      enum Message {
+        Newtype(Inner),
+        Tuple(u8, u8),
+        #[serde(untagged)]
+        Untagged(u8, u8),
+      }
+    });
+
+    assert_eq!(found, vec!["#[serde(tag = \"...\")] cannot be used with tuple variants"]);
+  }
+
+  #[test]
+  fn test_internally_tagged_tuple() {
+    let found = errors(syn::parse_quote! {
+      #[serde(tag = "type")]
+      enum Enum {
+        A,
+        C(u8, bool),
+        D { a: i32, b: String },
+      }
+    });
+
+    // serde can't write the tag in a sequence
+    assert_eq!(found, vec!["#[serde(tag = \"...\")] cannot be used with tuple variants"]);
+  }
+
+  #[test]
+  fn test_internally_tagged_newtype() {
+    let found = errors(syn::parse_quote! {
+      #[serde(tag = "type")]
+      enum Message<T> {
+        Number(u32),
+        Text(&'static str),
+        Pair((u8, u8)),
+        Items(Vec<u8>),
+        Unit(()),
+        Struct(Inner),
+        Generic(T),
+        #[serde(untagged)]
+        Untagged(u32),
+      }
+    });
+
+    assert_eq!(found, vec![
+      "#[serde(tag = \"...\")] cannot be used with newtype variants containing a number, serde fails to serialize them",
+      "#[serde(tag = \"...\")] cannot be used with newtype variants containing a string, serde fails to serialize them",
+      "#[serde(tag = \"...\")] cannot be used with newtype variants containing a tuple, serde fails to serialize them",
+      "#[serde(tag = \"...\")] cannot be used with newtype variants containing a sequence, serde fails to serialize them",
+    ]);
+  }
+
+  #[test]
+  fn test_internally_tagged_primitive() {
+    let found = errors(syn::parse_quote! {
+      #[serde(tag = "tag")]
+      enum InternallyTaggedEnum {
+        Unit,
+        NewType(i32),
+        Struct { field: String },
+      }
+    });
+
+    // `NewType(i32)` used to have the shape of a tagged number, but serde fails to serialize it
+    assert_eq!(found, vec![
+      "#[serde(tag = \"...\")] cannot be used with newtype variants containing a number, serde fails to serialize them",
+    ]);
+  }
+
+  #[test]
+  fn test_transparent_skipped() {
+    assert!(errors(syn::parse_quote! {
+      #[serde(transparent)]
+      struct Id {
+        value: u64,
+        #[serde(skip)]
+        cache: String,
+      }
+    }).is_empty());
+  }
+}
@@ -0,0 +1,104 @@
+use std::{borrow::Cow, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::{Array, Literal, Object, Property, Record, RecordKey, Tuple, Type};
+
+/// Constructors for hand written shapes, Eg: `Type::object().field("id", Type::Number).optional_field("tags", Type::array(Type::String))`
+impl Type {
+  pub fn object() -> Object {
+    Object { properties: IndexMap::new() }
+  }
+
+  pub fn array(item: impl Into<Type>) -> Type {
+    Type::Array(Array { item: Arc::new(item.into()) })
+  }
+
+  pub fn tuple(items: impl IntoIterator<Item = Type>) -> Type {
+    Type::Tuple(Tuple { items: items.into_iter().map(Arc::new).collect(), rest: None })
+  }
+
+  pub fn record(key: impl Into<RecordKey>, value: impl Into<Type>) -> Type {
+    Type::Record(Record { optional: false, readonly: false, key: key.into(), value: Arc::new(value.into()) })
+  }
+
+  pub fn literal(literal: impl Into<Literal>) -> Type {
+    Type::Literal(literal.into())
+  }
+
+  pub fn or(items: impl IntoIterator<Item = Type>) -> Type {
+    Type::Or(items.into_iter().map(Arc::new).collect())
+  }
+
+  pub fn and(items: impl IntoIterator<Item = Type>) -> Type {
+    Type::And(items.into_iter().map(Arc::new).collect())
+  }
+
+  /// `T | null`
+  pub fn or_null(self) -> Type {
+    Type::or([self, Type::Null])
+  }
+}
+
+impl Object {
+  pub fn field(self, key: impl Into<Cow<'static, str>>, ty: impl Into<Type>) -> Self {
+    self.property(key, Property { optional: false, readonly: false, ty: Arc::new(ty.into()) })
+  }
+
+  pub fn optional_field(self, key: impl Into<Cow<'static, str>>, ty: impl Into<Type>) -> Self {
+    self.property(key, Property { optional: true, readonly: false, ty: Arc::new(ty.into()) })
+  }
+
+  pub fn readonly_field(self, key: impl Into<Cow<'static, str>>, ty: impl Into<Type>) -> Self {
+    self.property(key, Property { optional: false, readonly: true, ty: Arc::new(ty.into()) })
+  }
+
+  pub fn property(mut self, key: impl Into<Cow<'static, str>>, property: Property) -> Self {
+    self.properties.insert(key.into(), property);
+    self
+  }
+}
+
+macro_rules! impl_from {
+  ($($node:ident)*) => {
+    $(
+      impl From<$node> for Type {
+        fn from(node: $node) -> Self {
+          Type::$node(node)
+        }
+      }
+    )*
+  };
+}
+
+impl_from!(Literal Tuple Array Object Record);
+
+impl From<&'static str> for Literal {
+  fn from(value: &'static str) -> Self {
+    Literal::String(Cow::Borrowed(value))
+  }
+}
+
+impl From<String> for Literal {
+  fn from(value: String) -> Self {
+    Literal::String(Cow::Owned(value))
+  }
+}
+
+impl From<Cow<'static, str>> for Literal {
+  fn from(value: Cow<'static, str>) -> Self {
+    Literal::String(value)
+  }
+}
+
+impl From<f64> for Literal {
+  fn from(value: f64) -> Self {
+    Literal::Number(value)
+  }
+}
+
+impl From<bool> for Literal {
+  fn from(value: bool) -> Self {
+    Literal::Boolean(value)
+  }
+}
@@ -0,0 +1,26 @@
+use std::{
+  any::TypeId,
+  collections::HashMap,
+  sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+use crate::{Shape, ShapeOptions, Type};
+
+type Cache = RwLock<HashMap<(TypeId, ShapeOptions), Arc<Type>>>;
+
+static CACHE: OnceLock<Cache> = OnceLock::new();
+
+/// The shape of `T`, computed once for each type and options and shared after that
+///
+/// Useful when the same shape is used many times, Eg: to validate every request body of a server
+pub fn shape_of<T: Shape + ?Sized + 'static>(options: &ShapeOptions) -> Arc<Type> {
+  let cache = CACHE.get_or_init(Default::default);
+  let key = (TypeId::of::<T>(), options.clone());
+  if let Some(ty) = cache.read().unwrap_or_else(PoisonError::into_inner).get(&key) {
+    return ty.clone();
+  }
+
+  // computed without the lock, the shape of `T` can use the cache for other types
+  let ty = Arc::new(T::shape(options));
+  cache.write().unwrap_or_else(PoisonError::into_inner).entry(key).or_insert(ty).clone()
+}
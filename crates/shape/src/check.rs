@@ -0,0 +1,868 @@
+use std::fmt;
+
+use serde::ser::{self, Impossible, Serialize};
+use serde_json::Value;
+
+use crate::is_assignable::{key_matches, number_key};
+use crate::{IsAsignable, Literal, Record, RecordKey, Shape, ShapeOptions, Type};
+
+/// Check that the JSON serialization of `value` is assignable to the shape of `T`,
+/// without building a [`serde_json::Value`]
+///
+/// The result is the same as [`IsAsignable::is_assignable`] with the JSON written by [`serde_json::to_writer`],
+/// that writes the map keys of `Some` as the inner key where [`serde_json::to_value`] rejects them
+pub fn check<T: Shape + Serialize + ?Sized>(value: &T, options: &ShapeOptions) -> Result<(), CheckError> {
+  check_type(value, &T::shape(options))
+}
+
+/// Like [`check`] with any type
+///
+/// A type with more than [`MAX_ALTERNATIVES`] alternatives is checked on a [`serde_json::Value`]
+pub fn check_type<T: Serialize + ?Sized>(value: &T, ty: &Type) -> Result<(), CheckError> {
+  if is_wide(ty) {
+    let value = serde_json::to_vec(value)
+      .and_then(|json| serde_json::from_slice::<Value>(&json))
+      .map_err(|error| CheckError::Serialize(error.to_string()))?;
+    return if ty.is_assignable(&value) { Ok(()) } else { Err(CheckError::Mismatch) };
+  }
+  let results = value.serialize(Checker { alts: conjunction(&[ty]) })?;
+  if results.into_iter().any(|ok| ok) {
+    Ok(())
+  } else {
+    Err(CheckError::Mismatch)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckError {
+  /// the value is not assignable to the type
+  Mismatch,
+  /// the value failed to serialize, Eg: a map with keys that are not strings
+  Serialize(String),
+}
+
+impl fmt::Display for CheckError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Mismatch => write!(f, "the serialized value is not assignable to the type"),
+      Self::Serialize(message) => write!(f, "{message}"),
+    }
+  }
+}
+
+impl std::error::Error for CheckError {}
+
+impl ser::Error for CheckError {
+  fn custom<T: fmt::Display>(message: T) -> Self {
+    Self::Serialize(message.to_string())
+  }
+}
+
+/// A type without unions or intersections that all the atoms must accept
+pub(crate) type Alt<'a> = Vec<&'a Type>;
+
+/// The most alternatives that are checked at the same time, intersections of unions expand to the
+/// product of their members, Eg: a struct that flattens many enums
+pub const MAX_ALTERNATIVES: usize = 1024;
+
+/// An upper bound of the alternatives of `ty` and of any value nested in it
+fn width(ty: &Type) -> usize {
+  match ty {
+    Type::Or(items) => items.iter().fold(0, |sum, item| sum.saturating_add(width(item))),
+    Type::And(items) => items.iter().fold(1, |product, item| product.saturating_mul(width(item))),
+    Type::Custom(custom) => custom.fallback().map_or(1, width).max(1),
+    Type::Array(array) => width(&array.item).max(1),
+    Type::Tuple(tuple) => tuple.items.iter().map(|item| &**item).chain(tuple.rest.as_deref()).map(width).fold(1, usize::max),
+    Type::Object(object) => object.properties.values().map(|property| width(&property.ty)).fold(1, usize::max),
+    Type::Record(record) => width(&record.value).max(1),
+    _ => 1,
+  }
+}
+
+/// Whether `ty` has too many alternatives to expand them
+pub(crate) fn is_wide(ty: &Type) -> bool {
+  width(ty) > MAX_ALTERNATIVES
+}
+
+/// The alternatives that are assignable to all the types, unions and intersections are
+/// expanded so a value is serialized once and checked against every alternative at the same time
+pub(crate) fn conjunction<'a>(types: &[&'a Type]) -> Vec<Alt<'a>> {
+  let mut alts = vec![vec![]];
+  for ty in types {
+    let options = match ty {
+      Type::Or(items) => items.iter().flat_map(|item| conjunction(&[item])).collect(),
+      Type::And(items) => conjunction(&items.iter().map(|item| &**item).collect::<Vec<_>>()),
+      // an alternative without atoms accepts every value
+      Type::Unknown => vec![vec![]],
+      // scalars are checked with the custom type itself, arrays and objects only with the fallback
+      Type::Custom(custom) => match custom.fallback() {
+        Some(fallback) => conjunction(&[fallback]).into_iter().map(|alt| [vec![*ty], alt].concat()).collect(),
+        None => vec![vec![*ty]],
+      },
+      ty => vec![vec![*ty]],
+    };
+    alts = alts.iter().flat_map(|alt| options.iter().map(move |option: &Alt<'a>| [alt.as_slice(), option].concat())).collect();
+  }
+  alts
+}
+
+/// Serializes a value into whether each alternative accepts it
+struct Checker<'a> {
+  alts: Vec<Alt<'a>>,
+}
+
+/// `value` is the JSON value for the custom types
+fn each(alts: &[Alt], value: impl Fn() -> Value, accepts: impl Fn(&Type) -> bool) -> Vec<bool> {
+  alts.iter().map(|alt| alt.iter().all(|atom| match atom {
+    Type::Custom(custom) => custom.is_assignable(&value()),
+    atom => accepts(atom),
+  })).collect()
+}
+
+pub(crate) fn null(alts: &[Alt]) -> Vec<bool> {
+  each(alts, || Value::Null, |atom| matches!(atom, Type::Null))
+}
+
+pub(crate) fn boolean(alts: &[Alt], value: bool) -> Vec<bool> {
+  each(alts, || Value::Bool(value), |atom| match atom {
+    Type::Boolean => true,
+    Type::Literal(Literal::Boolean(literal)) => *literal == value,
+    _ => false,
+  })
+}
+
+pub(crate) fn number(alts: &[Alt], value: f64) -> Vec<bool> {
+  // serde_json serializes NaN and infinities as null
+  if !value.is_finite() {
+    return null(alts);
+  }
+  each(alts, || Value::from(value), |atom| match atom {
+    Type::Number => true,
+    Type::Literal(Literal::Number(literal)) => *literal == value,
+    _ => false,
+  })
+}
+
+pub(crate) fn string(alts: &[Alt], value: &str) -> Vec<bool> {
+  each(alts, || Value::String(value.to_string()), |atom| match atom {
+    Type::String => true,
+    Type::Literal(Literal::String(literal)) => literal == value,
+    _ => false,
+  })
+}
+
+impl<'a> ser::Serializer for Checker<'a> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+  type SerializeSeq = Seq<'a>;
+  type SerializeTuple = Seq<'a>;
+  type SerializeTupleStruct = Seq<'a>;
+  type SerializeTupleVariant = Variant<'a, Seq<'a>>;
+  type SerializeMap = Map<'a>;
+  type SerializeStruct = Map<'a>;
+  type SerializeStructVariant = Variant<'a, Map<'a>>;
+
+  fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+    Ok(boolean(&self.alts, v))
+  }
+
+  fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v as f64))
+  }
+
+  fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+    Ok(number(&self.alts, v))
+  }
+
+  fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+    Ok(string(&self.alts, v.encode_utf8(&mut [0; 4])))
+  }
+
+  fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+    Ok(string(&self.alts, v))
+  }
+
+  // serde_json serializes bytes as an array of numbers
+  fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+    let mut seq = Seq::new(self.alts);
+    for byte in v {
+      seq.element(byte)?;
+    }
+    Ok(seq.finish())
+  }
+
+  fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+    Ok(null(&self.alts))
+  }
+
+  fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+    Ok(null(&self.alts))
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+    Ok(null(&self.alts))
+  }
+
+  fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+    Ok(string(&self.alts, variant))
+  }
+
+  fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T: ?Sized + Serialize>(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    value: &T,
+  ) -> Result<Self::Ok, Self::Error> {
+    let mut map = Map::new(self.alts);
+    map.entry(variant, value)?;
+    Ok(map.finish())
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+    Ok(Seq::new(self.alts))
+  }
+
+  fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+    Ok(Seq::new(self.alts))
+  }
+
+  fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+    Ok(Seq::new(self.alts))
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+    let mut outer = Map::new(self.alts);
+    let inner = Seq::new(outer.begin_entry(variant));
+    Ok(Variant { outer, inner })
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+    Ok(Map::new(self.alts))
+  }
+
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+    Ok(Map::new(self.alts))
+  }
+
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeStructVariant, Self::Error> {
+    let mut outer = Map::new(self.alts);
+    let inner = Map::new(outer.begin_entry(variant));
+    Ok(Variant { outer, inner })
+  }
+}
+
+/// Collect the results of the checks of a nested value by the alternative that requested them
+fn accepted(len: usize, owners: &[usize], results: &[bool]) -> Vec<bool> {
+  let mut accepted = vec![false; len];
+  for (owner, ok) in owners.iter().zip(results) {
+    accepted[*owner] |= ok;
+  }
+  accepted
+}
+
+/// A custom type whose fallback is in the same alternative
+fn has_fallback(atom: &Type) -> bool {
+  matches!(atom, Type::Custom(custom) if custom.fallback().is_some())
+}
+
+fn serialize_nested<T: ?Sized + Serialize>(alts: Vec<Alt<'_>>, value: &T) -> Result<Vec<bool>, CheckError> {
+  if alts.is_empty() {
+    return Ok(vec![]);
+  }
+  value.serialize(Checker { alts })
+}
+
+/// The alternatives of an array while its items are checked
+pub(crate) struct Seq<'a> {
+  alts: Vec<Alt<'a>>,
+  alive: Vec<bool>,
+  len: usize,
+  owners: Vec<usize>,
+}
+
+impl<'a> Seq<'a> {
+  pub(crate) fn new(alts: Vec<Alt<'a>>) -> Self {
+    let alive = alts.iter().map(|alt| alt.iter().all(|atom| matches!(atom, Type::Array(_) | Type::Tuple(_)) || has_fallback(atom))).collect();
+    Self { alts, alive, len: 0, owners: vec![] }
+  }
+
+  /// Whether any alternative still accepts the array
+  pub(crate) fn is_alive(&self) -> bool {
+    self.alive.iter().any(|alive| *alive)
+  }
+
+  /// The alternatives to check the next item with
+  pub(crate) fn begin_element(&mut self) -> Vec<Alt<'a>> {
+    let mut children = vec![];
+    self.owners.clear();
+    for (i, alt) in self.alts.iter().enumerate().filter(|(i, _)| self.alive[*i]) {
+      // the items after the items of a tuple without rest are not checked
+      let types = alt.iter().filter_map(|atom| match atom {
+        Type::Array(array) => Some(&*array.item),
+        Type::Tuple(tuple) => tuple.items.get(self.len).or(tuple.rest.as_ref()).map(|item| &**item),
+        _ => None,
+      }).collect::<Vec<_>>();
+
+      for child in conjunction(&types) {
+        children.push(child);
+        self.owners.push(i);
+      }
+    }
+    children
+  }
+
+  pub(crate) fn end_element(&mut self, results: Vec<bool>) {
+    let accepted = accepted(self.alts.len(), &self.owners, &results);
+    for (alive, accepted) in self.alive.iter_mut().zip(accepted) {
+      *alive &= accepted;
+    }
+    self.len += 1;
+  }
+
+  fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CheckError> {
+    let alts = self.begin_element();
+    let results = serialize_nested(alts, value)?;
+    self.end_element(results);
+    Ok(())
+  }
+
+  pub(crate) fn finish(self) -> Vec<bool> {
+    self.alts.iter().zip(self.alive).map(|(alt, alive)| {
+      alive && alt.iter().all(|atom| match atom {
+        Type::Tuple(tuple) => tuple.items.len() <= self.len,
+        _ => true,
+      })
+    }).collect()
+  }
+}
+
+impl ser::SerializeSeq for Seq<'_> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+    self.element(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Self::Error> {
+    Ok(self.finish())
+  }
+}
+
+impl ser::SerializeTuple for Seq<'_> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+    self.element(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Self::Error> {
+    Ok(self.finish())
+  }
+}
+
+impl ser::SerializeTupleStruct for Seq<'_> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+    self.element(value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Self::Error> {
+    Ok(self.finish())
+  }
+}
+
+/// A literal key of a record that must be present, Eg: `{ [key: "a"]: T }`
+struct RequiredKey<'a> {
+  key: &'a Literal,
+  value: &'a Type,
+  found: bool,
+}
+
+/// The alternatives of an object while its entries are checked
+pub(crate) struct Map<'a> {
+  alts: Vec<Alt<'a>>,
+  alive: Vec<bool>,
+  keys: Vec<String>,
+  required: Vec<Vec<RequiredKey<'a>>>,
+  /// the alternative that requested each check of the current entry, and the required key it checks
+  pending: Vec<(usize, Option<usize>)>,
+  key: Option<String>,
+}
+
+/// Whether the record can accept any object by the type of its keys
+fn record_keys_supported(key: &RecordKey) -> bool {
+  match key {
+    RecordKey::Type(key) => type_keys_supported(key),
+    _ => true,
+  }
+}
+
+fn type_keys_supported(key: &Type) -> bool {
+  match key {
+    Type::String | Type::Unknown | Type::Number | Type::And(_) => true,
+    Type::Literal(Literal::String(_) | Literal::Number(_)) => true,
+    Type::Or(keys) => keys.iter().all(|key| type_keys_supported(key)),
+    _ => false,
+  }
+}
+
+fn record_required_keys<'a>(key: &'a Type, value: &'a Type, out: &mut Vec<RequiredKey<'a>>) {
+  match key {
+    Type::Literal(literal) => out.push(RequiredKey { key: literal, value, found: false }),
+    Type::Or(keys) => keys.iter().for_each(|key| record_required_keys(key, value, out)),
+    _ => {},
+  }
+}
+
+/// The checks of the value of an entry of a record, false if the key is not accepted
+fn record_entry<'a>(record: &'a Record, key: &str, types: &mut Vec<&'a Type>) -> bool {
+  match &record.key {
+    RecordKey::Type(key_ty) if !record.optional => type_entry(key_ty, &record.value, key, types),
+    record_key => {
+      let accepted = key_matches(record_key, key);
+      if accepted {
+        types.push(&record.value);
+      }
+      accepted
+    },
+  }
+}
+
+fn type_entry<'a>(key_ty: &'a Type, value: &'a Type, key: &str, types: &mut Vec<&'a Type>) -> bool {
+  match key_ty {
+    Type::String | Type::Unknown => {
+      types.push(value);
+      true
+    },
+    Type::Number => {
+      types.push(value);
+      number_key(key).is_some()
+    },
+    Type::And(keys) => {
+      if keys.iter().all(|each| each.is_assignable(&Value::String(key.to_string()))) {
+        types.push(value);
+      }
+      true
+    },
+    Type::Or(keys) => keys.iter().all(|each| type_entry(each, value, key, types)),
+    // the literal keys are required keys
+    _ => true,
+  }
+}
+
+fn literal_key_matches(literal: &Literal, key: &str) -> bool {
+  match literal {
+    Literal::String(literal) => literal == key,
+    Literal::Number(literal) => number_key(key) == Some(*literal),
+    Literal::Boolean(_) => false,
+  }
+}
+
+impl<'a> Map<'a> {
+  pub(crate) fn new(alts: Vec<Alt<'a>>) -> Self {
+    let mut alive = Vec::with_capacity(alts.len());
+    let mut required = Vec::with_capacity(alts.len());
+    for alt in &alts {
+      let mut keys = vec![];
+      alive.push(alt.iter().all(|atom| match atom {
+        Type::Object(_) => true,
+        Type::Record(record) if record.optional => true,
+        custom if has_fallback(custom) => true,
+        Type::Record(record) => {
+          if let RecordKey::Type(key) = &record.key {
+            record_required_keys(key, &record.value, &mut keys);
+          }
+          record_keys_supported(&record.key)
+        },
+        _ => false,
+      }));
+      required.push(keys);
+    }
+
+    Self { alts, alive, keys: vec![], required, pending: vec![], key: None }
+  }
+
+  /// Whether any alternative still accepts the object
+  pub(crate) fn is_alive(&self) -> bool {
+    self.alive.iter().any(|alive| *alive)
+  }
+
+  /// The alternatives to check the value of the entry with
+  pub(crate) fn begin_entry(&mut self, key: &str) -> Vec<Alt<'a>> {
+    self.keys.push(key.to_string());
+    self.pending.clear();
+
+    let mut children = vec![];
+    for (i, alt) in self.alts.iter().enumerate() {
+      if !self.alive[i] {
+        continue;
+      }
+
+      let mut types = vec![];
+      let mut accepted = true;
+      for atom in alt {
+        match atom {
+          Type::Object(object) => {
+            if let Some(property) = object.properties.get(key) {
+              types.push(&*property.ty);
+            }
+          },
+          Type::Record(record) => accepted &= record_entry(record, key, &mut types),
+          _ => {},
+        }
+      }
+
+      if !accepted {
+        self.alive[i] = false;
+        continue;
+      }
+
+      for child in conjunction(&types) {
+        children.push(child);
+        self.pending.push((i, None));
+      }
+
+      for (j, required) in self.required[i].iter().enumerate() {
+        if literal_key_matches(required.key, key) {
+          for child in conjunction(&[required.value]) {
+            children.push(child);
+            self.pending.push((i, Some(j)));
+          }
+        }
+      }
+    }
+
+    children
+  }
+
+  pub(crate) fn end_entry(&mut self, results: Vec<bool>) {
+    let mut accepted = vec![false; self.alts.len()];
+    for ((i, required), ok) in self.pending.drain(..).zip(results) {
+      match required {
+        None => accepted[i] |= ok,
+        Some(j) => self.required[i][j].found |= ok,
+      }
+    }
+
+    for (alive, accepted) in self.alive.iter_mut().zip(accepted) {
+      *alive &= accepted;
+    }
+  }
+
+  fn entry<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), CheckError> {
+    let alts = self.begin_entry(key);
+    let results = serialize_nested(alts, value)?;
+    self.end_entry(results);
+    Ok(())
+  }
+
+  pub(crate) fn finish(self) -> Vec<bool> {
+    let keys = self.keys;
+    self.alts.iter().zip(self.alive).zip(self.required).map(|((alt, alive), required)| {
+      alive
+        && required.iter().all(|required| required.found)
+        && alt.iter().all(|atom| match atom {
+          Type::Object(object) => object.properties.iter().all(|(key, property)| property.optional || keys.iter().any(|k| k == key)),
+          _ => true,
+        })
+    }).collect()
+  }
+}
+
+impl ser::SerializeMap for Map<'_> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+    self.key = Some(key.serialize(MapKey)?);
+    Ok(())
+  }
+
+  fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+    let key = self.key.take().expect("serialize_value called before serialize_key");
+    self.entry(&key, value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Self::Error> {
+    Ok(self.finish())
+  }
+}
+
+impl ser::SerializeStruct for Map<'_> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+    self.entry(key, value)
+  }
+
+  fn end(self) -> Result<Self::Ok, Self::Error> {
+    Ok(self.finish())
+  }
+}
+
+/// `{ "Variant": content }` of an externally tagged enum
+struct Variant<'a, S> {
+  outer: Map<'a>,
+  inner: S,
+}
+
+impl ser::SerializeTupleVariant for Variant<'_, Seq<'_>> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+    self.inner.element(value)
+  }
+
+  fn end(mut self) -> Result<Self::Ok, Self::Error> {
+    self.outer.end_entry(self.inner.finish());
+    Ok(self.outer.finish())
+  }
+}
+
+impl ser::SerializeStructVariant for Variant<'_, Map<'_>> {
+  type Ok = Vec<bool>;
+  type Error = CheckError;
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+    self.inner.entry(key, value)
+  }
+
+  fn end(mut self) -> Result<Self::Ok, Self::Error> {
+    self.outer.end_entry(self.inner.finish());
+    Ok(self.outer.finish())
+  }
+}
+
+/// The keys of maps are strings in JSON, serde_json also accepts numbers and booleans
+struct MapKey;
+
+fn key_must_be_a_string() -> CheckError {
+  CheckError::Serialize(String::from("key must be a string"))
+}
+
+fn float_key_must_be_finite() -> CheckError {
+  CheckError::Serialize(String::from("float key must be finite (got NaN or +/-inf)"))
+}
+
+impl ser::Serializer for MapKey {
+  type Ok = String;
+  type Error = CheckError;
+  type SerializeSeq = Impossible<String, CheckError>;
+  type SerializeTuple = Impossible<String, CheckError>;
+  type SerializeTupleStruct = Impossible<String, CheckError>;
+  type SerializeTupleVariant = Impossible<String, CheckError>;
+  type SerializeMap = Impossible<String, CheckError>;
+  type SerializeStruct = Impossible<String, CheckError>;
+  type SerializeStructVariant = Impossible<String, CheckError>;
+
+  fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+    if !v.is_finite() {
+      return Err(float_key_must_be_finite());
+    }
+    serde_json::to_string(&v).map_err(|_| key_must_be_a_string())
+  }
+
+  fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+    if !v.is_finite() {
+      return Err(float_key_must_be_finite());
+    }
+    serde_json::to_string(&v).map_err(|_| key_must_be_a_string())
+  }
+
+  fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+    Ok(variant.to_string())
+  }
+
+  fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Self::Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T: ?Sized + Serialize>(
+    self,
+    _name: &'static str,
+    _index: u32,
+    _variant: &'static str,
+    _value: &T,
+  ) -> Result<Self::Ok, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _index: u32,
+    _variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _index: u32,
+    _variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeStructVariant, Self::Error> {
+    Err(key_must_be_a_string())
+  }
+}
@@ -0,0 +1,117 @@
+use std::{fmt, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{IsAsignable, Type};
+
+/// A type that is described by its name instead of its structure, Eg: a date serialized as a RFC 3339 string
+///
+/// Only the name is required, the targets that can't represent the type use the [`CustomType::fallback`]
+pub trait CustomType: Send + Sync {
+  /// The name of the type, custom types with the same name are the same type
+  fn name(&self) -> &str;
+
+  /// The TypeScript type, the name by default
+  fn typescript(&self) -> String {
+    self.name().to_string()
+  }
+
+  /// The JSON Schema of the values Eg: `{ "type": "string", "format": "date-time" }`
+  fn json_schema(&self) -> Option<Value> {
+    None
+  }
+
+  /// A TypeScript expression that checks the value `v` Eg: `v instanceof Date`,
+  /// by default the guard of the fallback or a call to a guard named after the type, Eg: `isDate(v)`
+  fn type_guard(&self, _v: &str) -> Option<String> {
+    None
+  }
+
+  /// The structure of the values, the value of a custom type without a fallback can't be checked
+  fn fallback(&self) -> Option<Type> {
+    None
+  }
+
+  /// Whether the value is of this type, by default whether it is assignable to the fallback
+  fn is_assignable(&self, value: &Value) -> bool {
+    self.fallback().is_some_and(|fallback| fallback.is_assignable(value))
+  }
+}
+
+/// A name only custom type Eg: `#[shape(type = "Date")]`
+impl CustomType for &'static str {
+  fn name(&self) -> &str {
+    self
+  }
+}
+
+impl CustomType for String {
+  fn name(&self) -> &str {
+    self
+  }
+}
+
+/// A [`CustomType`] inside a [`Type::Custom`]
+#[derive(Clone)]
+pub struct Custom {
+  inner: Arc<dyn CustomType>,
+  // kept to borrow it while a value is checked
+  fallback: Option<Arc<Type>>,
+}
+
+impl Custom {
+  pub fn new<T: CustomType + 'static>(custom: T) -> Self {
+    let fallback = custom.fallback().map(Arc::new);
+    Self { inner: Arc::new(custom), fallback }
+  }
+
+  pub fn name(&self) -> &str {
+    self.inner.name()
+  }
+
+  pub fn typescript(&self) -> String {
+    self.inner.typescript()
+  }
+
+  pub fn json_schema(&self) -> Option<Value> {
+    self.inner.json_schema()
+  }
+
+  pub fn type_guard(&self, v: &str) -> Option<String> {
+    self.inner.type_guard(v)
+  }
+
+  pub fn fallback(&self) -> Option<&Type> {
+    self.fallback.as_deref()
+  }
+}
+
+impl IsAsignable for Custom {
+  fn is_assignable(&self, v: &Value) -> bool {
+    self.inner.is_assignable(v)
+  }
+}
+
+impl PartialEq for Custom {
+  fn eq(&self, other: &Self) -> bool {
+    self.name() == other.name()
+  }
+}
+
+impl fmt::Debug for Custom {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Custom").field(&self.name()).finish()
+  }
+}
+
+impl From<&'static str> for Custom {
+  fn from(name: &'static str) -> Self {
+    Self::new(name)
+  }
+}
+
+impl From<String> for Custom {
+  fn from(name: String) -> Self {
+    Self::new(name)
+  }
+}
@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::layout::substitute;
+use crate::visit::{walk_type_fold, Fold, Pointer};
+use crate::{Shape, ShapeOptions, Type};
+
+/// A named, possibly generic, type declaration Eg: `type Page<T> = { items: Array<T> }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+  pub name: Cow<'static, str>,
+  pub params: Vec<String>,
+  pub ty: Type,
+}
+
+/// Two types that are declared with the same name, only the first one is declared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+  pub name: String,
+  /// the path of the declared type Eg: `my_crate::User`
+  pub first: &'static str,
+  pub second: &'static str,
+}
+
+/// A collection of named declarations, derived types are added once and referenced by name
+/// with [`Type::Apply`] from other types instead of being expanded for every use
+#[derive(Debug, Clone)]
+pub struct Declarations {
+  options: ShapeOptions,
+  declarations: IndexMap<Cow<'static, str>, Declaration>,
+  /// the path of the type that reserved each name
+  paths: HashMap<String, &'static str>,
+  collisions: Vec<Collision>,
+}
+
+impl Declarations {
+  pub fn new(options: ShapeOptions) -> Self {
+    Self {
+      options: ShapeOptions {
+        use_declarations: true,
+        ..options
+      },
+      declarations: IndexMap::new(),
+      paths: HashMap::new(),
+      collisions: vec![],
+    }
+  }
+
+  /// The options used to compute the declarations, always with `use_declarations` enabled
+  pub fn options(&self) -> &ShapeOptions {
+    &self.options
+  }
+
+  /// Declare `T` and all the types it references, and return the type to use to reference `T`
+  pub fn add<T: Shape + ?Sized>(&mut self) -> Type {
+    let options = self.options.clone();
+    T::declare(&options, self);
+    T::shape(&options)
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.declarations.contains_key(name)
+  }
+
+  /// Whether the type at `path` has to be declared as `name`, it is declared once
+  ///
+  /// The name of another type is reported in [`Declarations::collisions`]
+  pub fn reserve(&mut self, name: &str, path: &'static str) -> bool {
+    if let Some(&first) = self.paths.get(name) {
+      let collision = Collision { name: name.to_string(), first, second: path };
+      if first != path && !self.collisions.contains(&collision) {
+        self.collisions.push(collision);
+      }
+      return false;
+    }
+
+    if self.contains(name) {
+      return false;
+    }
+    self.paths.insert(name.to_string(), path);
+    true
+  }
+
+  /// The types that were not declared because their name is already declared by another type
+  pub fn collisions(&self) -> &[Collision] {
+    &self.collisions
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Declaration> {
+    self.declarations.get(name)
+  }
+
+  /// Insert a declaration, replacing any previous declaration with the same name
+  pub fn insert(&mut self, declaration: Declaration) {
+    self.declarations.insert(declaration.name.clone(), declaration);
+  }
+
+  pub fn len(&self) -> usize {
+    self.declarations.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.declarations.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Declaration> {
+    self.declarations.values()
+  }
+
+  /// The type with the references to these declarations replaced by their types, the recursive references are kept
+  pub fn expand(&self, ty: &Type) -> Type {
+    Expand { declarations: self, stack: vec![] }.fold_type(ty.clone(), &mut Pointer::new())
+  }
+}
+
+struct Expand<'a> {
+  declarations: &'a Declarations,
+  // the declarations being expanded
+  stack: Vec<String>,
+}
+
+impl Fold for Expand<'_> {
+  fn fold_type(&mut self, ty: Type, pointer: &mut Pointer) -> Type {
+    match ty {
+      Type::Apply(apply) if !self.stack.iter().any(|name| *name == apply.name) => match self.declarations.get(&apply.name) {
+        Some(declaration) => {
+          let args = apply.args.into_iter().map(|arg| Arc::new(self.fold_type(Arc::unwrap_or_clone(arg), pointer))).collect::<Vec<_>>();
+          let ty = substitute(&declaration.ty, &declaration.params, &args);
+          self.stack.push(apply.name.to_string());
+          let ty = self.fold_type(ty, pointer);
+          self.stack.pop();
+          ty
+        },
+        None => walk_type_fold(self, Type::Apply(apply), pointer),
+      },
+      ty => walk_type_fold(self, ty, pointer),
+    }
+  }
+}
+
+impl<'a> IntoIterator for &'a Declarations {
+  type Item = &'a Declaration;
+  type IntoIter = indexmap::map::Values<'a, Cow<'static, str>, Declaration>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.declarations.values()
+  }
+}
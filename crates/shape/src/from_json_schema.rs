@@ -0,0 +1,279 @@
+use std::{fmt, sync::Arc};
+
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+
+use crate::{Apply, Array, Declaration, Literal, Object, Property, Record, RecordKey, Tuple, Type};
+
+/// Parse a JSON Schema into the shape of the values it accepts
+///
+/// References to `#/$defs/Name` and `#/definitions/Name` are [`Type::Apply`]s, see [`parse_json_schema_declarations`].
+/// Validations that a shape can't describe Eg: `minLength` or `format` are ignored, so the shape can accept more values than the schema
+pub fn parse_json_schema(schema: &Value) -> Result<Type, JsonSchemaError> {
+  parse(schema, "")
+}
+
+/// Parse the `$defs` and `definitions` of a JSON Schema document
+pub fn parse_json_schema_declarations(schema: &Value) -> Result<Vec<Declaration>, JsonSchemaError> {
+  let mut declarations = vec![];
+  for keyword in ["$defs", "definitions"] {
+    let Some(definitions) = schema.get(keyword) else {
+      continue;
+    };
+    let Value::Object(definitions) = definitions else {
+      return Err(JsonSchemaError::new(format!("/{keyword}"), JsonSchemaErrorKind::Invalid(keyword.to_string())));
+    };
+    for (name, schema) in definitions {
+      let ty = parse(schema, &format!("/{keyword}/{}", escape(name)))?;
+      declarations.push(Declaration { name: name.clone().into(), params: vec![], ty });
+    }
+  }
+  Ok(declarations)
+}
+
+/// A JSON Schema that can't be parsed into a shape
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonSchemaError {
+  /// JSON pointer to the invalid schema Eg: `/properties/tags/items`
+  pub path: String,
+  pub kind: JsonSchemaErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonSchemaErrorKind {
+  /// a keyword with a value of the wrong type Eg: `"required": "id"`
+  Invalid(String),
+  /// a keyword that can't be represented as a shape Eg: `not`
+  Unsupported(String),
+  /// a `$ref` that doesn't point to a definition of the document
+  Reference(String),
+}
+
+impl JsonSchemaError {
+  fn new(path: String, kind: JsonSchemaErrorKind) -> Self {
+    Self { path, kind }
+  }
+}
+
+impl fmt::Display for JsonSchemaErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Invalid(keyword) => write!(f, "invalid value for `{keyword}`"),
+      Self::Unsupported(keyword) => write!(f, "`{keyword}` can't be represented as a shape"),
+      Self::Reference(reference) => write!(f, "only references to `#/$defs` and `#/definitions` are supported, found `{reference}`"),
+    }
+  }
+}
+
+impl fmt::Display for JsonSchemaError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", if self.path.is_empty() { "/" } else { &self.path }, self.kind)
+  }
+}
+
+impl std::error::Error for JsonSchemaError {}
+
+/// Keywords that change the accepted values in ways a shape can't describe
+const UNSUPPORTED: &[&str] = &["not", "if", "patternProperties", "dependentSchemas", "dependencies", "$dynamicRef", "$recursiveRef"];
+
+fn escape(segment: &str) -> String {
+  segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn invalid(path: &str, keyword: &str) -> JsonSchemaError {
+  JsonSchemaError::new(format!("{path}/{keyword}"), JsonSchemaErrorKind::Invalid(keyword.to_string()))
+}
+
+fn parse(schema: &Value, path: &str) -> Result<Type, JsonSchemaError> {
+  let schema = match schema {
+    Value::Bool(true) => return Ok(Type::Unknown),
+    Value::Bool(false) => return Ok(Type::Never),
+    Value::Object(schema) => schema,
+    _ => return Err(JsonSchemaError::new(path.to_string(), JsonSchemaErrorKind::Invalid(String::from("schema")))),
+  };
+
+  if let Some(keyword) = UNSUPPORTED.iter().find(|keyword| schema.contains_key(**keyword)) {
+    return Err(JsonSchemaError::new(format!("{path}/{keyword}"), JsonSchemaErrorKind::Unsupported(keyword.to_string())));
+  }
+
+  // every keyword narrows the values, the shape is the intersection of them
+  let mut items = vec![];
+
+  if let Some(reference) = schema.get("$ref") {
+    items.push(reference_type(reference, path)?);
+  }
+
+  if let Some(value) = schema.get("const") {
+    items.push(literal(value, &format!("{path}/const"))?);
+  } else if let Some(values) = schema.get("enum") {
+    let Value::Array(values) = values else {
+      return Err(invalid(path, "enum"));
+    };
+    let mut variants = values
+      .iter()
+      .enumerate()
+      .map(|(i, value)| literal(value, &format!("{path}/enum/{i}")))
+      .collect::<Result<Vec<_>, _>>()?;
+    items.push(if variants.len() == 1 { variants.remove(0) } else { Type::or(variants) });
+  } else {
+    // `enum` and `const` already narrow the values to some of the `type`
+    let names = match schema.get("type") {
+      Some(Value::String(name)) => vec![name.as_str()],
+      Some(Value::Array(names)) => names.iter().map(|name| name.as_str().ok_or_else(|| invalid(path, "type"))).collect::<Result<_, _>>()?,
+      Some(_) => return Err(invalid(path, "type")),
+      None if ["properties", "required", "additionalProperties", "propertyNames"].iter().any(|keyword| schema.contains_key(*keyword)) => vec!["object"],
+      None if ["items", "prefixItems"].iter().any(|keyword| schema.contains_key(*keyword)) => vec!["array"],
+      None => vec![],
+    };
+
+    let mut types = names.into_iter().map(|name| typed(name, schema, path)).collect::<Result<Vec<_>, _>>()?;
+    match types.len() {
+      0 => {},
+      1 => items.push(types.remove(0)),
+      _ => items.push(Type::or(types)),
+    }
+  }
+
+  for keyword in ["anyOf", "oneOf", "allOf"] {
+    let Some(schemas) = schema.get(keyword) else {
+      continue;
+    };
+    let Value::Array(schemas) = schemas else {
+      return Err(invalid(path, keyword));
+    };
+    let types = schemas
+      .iter()
+      .enumerate()
+      .map(|(i, schema)| parse(schema, &format!("{path}/{keyword}/{i}")))
+      .collect::<Result<Vec<_>, _>>()?;
+    items.push(if keyword == "allOf" { Type::and(types) } else { Type::or(types) });
+  }
+
+  let ty = match items.len() {
+    0 => Type::Unknown,
+    1 => items.remove(0),
+    _ => Type::and(items),
+  };
+
+  // OpenAPI 3.0 marks optional values with `nullable`
+  Ok(match schema.get("nullable") {
+    Some(Value::Bool(true)) => Type::or([ty, Type::Null]),
+    _ => ty,
+  })
+}
+
+fn reference_type(reference: &Value, path: &str) -> Result<Type, JsonSchemaError> {
+  let Value::String(reference) = reference else {
+    return Err(invalid(path, "$ref"));
+  };
+
+  let name = reference.strip_prefix("#/$defs/").or_else(|| reference.strip_prefix("#/definitions/"));
+  match name {
+    Some(name) if !name.contains('/') => Ok(Type::Apply(Apply { name: name.replace("~1", "/").replace("~0", "~").into(), args: vec![] })),
+    _ => Err(JsonSchemaError::new(format!("{path}/$ref"), JsonSchemaErrorKind::Reference(reference.clone()))),
+  }
+}
+
+fn literal(value: &Value, path: &str) -> Result<Type, JsonSchemaError> {
+  match value {
+    Value::Null => Ok(Type::Null),
+    Value::Bool(value) => Ok(Type::Literal(Literal::Boolean(*value))),
+    Value::Number(value) => Ok(Type::Literal(Literal::Number(value.as_f64().unwrap_or(f64::NAN)))),
+    Value::String(value) => Ok(Type::Literal(Literal::String(value.clone().into()))),
+    // shapes only have literals of scalars
+    Value::Array(_) | Value::Object(_) => Err(JsonSchemaError::new(path.to_string(), JsonSchemaErrorKind::Unsupported(String::from("const")))),
+  }
+}
+
+fn typed(name: &str, schema: &Map<String, Value>, path: &str) -> Result<Type, JsonSchemaError> {
+  match name {
+    "null" => Ok(Type::Null),
+    "boolean" => Ok(Type::Boolean),
+    "number" | "integer" => Ok(Type::Number),
+    "string" => Ok(Type::String),
+    "array" => array(schema, path),
+    "object" => object(schema, path),
+    _ => Err(invalid(path, "type")),
+  }
+}
+
+fn array(schema: &Map<String, Value>, path: &str) -> Result<Type, JsonSchemaError> {
+  // draft 2020-12 has `prefixItems` and `items`, older drafts `items` and `additionalItems`
+  let (prefix, rest, keyword) = match (schema.get("prefixItems"), schema.get("items")) {
+    (Some(Value::Array(prefix)), rest) => (prefix, rest, "prefixItems"),
+    (Some(_), _) => return Err(invalid(path, "prefixItems")),
+    (None, Some(Value::Array(prefix))) => (prefix, schema.get("additionalItems"), "items"),
+    (None, item) => {
+      let item = match item {
+        Some(item) => parse(item, &format!("{path}/items"))?,
+        None => Type::Unknown,
+      };
+      return Ok(Type::Array(Array { item: Arc::new(item) }));
+    },
+  };
+
+  let items = prefix
+    .iter()
+    .enumerate()
+    .map(|(i, schema)| parse(schema, &format!("{path}/{keyword}/{i}")).map(Arc::new))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let rest_keyword = if keyword == "items" { "additionalItems" } else { "items" };
+  let closed = schema.get("maxItems").and_then(Value::as_u64) == Some(items.len() as u64);
+  let rest = match rest {
+    _ if closed => None,
+    Some(Value::Bool(false)) => None,
+    Some(rest) => Some(Arc::new(parse(rest, &format!("{path}/{rest_keyword}"))?)),
+    None => Some(Arc::new(Type::Unknown)),
+  };
+
+  Ok(Type::Tuple(Tuple { items, rest }))
+}
+
+fn object(schema: &Map<String, Value>, path: &str) -> Result<Type, JsonSchemaError> {
+  let required = match schema.get("required") {
+    Some(Value::Array(required)) => required.iter().map(|key| key.as_str().ok_or_else(|| invalid(path, "required"))).collect::<Result<Vec<_>, _>>()?,
+    Some(_) => return Err(invalid(path, "required")),
+    None => vec![],
+  };
+
+  let properties = match schema.get("properties") {
+    Some(Value::Object(properties)) => {
+      let mut out = IndexMap::new();
+      for (key, schema) in properties {
+        let ty = parse(schema, &format!("{path}/properties/{}", escape(key)))?;
+        let readonly = schema.get("readOnly") == Some(&Value::Bool(true));
+        out.insert(key.clone().into(), Property { optional: !required.contains(&key.as_str()), readonly, ty: Arc::new(ty) });
+      }
+      Some(out)
+    },
+    Some(_) => return Err(invalid(path, "properties")),
+    None => None,
+  };
+
+  let key = match schema.get("propertyNames") {
+    Some(names) => RecordKey::coerce(parse(names, &format!("{path}/propertyNames"))?),
+    None => RecordKey::String,
+  };
+
+  let value = match schema.get("additionalProperties") {
+    Some(Value::Bool(false)) => None,
+    Some(Value::Bool(true)) => Some(Type::Unknown),
+    Some(value) => Some(parse(value, &format!("{path}/additionalProperties"))?),
+    // objects accept other properties by default, but a shape object doesn't list them either
+    None if properties.is_some() => None,
+    None => Some(Type::Unknown),
+  };
+
+  let record = value
+    .filter(|value| properties.is_none() || *value != Type::Unknown)
+    .map(|value| Type::Record(Record { optional: key.is_variants(), readonly: false, key, value: Arc::new(value) }));
+
+  let object = properties.map(|properties| Type::Object(Object { properties }));
+  Ok(match (object, record) {
+    (Some(object), Some(record)) => Type::and([object, record]),
+    (Some(object), None) => object,
+    (None, Some(record)) => record,
+    (None, None) => Type::Object(Object { properties: IndexMap::new() }),
+  })
+}
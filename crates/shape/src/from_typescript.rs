@@ -0,0 +1,457 @@
+use std::{fmt, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::{Apply, Array, Declaration, Literal, Object, Property, Record, RecordKey, Tuple, Type};
+
+/// Parse a TypeScript type expression, the subset of TypeScript that [`crate::ToTypescript`] writes
+///
+/// The names of other types are [`Type::Apply`]s Eg: `Page<User>`, `any` is [`Type::Unknown`]
+pub fn parse_typescript(source: &str) -> Result<Type, TypescriptError> {
+  let mut parser = Parser { source, offset: 0, params: vec![] };
+  let ty = parser.union()?;
+  parser.end()?;
+  Ok(ty)
+}
+
+/// Parse the type aliases and interfaces of a TypeScript module, Eg: the output of [`crate::ToTypescript`] for [`crate::Declarations`]
+pub fn parse_typescript_declarations(source: &str) -> Result<Vec<Declaration>, TypescriptError> {
+  let mut parser = Parser { source, offset: 0, params: vec![] };
+  let mut declarations = vec![];
+  loop {
+    parser.skip();
+    if parser.rest().is_empty() {
+      return Ok(declarations);
+    }
+    declarations.push(parser.declaration()?);
+  }
+}
+
+/// A TypeScript source that can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypescriptError {
+  /// the byte of the source where the error was found
+  pub offset: usize,
+  pub message: String,
+}
+
+impl fmt::Display for TypescriptError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at byte {}", self.message, self.offset)
+  }
+}
+
+impl std::error::Error for TypescriptError {}
+
+struct Parser<'a> {
+  source: &'a str,
+  offset: usize,
+  /// the generic parameters of the declaration being parsed
+  params: Vec<String>,
+}
+
+fn is_ident_start(c: char) -> bool {
+  c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident_continue(c: char) -> bool {
+  c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+impl Parser<'_> {
+  fn rest(&self) -> &str {
+    &self.source[self.offset..]
+  }
+
+  fn error<T>(&self, message: impl Into<String>) -> Result<T, TypescriptError> {
+    Err(TypescriptError { offset: self.offset, message: message.into() })
+  }
+
+  /// Skip whitespace and comments
+  fn skip(&mut self) {
+    loop {
+      let rest = self.rest();
+      let trimmed = rest.trim_start();
+      let comment = if trimmed.starts_with("//") {
+        trimmed.find('\n').unwrap_or(trimmed.len())
+      } else if trimmed.starts_with("/*") {
+        trimmed.find("*/").map_or(trimmed.len(), |end| end + 2)
+      } else {
+        0
+      };
+      let skipped = rest.len() - trimmed.len() + comment;
+      self.offset += skipped;
+      if comment == 0 {
+        return;
+      }
+    }
+  }
+
+  fn peek(&mut self) -> Option<char> {
+    self.skip();
+    self.rest().chars().next()
+  }
+
+  fn eat(&mut self, symbol: &str) -> bool {
+    self.skip();
+    if self.rest().starts_with(symbol) {
+      self.offset += symbol.len();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn expect(&mut self, symbol: &str) -> Result<(), TypescriptError> {
+    if self.eat(symbol) {
+      Ok(())
+    } else {
+      self.error(format!("expected `{symbol}`"))
+    }
+  }
+
+  /// `[]` after a type, a `[` alone starts a tuple
+  fn eat_brackets(&mut self) -> bool {
+    let start = self.offset;
+    if self.eat("[") && self.eat("]") {
+      return true;
+    }
+    self.offset = start;
+    false
+  }
+
+  fn end(&mut self) -> Result<(), TypescriptError> {
+    self.skip();
+    if self.rest().is_empty() {
+      Ok(())
+    } else {
+      self.error("expected the end of the type")
+    }
+  }
+
+  fn peek_ident(&mut self) -> Option<&str> {
+    self.skip();
+    let rest = self.rest();
+    if !rest.starts_with(is_ident_start) {
+      return None;
+    }
+    let end = rest.find(|c| !is_ident_continue(c)).unwrap_or(rest.len());
+    Some(&rest[..end])
+  }
+
+  fn ident(&mut self) -> Result<String, TypescriptError> {
+    match self.peek_ident() {
+      Some(ident) => {
+        let ident = ident.to_string();
+        self.offset += ident.len();
+        Ok(ident)
+      },
+      None => self.error("expected an identifier"),
+    }
+  }
+
+  fn eat_keyword(&mut self, keyword: &str) -> bool {
+    if self.peek_ident() == Some(keyword) {
+      self.offset += keyword.len();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn declaration(&mut self) -> Result<Declaration, TypescriptError> {
+    self.eat_keyword("export");
+    let interface = self.eat_keyword("interface");
+    if !interface && !self.eat_keyword("type") {
+      return self.error("expected a type alias or an interface");
+    }
+
+    let name = self.ident()?;
+    let mut params = vec![];
+    if self.eat("<") {
+      loop {
+        params.push(self.ident()?);
+        // constraints and defaults don't change the shape
+        if self.eat_keyword("extends") {
+          self.union()?;
+        }
+        if self.eat("=") {
+          self.union()?;
+        }
+        if !self.eat(",") {
+          break;
+        }
+      }
+      self.expect(">")?;
+    }
+    self.params = params;
+
+    let ty = if interface {
+      let mut items = vec![];
+      if self.eat_keyword("extends") {
+        loop {
+          items.push(self.postfix()?);
+          if !self.eat(",") {
+            break;
+          }
+        }
+      }
+      items.push(self.object()?);
+      if items.len() == 1 { items.remove(0) } else { Type::and(items) }
+    } else {
+      self.expect("=")?;
+      self.union()?
+    };
+    self.eat(";");
+
+    Ok(Declaration { name: name.into(), params: std::mem::take(&mut self.params), ty })
+  }
+
+  fn union(&mut self) -> Result<Type, TypescriptError> {
+    // a leading `|` is allowed in multiline unions
+    self.eat("|");
+    let mut items = vec![self.intersection()?];
+    while self.eat("|") {
+      items.push(self.intersection()?);
+    }
+    Ok(if items.len() == 1 { items.remove(0) } else { Type::or(items) })
+  }
+
+  fn intersection(&mut self) -> Result<Type, TypescriptError> {
+    self.eat("&");
+    let mut items = vec![self.postfix()?];
+    while self.eat("&") {
+      items.push(self.postfix()?);
+    }
+    Ok(if items.len() == 1 { items.remove(0) } else { Type::and(items) })
+  }
+
+  fn postfix(&mut self) -> Result<Type, TypescriptError> {
+    // readonly arrays and tuples have the same values
+    self.eat_keyword("readonly");
+    let mut ty = self.primary()?;
+    while self.eat_brackets() {
+      ty = Type::Array(Array { item: Arc::new(ty) });
+    }
+    Ok(ty)
+  }
+
+  fn primary(&mut self) -> Result<Type, TypescriptError> {
+    match self.peek() {
+      Some('(') => {
+        self.expect("(")?;
+        let ty = self.union()?;
+        self.expect(")")?;
+        Ok(ty)
+      },
+      Some('{') => self.object(),
+      Some('[') => self.tuple(),
+      Some('"' | '\'') => Ok(Type::Literal(Literal::String(self.string()?.into()))),
+      Some('-' | '0'..='9' | '.') => Ok(Type::Literal(Literal::Number(self.number()?))),
+      Some(c) if is_ident_start(c) => self.reference(),
+      Some(c) => self.error(format!("unexpected `{c}`")),
+      None => self.error("expected a type"),
+    }
+  }
+
+  fn reference(&mut self) -> Result<Type, TypescriptError> {
+    let start = self.offset;
+    let name = self.ident()?;
+    let ty = match name.as_str() {
+      "string" => Type::String,
+      "number" => Type::Number,
+      "boolean" => Type::Boolean,
+      "null" => Type::Null,
+      "undefined" | "void" => Type::Undefined,
+      "never" => Type::Never,
+      "unknown" | "any" => Type::Unknown,
+      "true" => Type::Literal(Literal::Boolean(true)),
+      "false" => Type::Literal(Literal::Boolean(false)),
+      "Uint8Array" => Type::Bytes,
+      "typeof" | "keyof" | "infer" | "unique" => {
+        self.offset = start;
+        return self.error(format!("`{name}` types are not supported"));
+      },
+      _ => {
+        let mut args = vec![];
+        if self.eat("<") {
+          loop {
+            args.push(Arc::new(self.union()?));
+            if !self.eat(",") {
+              break;
+            }
+          }
+          self.expect(">")?;
+        }
+
+        return match (name.as_str(), args.len()) {
+          ("Array" | "ReadonlyArray", 1) => Ok(Type::Array(Array { item: args.remove(0) })),
+          ("Record", 2) => {
+            let value = args.pop().unwrap();
+            let key = RecordKey::coerce(Arc::unwrap_or_clone(args.pop().unwrap()));
+            Ok(Type::Record(Record { optional: key.is_variants(), readonly: false, key, value }))
+          },
+          ("Array" | "ReadonlyArray" | "Record", _) => {
+            self.offset = start;
+            self.error(format!("wrong number of type arguments for `{name}`"))
+          },
+          _ if args.is_empty() && self.params.contains(&name) => Ok(Type::Param(name.into())),
+          _ => Ok(Type::Apply(Apply { name: name.into(), args })),
+        };
+      },
+    };
+    Ok(ty)
+  }
+
+  fn string(&mut self) -> Result<String, TypescriptError> {
+    self.skip();
+    let mut chars = self.rest().char_indices();
+    let Some((_, quote)) = chars.next() else {
+      return self.error("expected a string");
+    };
+
+    let mut out = String::new();
+    while let Some((i, c)) = chars.next() {
+      match c {
+        c if c == quote => {
+          self.offset += i + 1;
+          return Ok(out);
+        },
+        '\\' => match chars.next().map(|(_, c)| c) {
+          Some('n') => out.push('\n'),
+          Some('r') => out.push('\r'),
+          Some('t') => out.push('\t'),
+          Some('b') => out.push('\u{8}'),
+          Some('f') => out.push('\u{c}'),
+          Some('0') => out.push('\0'),
+          Some('u') => {
+            let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+              Some(c) => out.push(c),
+              None => return self.error("invalid unicode escape"),
+            }
+          },
+          Some(c) => out.push(c),
+          None => break,
+        },
+        c => out.push(c),
+      }
+    }
+    self.error("unterminated string")
+  }
+
+  fn number(&mut self) -> Result<f64, TypescriptError> {
+    self.skip();
+    let rest = self.rest();
+    let sign = usize::from(rest.starts_with('-'));
+    let end = rest[sign..]
+      .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '_')))
+      .map_or(rest.len(), |end| end + sign);
+    let text = rest[..end].replace('_', "");
+    match text.parse::<f64>() {
+      Ok(value) => {
+        self.offset += end;
+        Ok(value)
+      },
+      Err(_) => self.error("invalid number"),
+    }
+  }
+
+  fn key(&mut self) -> Result<String, TypescriptError> {
+    match self.peek() {
+      Some('"' | '\'') => self.string(),
+      Some('0'..='9') => Ok(self.number()?.to_string()),
+      _ => self.ident(),
+    }
+  }
+
+  fn object(&mut self) -> Result<Type, TypescriptError> {
+    self.expect("{")?;
+    let mut properties = IndexMap::new();
+    let mut index = None;
+    while !self.eat("}") {
+      if self.eat("[") {
+        let start = self.offset;
+        self.ident()?;
+        // `[key in K]` is a mapped type, `[key: K]` an index signature
+        let mapped = self.eat_keyword("in");
+        if !mapped {
+          self.expect(":")?;
+        }
+        let key = self.union()?;
+        self.expect("]")?;
+        let optional = self.eat("?");
+        self.expect(":")?;
+        let value = self.union()?;
+
+        let (optional, key) = match key {
+          Type::Never if mapped => (false, RecordKey::Unsupported(Arc::new(Type::Never))),
+          Type::Or(items) if mapped && is_boolean_keys(&items) => (false, RecordKey::Boolean),
+          key => (optional, RecordKey::coerce(key)),
+        };
+
+        if index.replace(Record { optional, readonly: false, key, value: Arc::new(value) }).is_some() {
+          self.offset = start;
+          return self.error("an object can only have one index signature");
+        }
+      } else {
+        let mut readonly = false;
+        if self.peek_ident() == Some("readonly") {
+          let start = self.offset;
+          self.eat_keyword("readonly");
+          readonly = !matches!(self.peek(), Some(':' | '?' | ';' | ',' | '}'));
+          if !readonly {
+            self.offset = start;
+          }
+        }
+
+        let key = self.key()?;
+        let optional = self.eat("?");
+        self.expect(":")?;
+        let ty = self.union()?;
+        properties.insert(key.into(), Property { optional, readonly, ty: Arc::new(ty) });
+      }
+
+      if !self.eat(";") && !self.eat(",") && self.peek() != Some('}') {
+        return self.error("expected `;`, `,` or `}`");
+      }
+    }
+
+    Ok(match index {
+      None => Type::Object(Object { properties }),
+      Some(record) if properties.is_empty() => Type::Record(record),
+      Some(record) => Type::and([Type::Object(Object { properties }), Type::Record(record)]),
+    })
+  }
+
+  fn tuple(&mut self) -> Result<Type, TypescriptError> {
+    self.expect("[")?;
+    let mut items = vec![];
+    let mut rest = None;
+    while !self.eat("]") {
+      if self.eat("...") {
+        let start = self.offset;
+        match self.postfix()? {
+          Type::Array(array) => rest = Some(array.item),
+          _ => {
+            self.offset = start;
+            return self.error("the rest of a tuple must be an array");
+          },
+        }
+      } else {
+        items.push(Arc::new(self.union()?));
+      }
+
+      if !self.eat(",") && self.peek() != Some(']') {
+        return self.error("expected `,` or `]`");
+      }
+    }
+    Ok(Type::Tuple(Tuple { items, rest }))
+  }
+}
+
+/// `"true" | "false"` are the keys of a map with boolean keys
+fn is_boolean_keys(items: &[Arc<Type>]) -> bool {
+  let key = |value: &str| Arc::new(Type::Literal(Literal::String(value.to_string().into())));
+  items.len() == 2 && items.contains(&key("true")) && items.contains(&key("false"))
+}
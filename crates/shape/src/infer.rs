@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use indexmap::{IndexMap, IndexSet};
+use serde_json::{Map, Value};
+
+use crate::{Array, Literal, Object, Property, Tuple, Type};
+
+/// How [`infer`] generalizes the values of the samples
+#[derive(Debug, Clone)]
+pub struct InferOptions {
+  /// strings are a union of literals if they have at most this many different values and some of them repeat, `0` disables it
+  pub max_literals: usize,
+  /// arrays with the same length in every sample and different types in each position are tuples
+  pub tuples: bool,
+  /// objects with a string property that has a different value for each set of properties are a tagged union
+  pub discriminators: bool,
+}
+
+impl Default for InferOptions {
+  fn default() -> Self {
+    Self {
+      max_literals: 8,
+      tuples: true,
+      discriminators: true,
+    }
+  }
+}
+
+/// The shape of the samples, Eg: the example payloads of an API without types
+///
+/// The properties missing in some samples are optional, and a value that is `null` in some samples is `T | null`
+pub fn infer<'a>(samples: impl IntoIterator<Item = &'a Value>, options: &InferOptions) -> Type {
+  let samples = samples.into_iter().collect::<Vec<_>>();
+  values(&samples, options)
+}
+
+/// The kinds of values, a union has them in order of appearance with `null` last
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+  Boolean,
+  Number,
+  String,
+  Array,
+  Object,
+}
+
+fn values(values: &[&Value], options: &InferOptions) -> Type {
+  let mut kinds = IndexMap::<Kind, Vec<&Value>>::new();
+  let mut null = false;
+  for value in values {
+    let kind = match value {
+      Value::Null => {
+        null = true;
+        continue;
+      },
+      Value::Bool(_) => Kind::Boolean,
+      Value::Number(_) => Kind::Number,
+      Value::String(_) => Kind::String,
+      Value::Array(_) => Kind::Array,
+      Value::Object(_) => Kind::Object,
+    };
+    kinds.entry(kind).or_default().push(value);
+  }
+
+  let mut items = kinds
+    .into_iter()
+    .map(|(kind, values)| match kind {
+      Kind::Boolean => Type::Boolean,
+      Kind::Number => Type::Number,
+      Kind::String => strings(values.iter().filter_map(|value| value.as_str()), options),
+      Kind::Array => arrays(&values.iter().filter_map(|value| value.as_array()).collect::<Vec<_>>(), options),
+      Kind::Object => objects(&values.iter().filter_map(|value| value.as_object()).collect::<Vec<_>>(), options),
+    })
+    .collect::<Vec<_>>();
+
+  if null {
+    items.push(Type::Null);
+  }
+
+  match items.len() {
+    0 => Type::Never,
+    1 => items.remove(0),
+    _ => Type::or(items),
+  }
+}
+
+fn strings<'a>(values: impl Iterator<Item = &'a str>, options: &InferOptions) -> Type {
+  let mut count = 0;
+  let mut distinct = IndexSet::new();
+  for value in values {
+    count += 1;
+    if distinct.insert(value) && distinct.len() > options.max_literals {
+      return Type::String;
+    }
+  }
+
+  // a value that is seen once is not evidence of an enum
+  if count == distinct.len() {
+    return Type::String;
+  }
+
+  let mut literals = distinct.into_iter().map(|value| Type::Literal(Literal::String(value.to_string().into()))).collect::<Vec<_>>();
+  match literals.len() {
+    1 => literals.remove(0),
+    _ => Type::or(literals),
+  }
+}
+
+fn arrays(arrays: &[&Vec<Value>], options: &InferOptions) -> Type {
+  let len = arrays[0].len();
+  if options.tuples && len > 0 && arrays.iter().all(|array| array.len() == len) {
+    let items = (0..len)
+      .map(|i| Arc::new(values(&arrays.iter().map(|array| &array[i]).collect::<Vec<_>>(), options)))
+      .collect::<Vec<_>>();
+    if items.iter().any(|item| *item != items[0]) {
+      return Type::Tuple(Tuple { items, rest: None });
+    }
+  }
+
+  let items = arrays.iter().flat_map(|array| array.iter()).collect::<Vec<_>>();
+  let item = match items.is_empty() {
+    // the samples don't tell the type of the items
+    true => Type::Unknown,
+    false => values(&items, options),
+  };
+  Type::Array(Array { item: Arc::new(item) })
+}
+
+fn objects(objects: &[&Map<String, Value>], options: &InferOptions) -> Type {
+  if options.discriminators {
+    if let Some(ty) = tagged(objects, options) {
+      return ty;
+    }
+  }
+
+  Type::Object(merge(objects, options))
+}
+
+/// The properties of all the objects, the properties missing in some of them are optional
+fn merge(objects: &[&Map<String, Value>], options: &InferOptions) -> Object {
+  let mut properties = IndexMap::<&str, Vec<&Value>>::new();
+  for object in objects {
+    for (key, value) in *object {
+      properties.entry(key).or_default().push(value);
+    }
+  }
+
+  let properties = properties
+    .into_iter()
+    .map(|(key, samples)| {
+      let optional = samples.len() < objects.len();
+      (key.to_string().into(), Property { optional, readonly: false, ty: Arc::new(values(&samples, options)) })
+    })
+    .collect();
+
+  Object { properties }
+}
+
+/// A union of objects discriminated by the first property that is a string in all the objects, with at least two
+/// and at most [`InferOptions::max_literals`] values, and different properties for some of its values, the tag goes first
+fn tagged(objects: &[&Map<String, Value>], options: &InferOptions) -> Option<Type> {
+  let tag = objects[0].keys().find(|key| {
+    let mut variants = IndexMap::<&str, IndexSet<&str>>::new();
+    for object in objects {
+      let Some(Value::String(value)) = object.get(*key) else {
+        return false;
+      };
+      variants.entry(value).or_default().extend(object.keys().map(String::as_str));
+    }
+
+    // like literals, some value must repeat
+    let first = &variants[0];
+    (2..=options.max_literals).contains(&variants.len()) && variants.len() < objects.len() && variants.values().any(|keys| keys != first)
+  })?;
+
+  let mut variants = IndexMap::<&str, Vec<&Map<String, Value>>>::new();
+  for object in objects {
+    variants.entry(object[tag].as_str()?).or_default().push(object);
+  }
+
+  let items = variants
+    .into_iter()
+    .map(|(value, objects)| {
+      let mut object = merge(&objects, options);
+      let (index, _, property) = object.properties.get_full_mut(tag.as_str())?;
+      property.ty = Arc::new(Type::Literal(Literal::String(value.to_string().into())));
+      object.properties.move_index(index, 0);
+      Some(Arc::new(Type::Object(object)))
+    })
+    .collect::<Option<_>>()?;
+
+  Some(Type::Or(items))
+}
@@ -1,6 +1,6 @@
 use serde_json::{json, Map, Value};
 
-use crate::{Array, Literal, Object, Record, Tuple, Type};
+use crate::{Array, Literal, Object, Record, RecordKey, Tuple, Type};
 
 pub trait IsAsignable {
   fn is_assignable(&self, v: &Value) -> bool;  
@@ -25,8 +25,9 @@ impl IsAsignable for Tuple {
     match v {
       Value::Array(items) => {
         for (i, t) in self.items.iter().enumerate() {
-          if !t.is_assignable(&items[i]) {
-            return false;
+          match items.get(i) {
+            Some(item) if t.is_assignable(item) => {},
+            _ => return false,
           }
         }
         
@@ -59,7 +60,7 @@ impl IsAsignable for Object {
     match v {
       Value::Object(map) => {
         self.properties.iter().all(|(key, prop)| {
-          let v = map.get(key);
+          let v = map.get(key.as_ref());
           match v {
             None => prop.optional,
             Some(v) => prop.ty.is_assignable(v),
@@ -75,33 +76,41 @@ impl IsAsignable for Record {
   fn is_assignable(&self, v: &Value) -> bool {
     match v {
       Value::Object(map) => {
-        // A partial struct is assignable to all structs
+        // the keys of an optional record are not required, but the present keys must be keys of the record
         if self.optional {
-          return true
+          return map.iter().all(|(k, v)| key_matches(&self.key, k) && self.value.is_assignable(v));
         }
 
+        let key = match &self.key {
+          RecordKey::Type(key) => key,
+          RecordKey::Unsupported(_) => return map.is_empty(),
+          key => return map.iter().all(|(k, v)| key_matches(key, k) && self.value.is_assignable(v)),
+        };
+
         fn match_key(key: &Type, ty: &Type, map: &Map<String, Value>) -> bool { 
           match key {
             Type::Null => false,
             Type::Undefined => false,
             Type::Boolean => false,
+            Type::Bytes => false,
             Type::Tuple(_) => false,
             Type::Array(_) => false,
             Type::Object(_) => false,
             Type::Record(_) => false,
             Type::Custom(_) => false,
+            Type::Param(_) => false,
+            Type::Apply(_) => false,
             Type::Never => false,
-            Type::String => {
+            Type::String | Type::Unknown => {
               map.iter().all(|(_, v)| {
                 ty.is_assignable(v)
               })
             },
             Type::Number => {
               map.iter().all(|(k, v)| {
-                use std::str::FromStr;
-                match f64::from_str(k) {
-                  Err(_) => false,
-                  Ok(_) => ty.is_assignable(v)
+                match number_key(k) {
+                  None => false,
+                  Some(_) => ty.is_assignable(v)
                 }
               })
             },
@@ -123,16 +132,15 @@ impl IsAsignable for Record {
                 })
               }
               Literal::Number(n) => {
-                use std::str::FromStr;
                 map.iter().any(|(k, v)| {
-                  f64::from_str(k) == Ok(*n) && ty.is_assignable(v)
+                  number_key(k) == Some(*n) && ty.is_assignable(v)
                 })
               }
             }
           }
         }
 
-        match_key(&self.key, &self.value, map)        
+        match_key(key, &self.value, map)
       },
       _ => false,
     }
@@ -143,11 +151,14 @@ impl IsAsignable for Type {
   fn is_assignable(&self, v: &Value) -> bool {
     match self {
       Type::String => v.is_string(),
+      // JSON has no byte strings
+      Type::Bytes => false,
       Type::Number => v.is_number(),
       Type::Boolean => v.is_boolean(),
       Type::Null => v.is_null(),
       Type::Undefined => false,
       Type::Never => false,
+      Type::Unknown => true,
       Type::Literal(literal) => literal.is_assignable(v),
       Type::Tuple(tuple) => tuple.is_assignable(v),
       Type::Array(array) => array.is_assignable(v),
@@ -155,7 +166,47 @@ impl IsAsignable for Type {
       Type::Record(record) => record.is_assignable(v),
       Type::And(types) => types.iter().all(|t| t.is_assignable(v)),
       Type::Or(types) => types.iter().any(|t| t.is_assignable(v)),
-      Type::Custom(_) => false,
+      Type::Custom(custom) => custom.is_assignable(v),
+      // declarations are not resolved here, use a fully inlined shape to check values
+      Type::Param(_) => false,
+      Type::Apply(_) => false,
     }
   }
-}
\ No newline at end of file
+}
+
+/// Whether serde_json writes a key of the record as `k`
+pub(crate) fn key_matches(key: &RecordKey, k: &str) -> bool {
+  match key {
+    RecordKey::String => true,
+    RecordKey::Number => number_key(k).is_some(),
+    RecordKey::Integer => is_integer(k),
+    RecordKey::Boolean => k == "true" || k == "false",
+    RecordKey::Type(ty) => type_key_matches(ty, k),
+    RecordKey::Unsupported(_) => false,
+  }
+}
+
+fn type_key_matches(ty: &Type, k: &str) -> bool {
+  match ty {
+    Type::Number => number_key(k).is_some(),
+    Type::Literal(Literal::Number(n)) => number_key(k) == Some(*n),
+    Type::Or(items) => items.iter().any(|item| type_key_matches(item, k)),
+    Type::And(items) => items.iter().all(|item| type_key_matches(item, k)),
+    ty => ty.is_assignable(&Value::String(k.to_string())),
+  }
+}
+
+/// The value of a key that is a finite JSON number, serde_json doesn't write or read `NaN`, `inf` or `+1` as number keys
+pub(crate) fn number_key(k: &str) -> Option<f64> {
+  // serde_json skips the whitespace around a number
+  if k.trim() != k {
+    return None;
+  }
+  serde_json::from_str::<serde_json::Number>(k).ok()?.as_f64().filter(|n| n.is_finite())
+}
+
+/// An integer as itoa writes it, serde_json rejects leading zeros and `+` when it parses the key
+fn is_integer(k: &str) -> bool {
+  let digits = k.strip_prefix('-').unwrap_or(k);
+  !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()) && (digits == "0" || !digits.starts_with('0'))
+}
@@ -0,0 +1,240 @@
+use std::{borrow::Cow, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::visit::{walk_record_fold, walk_type_fold, Fold, Pointer};
+use crate::{Literal, Object, Record, RecordKey, Type};
+
+/// The properties of a type that is always an object, intersections of objects are merged
+/// Eg: the tag and the fields of a variant of an internally tagged enum
+pub(crate) fn object(ty: &Type) -> Option<Object> {
+  match ty {
+    Type::Object(object) => Some(object.clone()),
+    Type::And(items) => {
+      let mut properties = IndexMap::new();
+      for item in items {
+        properties.extend(object(item)?.properties);
+      }
+      Some(Object { properties })
+    },
+    _ => None,
+  }
+}
+
+/// A union of objects that have a required string literal property, with a different value in each object
+/// Eg: an internally or adjacently tagged enum
+pub(crate) struct Discriminated {
+  pub tag: Cow<'static, str>,
+  /// the value of the tag and the object of each variant
+  pub variants: Vec<(Cow<'static, str>, Object)>,
+}
+
+pub(crate) fn discriminated(items: &[Arc<Type>]) -> Option<Discriminated> {
+  let objects = items.iter().map(|item| object(item)).collect::<Option<Vec<_>>>()?;
+  let first = objects.first()?;
+
+  'tags: for tag in first.properties.keys() {
+    let mut variants = Vec::with_capacity(objects.len());
+    for object in &objects {
+      let value = match object.properties.get(tag) {
+        Some(property) if !property.optional => match &*property.ty {
+          Type::Literal(Literal::String(value)) => value,
+          _ => continue 'tags,
+        },
+        _ => continue 'tags,
+      };
+
+      if variants.iter().any(|(prev, _)| prev == value) {
+        continue 'tags;
+      }
+
+      variants.push((value.clone(), object.clone()));
+    }
+
+    return Some(Discriminated { tag: tag.clone(), variants });
+  }
+
+  None
+}
+
+/// How the variants of an enum are represented, see <https://serde.rs/enum-representations.html>
+pub(crate) enum Layout {
+  /// a union with `null` or `undefined`, Eg: an `Option<T>`
+  Optional(Type),
+  /// `"Variant"` or `{ "Variant": content }`
+  External(Vec<Variant>),
+  /// `{ tag: "Variant", ...content }`, the content is an object without the tag
+  Internal { tag: Cow<'static, str>, variants: Vec<Variant> },
+  /// `{ tag: "Variant", content: content }`
+  Adjacent { tag: Cow<'static, str>, content: Cow<'static, str>, variants: Vec<Variant> },
+  Untagged(Vec<Arc<Type>>),
+}
+
+pub(crate) struct Variant {
+  pub name: Cow<'static, str>,
+  /// `None` for unit variants
+  pub content: Option<Arc<Type>>,
+}
+
+/// The layout of the members of a union
+///
+/// An internally tagged enum whose variants all have a single field with the same name
+/// is serialized in the same way as an adjacently tagged enum, it is detected as one
+/// if more than one variant has that field
+pub(crate) fn layout(items: &[Arc<Type>]) -> Layout {
+  let present = items.iter().filter(|item| !matches!(***item, Type::Null | Type::Undefined)).cloned().collect::<Vec<_>>();
+  if present.len() != items.len() && !present.is_empty() {
+    return match present.len() {
+      1 => Layout::Optional(Arc::unwrap_or_clone(present.into_iter().next().unwrap())),
+      _ => Layout::Optional(Type::Or(present)),
+    };
+  }
+
+  if let Some(discriminated) = discriminated(items) {
+    let tag = discriminated.tag;
+    let variants = discriminated.variants.into_iter().map(|(name, mut object)| {
+      object.properties.shift_remove(&tag);
+      (name, object)
+    }).collect::<Vec<_>>();
+
+    let contents = variants.iter().filter(|(_, object)| !object.properties.is_empty()).collect::<Vec<_>>();
+    let adjacent = contents.first().filter(|_| contents.len() > 1).and_then(|(_, first)| {
+      let key = first.properties.keys().next()?;
+      let same = contents.iter().all(|(_, object)| {
+        object.properties.len() == 1 && object.properties.get(key).is_some_and(|property| !property.optional)
+      });
+      same.then(|| key.clone())
+    });
+
+    return match adjacent {
+      Some(content) => Layout::Adjacent {
+        variants: variants.into_iter().map(|(name, mut object)| Variant {
+          name,
+          content: object.properties.shift_remove(&content).map(|property| property.ty),
+        }).collect(),
+        tag,
+        content,
+      },
+      None => Layout::Internal {
+        tag,
+        variants: variants.into_iter().map(|(name, object)| Variant {
+          name,
+          content: (!object.properties.is_empty()).then_some(Arc::new(Type::Object(object))),
+        }).collect(),
+      },
+    };
+  }
+
+  let mut variants: Vec<Variant> = Vec::with_capacity(items.len());
+  for item in items {
+    let variant = match &**item {
+      Type::Literal(Literal::String(name)) => Variant { name: name.clone(), content: None },
+      Type::Object(object) if object.properties.len() == 1 => {
+        let (name, property) = object.properties.first().unwrap();
+        if property.optional {
+          return Layout::Untagged(items.to_vec());
+        }
+        Variant { name: name.clone(), content: Some(property.ty.clone()) }
+      },
+      _ => return Layout::Untagged(items.to_vec()),
+    };
+
+    if variants.iter().any(|prev| prev.name == variant.name) {
+      return Layout::Untagged(items.to_vec());
+    }
+    variants.push(variant);
+  }
+
+  Layout::External(variants)
+}
+
+/// The type without the `undefined` members of its unions, and whether it had any
+pub(crate) fn strip_undefined(ty: &Type) -> (Type, bool) {
+  match ty {
+    Type::Undefined => (Type::Never, true),
+    Type::Or(items) => {
+      let kept = items.iter().filter(|item| ***item != Type::Undefined).cloned().collect::<Vec<_>>();
+      let had = kept.len() != items.len();
+      match kept.len() {
+        1 => (Arc::unwrap_or_clone(kept.into_iter().next().unwrap()), had),
+        _ => (Type::Or(kept), had),
+      }
+    },
+    ty => (ty.clone(), false),
+  }
+}
+
+/// The generic parameters referenced by a type, in order of appearance
+pub(crate) fn params(ty: &Type, out: &mut Vec<String>) {
+  ty.walk(|ty, _| match ty {
+    Type::Param(name) if !out.iter().any(|param| param == name) => out.push(name.to_string()),
+    _ => {},
+  });
+}
+
+/// The type with its generic parameters replaced by the arguments of an [`crate::Apply`]
+pub(crate) fn substitute(ty: &Type, params: &[String], args: &[Arc<Type>]) -> Type {
+  Substitute { params, args }.fold_type(ty.clone(), &mut Pointer::new())
+}
+
+struct Substitute<'a> {
+  params: &'a [String],
+  args: &'a [Arc<Type>],
+}
+
+impl Fold for Substitute<'_> {
+  fn fold_type(&mut self, ty: Type, pointer: &mut Pointer) -> Type {
+    match ty {
+      Type::Param(name) => match self.params.iter().position(|param| *param == name) {
+        Some(i) => self.args.get(i).map(|arg| Type::clone(arg)).unwrap_or(Type::Param(name)),
+        None => Type::Param(name),
+      },
+      ty => walk_type_fold(self, ty, pointer),
+    }
+  }
+
+  // a map with generic keys doesn't have all the unit variants of an enum argument
+  fn fold_record(&mut self, record: Record, pointer: &mut Pointer) -> Record {
+    let generic = matches!(&record.key, RecordKey::Type(key) if matches!(**key, Type::Param(_)));
+    let record = walk_record_fold(self, record, pointer);
+    Record { optional: record.optional || (generic && record.key.is_variants()), ..record }
+  }
+
+  // an argument can change the coercion of the key
+  fn fold_record_key(&mut self, key: RecordKey, pointer: &mut Pointer) -> RecordKey {
+    match key {
+      RecordKey::Type(key) => RecordKey::coerce(self.fold_type(Arc::unwrap_or_clone(key), pointer)),
+      key => key,
+    }
+  }
+}
+
+/// Eg: `created_at` and `created-at` are `createdAt`, used to name properties
+pub(crate) fn camel_case(value: &str) -> String {
+  let pascal = pascal_case(value);
+  let mut chars = pascal.chars();
+  match chars.next() {
+    Some(first) if first.is_ascii_digit() => format!("_{pascal}"),
+    Some(first) => first.to_lowercase().chain(chars).collect(),
+    None => String::from("_"),
+  }
+}
+
+/// Eg: `created_at` and `created-at` are `CreatedAt`, used to name nested types
+pub(crate) fn pascal_case(value: &str) -> String {
+  let mut out = String::with_capacity(value.len());
+  let mut upper = true;
+  for c in value.chars() {
+    if c.is_alphanumeric() {
+      if upper {
+        out.extend(c.to_uppercase());
+      } else {
+        out.push(c);
+      }
+      upper = false;
+    } else {
+      upper = true;
+    }
+  }
+  out
+}
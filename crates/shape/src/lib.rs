@@ -1,35 +1,132 @@
-pub use shape_macros::Shape;
+pub use shape_macros::{shape, Shape};
 
 mod to_typescript;
 pub use to_typescript::ToTypescript;
+mod to_type_guards;
+pub use to_type_guards::{ToTypeGuards, TYPE_GUARDS_PRELUDE};
+mod to_python;
+pub use to_python::{PythonStyle, ToPython};
+mod to_swift;
+pub use to_swift::ToSwift;
+mod to_kotlin;
+pub use to_kotlin::ToKotlin;
+mod to_graphql;
+pub use to_graphql::{GraphqlError, GraphqlErrorKind, ToGraphql};
+mod to_jtd;
+pub use to_jtd::{validate_jtd, JtdError, JtdErrorKind, JtdValidationError, ToJtd};
+mod to_rust;
+pub use to_rust::ToRust;
 mod is_assignable;
 pub use is_assignable::IsAsignable;
+mod check;
+pub use check::{check, check_type, CheckError, MAX_ALTERNATIVES};
+mod validate;
+pub use validate::{validate_ndjson, validate_reader, validate_slice, Ndjson, Validate, ValidateError, ValidateErrorKind, Validator};
+mod builder;
+mod custom;
+pub use custom::{Custom, CustomType};
+mod declarations;
+pub use declarations::{Collision, Declaration, Declarations};
+mod from_typescript;
+pub use from_typescript::{parse_typescript, parse_typescript_declarations, TypescriptError};
+mod from_json_schema;
+pub use from_json_schema::{parse_json_schema, parse_json_schema_declarations, JsonSchemaError, JsonSchemaErrorKind};
+mod subtype;
+mod infer;
+pub use infer::{infer, InferOptions};
+mod satisfiable;
+mod layout;
+mod cache;
+pub use cache::shape_of;
+pub mod visit;
+pub use visit::{Fold, Pointer, Visit, VisitMut};
+#[cfg(feature = "schemars")]
+pub mod schemars;
+#[cfg(feature = "specta")]
+pub mod specta;
+#[cfg(feature = "utoipa")]
+pub mod utoipa;
 pub use indexmap;
 
 use std::{
+  borrow::Cow,
   collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+  ffi::{CStr, CString},
+  marker::PhantomData,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
   rc::Rc,
   sync::Arc,
+  time::{Duration, SystemTime},
 };
 use indexmap::{IndexMap, IndexSet};
 
 /// The shape trait is derived in a type to generate a schema for the (de)serialization of that type
 pub trait Shape {
   fn shape(options: &ShapeOptions) -> Type;
+
+  /// The named, possibly generic, declaration of this type, if it has one
+  fn declaration(_options: &ShapeOptions) -> Option<Declaration> {
+    None
+  }
+
+  /// Add the declaration of this type and of all the types it references to `declarations`
+  fn declare(_options: &ShapeOptions, _declarations: &mut Declarations) {}
+
+  /// The keys of a map with keys of this type, see [`RecordKey::coerce`]
+  fn key(options: &ShapeOptions) -> RecordKey {
+    RecordKey::coerce(Self::shape(options))
+  }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ShapeOptionsKind {
   Serialize,
   Deserialize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ShapeOptions {
   pub kind: ShapeOptionsKind,
   pub option_is_optional: bool,
   pub option_add_undefined: bool,
   pub option_add_null: bool,
+  /// reference derived types by name with [`Type::Apply`] instead of inlining them, see [`Declarations`]
+  pub use_declarations: bool,
+  /// the serde format of the values
+  pub format: Format,
+}
+
+/// The serde formats with differences in how they encode the same types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Format {
+  #[default]
+  Json,
+  MessagePack,
+  Cbor,
+  Yaml,
+  Toml,
+}
+
+impl Format {
+  /// The value of [`serde::Serializer::is_human_readable`], Eg: an [`IpAddr`] is a string or a tuple of bytes
+  pub fn is_human_readable(&self) -> bool {
+    matches!(self, Self::Json | Self::Yaml | Self::Toml)
+  }
+
+  /// Whether byte strings are encoded as [`Type::Bytes`] instead of an array of numbers
+  pub fn has_bytes(&self) -> bool {
+    matches!(self, Self::MessagePack | Self::Cbor)
+  }
+
+  /// TOML fails to encode units and skips the fields that are `None`
+  pub fn has_null(&self) -> bool {
+    !matches!(self, Self::Toml)
+  }
+
+  /// Whether the keys of maps keep their type, JSON converts numbers to strings and TOML only has string keys
+  pub fn has_non_string_keys(&self) -> bool {
+    matches!(self, Self::MessagePack | Self::Cbor | Self::Yaml)
+  }
 }
 
 impl ShapeOptions {
@@ -40,6 +137,8 @@ impl ShapeOptions {
       option_is_optional: false,
       option_add_undefined: false,
       option_add_null: true,
+      use_declarations: false,
+      format: Format::Json,
     }
   }
 
@@ -49,6 +148,8 @@ impl ShapeOptions {
       option_is_optional: true, 
       option_add_undefined: true,
       option_add_null: true,
+      use_declarations: false,
+      format: Format::Json,
     }
   }
 
@@ -59,64 +160,173 @@ impl ShapeOptions {
   pub fn is_deserialize(&self) -> bool {
     matches!(self.kind, ShapeOptionsKind::Deserialize)
   }
+
+  /// The shape of a unit in the format, TOML can't encode it
+  pub fn unit(&self) -> Type {
+    if self.format.has_null() {
+      Type::Null
+    } else {
+      Type::Never
+    }
+  }
+
+  /// The shape of a value encoded with `serialize_bytes`, Eg: a field with `#[serde(with = "serde_bytes")]`
+  pub fn bytes(&self) -> Type {
+    let array = Type::Array(Array { item: Arc::new(Type::Number) });
+    if self.format.has_bytes() {
+      Type::Bytes
+    } else if self.is_deserialize() && self.format == Format::Json {
+      // serde_json deserializes bytes from strings too
+      Type::or([array, Type::String])
+    } else {
+      array
+    }
+  }
+
+  /// The keys of a map in the format, `key` is the key of serde_json
+  pub fn map_key(&self, key: RecordKey) -> RecordKey {
+    match key {
+      // binary formats and YAML encode any key
+      RecordKey::Unsupported(ty) if self.format.has_non_string_keys() => RecordKey::Type(ty),
+      RecordKey::Integer | RecordKey::Number | RecordKey::Boolean if self.format == Format::Toml => RecordKey::Unsupported(Arc::new(key.to_type())),
+      key => key,
+    }
+  }
 }
 
 /// This type tries to match the way JSON serialized Rust structs can be represented in typescript
+///
+/// The children of a node are shared with [`Arc`], cloning a type doesn't copy its subtrees
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
   Null,
   Undefined,
   Never,
+  /// any value, Eg: a `serde_json::Value`
+  Unknown,
   Boolean,
   Number,
   String,
+  /// a byte string of a binary format, see [`Format::has_bytes`]
+  Bytes,
   Literal(Literal),
   Tuple(Tuple),
   Array(Array),
   Object(Object),
   Record(Record),
-  And(Vec<Type>),
-  Or(Vec<Type>),
-  /// a way to declare a custom type Eg: #\[shape(type = "Date")\]
-  Custom(String),
+  And(Vec<Arc<Type>>),
+  Or(Vec<Arc<Type>>),
+  /// a type described by its name Eg: #\[shape(type = "Date")\], see [`CustomType`]
+  Custom(Custom),
+  /// a generic type parameter inside a [`Declaration`] Eg: `T`
+  Param(Cow<'static, str>),
+  /// a reference to a named declaration Eg: `Page<User>`
+  Apply(Apply),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Apply {
+  pub name: Cow<'static, str>,
+  pub args: Vec<Arc<Type>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Tuple {
-  pub items: Vec<Type>,
-  pub rest: Option<Box<Type>>,
+  pub items: Vec<Arc<Type>>,
+  pub rest: Option<Arc<Type>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Array {
-  pub item: Box<Type>,
+  pub item: Arc<Type>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Object {
-  pub properties: IndexMap<String, Property>,
+  pub properties: IndexMap<Cow<'static, str>, Property>,
 }
 
+/// `optional` records don't require their literal keys, Eg: a map with the unit variants of an enum as keys
 #[derive(Debug, Clone, PartialEq)]
 pub struct Record {
   pub optional: bool,
   pub readonly: bool,
-  pub key: Box<Type>,
-  pub value: Box<Type>,
+  pub key: RecordKey,
+  pub value: Arc<Type>,
+}
+
+/// The keys of a [`Record`], serde_json writes the keys of maps as strings
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordKey {
+  String,
+  /// a string that parses as a number Eg: `"1.5"`
+  Number,
+  /// the string of an integer Eg: `"-1"`
+  Integer,
+  /// `"true"` or `"false"`
+  Boolean,
+  /// the keys that are assignable to a type Eg: a union of string literals or a generic parameter
+  Type(Arc<Type>),
+  /// a key of this type fails to serialize Eg: a tuple, only empty maps can be serialized
+  Unsupported(Arc<Type>),
+}
+
+impl RecordKey {
+  /// The key of a map with keys of shape `ty`, serde_json writes newtypes, numbers, booleans
+  /// and unit variants as strings and fails to serialize other keys
+  pub fn coerce(ty: Type) -> Self {
+    match ty {
+      Type::String => Self::String,
+      Type::Number => Self::Number,
+      Type::Boolean => Self::Boolean,
+      Type::Literal(Literal::String(_)) | Type::Custom(_) | Type::Param(_) | Type::Apply(_) | Type::And(_) => Self::Type(Arc::new(ty)),
+      Type::Or(ref items) if items.iter().all(|item| matches!(&**item, Type::Literal(Literal::String(_)))) => Self::Type(Arc::new(ty)),
+      ty => Self::Unsupported(Arc::new(ty)),
+    }
+  }
+
+  /// The keys are the names of the unit variants of an enum, a map doesn't need all of them
+  pub fn is_variants(&self) -> bool {
+    match self {
+      Self::Type(ty) => match &**ty {
+        Type::Literal(Literal::String(_)) => true,
+        Type::Or(items) => !items.is_empty() && items.iter().all(|item| matches!(&**item, Type::Literal(Literal::String(_)))),
+        _ => false,
+      },
+      _ => false,
+    }
+  }
+
+  /// The type of the key before serde_json writes it as a string
+  pub fn to_type(&self) -> Type {
+    match self {
+      Self::String => Type::String,
+      Self::Number | Self::Integer => Type::Number,
+      Self::Boolean => Type::Boolean,
+      Self::Type(ty) | Self::Unsupported(ty) => (**ty).clone(),
+    }
+  }
+}
+
+/// The key of a map with keys of shape `ty`, see [`RecordKey::coerce`]
+impl From<Type> for RecordKey {
+  fn from(ty: Type) -> Self {
+    Self::coerce(ty)
+  }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Property {
   pub optional: bool,
   pub readonly: bool,
-  pub ty: Type,
+  pub ty: Arc<Type>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
   Boolean(bool),
   Number(f64),
-  String(String),
+  String(Cow<'static, str>),
 }
 
 macro_rules! impl_ty {
@@ -127,42 +337,150 @@ macro_rules! impl_ty {
       }
     }
   };
+
+  ($ty:ty, $value:expr, $key:expr) => {
+    impl Shape for $ty {
+      fn shape(_: &ShapeOptions) -> Type {
+        $value
+      }
+
+      fn key(_: &ShapeOptions) -> RecordKey {
+        $key
+      }
+    }
+  };
 }
 
 impl_ty!(String, Type::String);
 impl_ty!(str, Type::String);
-impl_ty!(i8, Type::Number);
-impl_ty!(i16, Type::Number);
-impl_ty!(i32, Type::Number);
-impl_ty!(i64, Type::Number);
-impl_ty!(i128, Type::Number);
-impl_ty!(isize, Type::Number);
-impl_ty!(u8, Type::Number);
-impl_ty!(u16, Type::Number);
-impl_ty!(u32, Type::Number);
-impl_ty!(u64, Type::Number);
-impl_ty!(u128, Type::Number);
-impl_ty!(usize, Type::Number);
+impl_ty!(char, Type::String, RecordKey::String);
+impl_ty!(i8, Type::Number, RecordKey::Integer);
+impl_ty!(i16, Type::Number, RecordKey::Integer);
+impl_ty!(i32, Type::Number, RecordKey::Integer);
+impl_ty!(i64, Type::Number, RecordKey::Integer);
+impl_ty!(i128, Type::Number, RecordKey::Integer);
+impl_ty!(isize, Type::Number, RecordKey::Integer);
+impl_ty!(u8, Type::Number, RecordKey::Integer);
+impl_ty!(u16, Type::Number, RecordKey::Integer);
+impl_ty!(u32, Type::Number, RecordKey::Integer);
+impl_ty!(u64, Type::Number, RecordKey::Integer);
+impl_ty!(u128, Type::Number, RecordKey::Integer);
+impl_ty!(usize, Type::Number, RecordKey::Integer);
 impl_ty!(f32, Type::Number);
 impl_ty!(f64, Type::Number);
-impl_ty!(bool, Type::Boolean);
-impl_ty!((), Type::Null);
+impl_ty!(bool, Type::Boolean, RecordKey::Boolean);
+
+impl Shape for () {
+  fn shape(options: &ShapeOptions) -> Type {
+    options.unit()
+  }
+}
+
+// serde serializes PhantomData as unit, and never requires T to be (de)serializable
+impl<T: ?Sized> Shape for PhantomData<T> {
+  fn shape(options: &ShapeOptions) -> Type {
+    options.unit()
+  }
+}
+
+impl Shape for CStr {
+  fn shape(options: &ShapeOptions) -> Type {
+    options.bytes()
+  }
+}
+
+impl Shape for CString {
+  fn shape(options: &ShapeOptions) -> Type {
+    options.bytes()
+  }
+}
+
+/// The externally tagged `V4` and `V6` variants of the compact encodings of addresses
+fn ip_variants(v4: Type, v6: Type) -> Type {
+  let variant = |name: &'static str, ty| Type::Object(Object {
+    properties: IndexMap::from([(Cow::Borrowed(name), Property { optional: false, readonly: false, ty: Arc::new(ty) })]),
+  });
+  Type::or([variant("V4", v4), variant("V6", v6)])
+}
+
+macro_rules! impl_addr {
+  ($ty:ty, $compact:expr) => {
+    // human readable formats encode addresses as strings
+    impl Shape for $ty {
+      fn shape(options: &ShapeOptions) -> Type {
+        if options.format.is_human_readable() {
+          Type::String
+        } else {
+          $compact(options)
+        }
+      }
+    }
+  };
+}
+
+impl_addr!(Ipv4Addr, <[u8; 4]>::shape);
+impl_addr!(Ipv6Addr, <[u8; 16]>::shape);
+impl_addr!(IpAddr, |options| ip_variants(Ipv4Addr::shape(options), Ipv6Addr::shape(options)));
+impl_addr!(SocketAddrV4, <(Ipv4Addr, u16)>::shape);
+impl_addr!(SocketAddrV6, <(Ipv6Addr, u16)>::shape);
+impl_addr!(SocketAddr, |options| ip_variants(SocketAddrV4::shape(options), SocketAddrV6::shape(options)));
+
+/// serde encodes durations as a struct in all formats, and also deserializes them from a tuple
+fn duration(options: &ShapeOptions, secs: &'static str, nanos: &'static str) -> Type {
+  let property = |ty| Property { optional: false, readonly: false, ty: Arc::new(ty) };
+  let object = Type::Object(Object {
+    properties: IndexMap::from([(Cow::Borrowed(secs), property(Type::Number)), (Cow::Borrowed(nanos), property(Type::Number))]),
+  });
+  if options.is_deserialize() {
+    Type::or([object, <(u64, u32)>::shape(options)])
+  } else {
+    object
+  }
+}
+
+impl Shape for Duration {
+  fn shape(options: &ShapeOptions) -> Type {
+    duration(options, "secs", "nanos")
+  }
+}
+
+impl Shape for SystemTime {
+  fn shape(options: &ShapeOptions) -> Type {
+    duration(options, "secs_since_epoch", "nanos_since_epoch")
+  }
+}
 
 impl<T: Shape + ?Sized> Shape for &T {
   fn shape(options: &ShapeOptions) -> Type {
     T::shape(options)
   }
+
+  fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+    T::declare(options, declarations)
+  }
+
+  fn key(options: &ShapeOptions) -> RecordKey {
+    T::key(options)
+  }
 }
 
 macro_rules! impl_inner {
   ($ty:ty, $inner:ident) => {
     impl<$inner> Shape for $ty
     where
-      $inner: Shape,
+      $inner: Shape + ?Sized,
     {
       fn shape(options: &ShapeOptions) -> Type {
         <$inner>::shape(options)
       }
+
+      fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+        <$inner>::declare(options, declarations)
+      }
+
+      fn key(options: &ShapeOptions) -> RecordKey {
+        <$inner>::key(options)
+      }
     }
   };
 }
@@ -170,16 +488,30 @@ macro_rules! impl_inner {
 impl<T: Shape> Shape for Option<T> {
   fn shape(options: &ShapeOptions) -> Type {
     let inner = T::shape(options);
+    // TOML skips the fields that are None
+    if !options.format.has_null() {
+      return Type::or([inner, Type::Undefined]);
+    }
+
     if options.option_add_null && options.option_add_undefined {
-      Type::Or(vec![ inner, Type::Null, Type::Undefined ])
+      Type::or([ inner, Type::Null, Type::Undefined ])
     } else if options.option_add_null {
-      Type::Or(vec![ inner, Type::Null ])
+      Type::or([ inner, Type::Null ])
     } else if options.option_add_undefined {
-      Type::Or(vec![inner, Type::Undefined ])
+      Type::or([inner, Type::Undefined ])
     } else {
       inner
     }
   }
+
+  fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+    T::declare(options, declarations)
+  }
+
+  // serde_json writes the keys of `Some` as the inner key, and fails on `None`
+  fn key(options: &ShapeOptions) -> RecordKey {
+    T::key(options)
+  }
 }
 
 // TODO: add generics for Alloc in nightly
@@ -193,9 +525,13 @@ macro_rules! impl_slice {
     {
       fn shape(options: &ShapeOptions) -> Type {
         Type::Array(Array {
-          item: Box::new(<$inner>::shape(options)),
+          item: Arc::new(<$inner>::shape(options)),
         })
       }
+
+      fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+        <$inner>::declare(options, declarations)
+      }
     }
   };
 }
@@ -207,18 +543,37 @@ impl_slice!(T, impl<T: Shape, H> Shape for HashSet<T, H>);
 impl_slice!(T, impl<T: Shape, H> Shape for IndexSet<T, H>);
 impl_slice!(T, impl<T: Shape> Shape for BTreeSet<T>);
 
+/// Whether the keys of a map with keys of type `K` are the unit variants of an enum, see [`RecordKey::is_variants`]
+///
+/// A reference to the declaration of `K` is resolved with the shape of `K` without declarations
+fn is_variants<K: Shape + ?Sized>(key: &RecordKey, options: &ShapeOptions) -> bool {
+  match key {
+    RecordKey::Type(ty) if matches!(**ty, Type::Apply(_)) => {
+      let options = ShapeOptions { use_declarations: false, ..options.clone() };
+      options.map_key(K::key(&options)).is_variants()
+    },
+    key => key.is_variants(),
+  }
+}
+
 macro_rules! impl_map {
   ($k:ty, $v:ty, $($tt:tt)*) => {
     $($tt)*
     {
       fn shape(options: &ShapeOptions) -> Type {
+        let key = options.map_key(<$k>::key(options));
         Type::Record(Record {
-          optional: false,
+          optional: is_variants::<$k>(&key, options),
           readonly: false,
-          key: Box::new(<$k>::shape(options)),
-          value: Box::new(<$v>::shape(options)),
+          key,
+          value: Arc::new(<$v>::shape(options)),
         })
       }
+
+      fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+        <$k>::declare(options, declarations);
+        <$v>::declare(options, declarations);
+      }
     }
   };
 }
@@ -234,11 +589,15 @@ macro_rules! impl_tuple {
       fn shape(options: &ShapeOptions) -> Type {
         Type::Tuple(Tuple {
           items: vec![
-            $(<$ty>::shape(options)),*
+            $(Arc::new(<$ty>::shape(options))),*
           ],
           rest: None,
         })
       }
+
+      fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+        $(<$ty>::declare(options, declarations);)*
+      }
     }
   }
 }
@@ -261,12 +620,81 @@ where
   T: Shape,
 {
   fn shape(options: &ShapeOptions) -> Type {
-    let inner = T::shape(options);
-    let mut items = Vec::with_capacity(N);
-    for _ in 0..N {
-      items.push(inner.clone());
+    // the items share the shape of `T`
+    let inner = Arc::new(T::shape(options));
+    Type::Tuple(Tuple { items: vec![inner; N], rest: None })
+  }
+
+  fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+    T::declare(options, declarations)
+  }
+}
+
+impl Shape for serde_json::Value {
+  fn shape(_: &ShapeOptions) -> Type {
+    Type::Unknown
+  }
+}
+
+impl Shape for serde_json::Map<String, serde_json::Value> {
+  fn shape(_: &ShapeOptions) -> Type {
+    Type::Record(Record {
+      optional: false,
+      readonly: false,
+      key: RecordKey::String,
+      value: Arc::new(Type::Unknown),
+    })
+  }
+}
+
+impl_ty!(serde_json::Number, Type::Number);
+// the raw JSON is written as is, it can be any value
+impl_ty!(serde_json::value::RawValue, Type::Unknown);
+
+#[doc(hidden)]
+pub mod internal {
+  use std::sync::Arc;
+
+  use crate::{ShapeOptions, ShapeOptionsKind, Type};
+
+  /// The shape of a newtype variant of an internally tagged enum, `tag` is the object with the tag property
+  ///
+  /// serde fails to serialize the variant when its content is not a struct, a map or a unit,
+  /// the shape of those contents is [`Type::Never`]
+  pub fn internally_tagged(tag: Type, content: Type, options: &ShapeOptions) -> Type {
+    match content {
+      // a unit is serialized as the tag alone
+      Type::Null => tag,
+      Type::Or(items) => {
+        // a union with null is an `Option`, serde fails to serialize both `None` and `Some`,
+        // and the content that is left after the tag is never deserialized as `None`
+        let is_option = items.iter().any(|item| matches!(**item, Type::Null | Type::Undefined));
+        if is_option && options.kind == ShapeOptionsKind::Serialize {
+          return Type::Never;
+        }
+
+        let items = items.into_iter()
+          .filter(|item| !matches!(**item, Type::Null | Type::Undefined))
+          .map(|item| internally_tagged(tag.clone(), Arc::unwrap_or_clone(item), options))
+          .filter(|item| *item != Type::Never)
+          .map(Arc::new)
+          .collect::<Vec<_>>();
+
+        if items.is_empty() {
+          Type::Never
+        } else {
+          Type::Or(items)
+        }
+      },
+      content => {
+        let ty = Type::and([tag, content]);
+        if ty.is_satisfiable() {
+          ty
+        } else {
+          Type::Never
+        }
+      }
     }
-    Type::Tuple(Tuple { items, rest: None })
   }
 }
 
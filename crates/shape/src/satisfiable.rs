@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use crate::{Literal, Type};
+
+// the kinds of values a type can describe, as a bit set
+const NULL: u8 = 1;
+const UNDEFINED: u8 = 1 << 1;
+const BOOLEAN: u8 = 1 << 2;
+const NUMBER: u8 = 1 << 3;
+const STRING: u8 = 1 << 4;
+const ARRAY: u8 = 1 << 5;
+const OBJECT: u8 = 1 << 6;
+const BYTES: u8 = 1 << 7;
+const ANY: u8 = NULL | UNDEFINED | BOOLEAN | NUMBER | STRING | ARRAY | OBJECT | BYTES;
+
+impl Type {
+  /// Returns false if no value can be assigned to this type, Eg: `{ type: "A" } & number`
+  ///
+  /// The check is conservative, it can return true for some types that have no values,
+  /// [`Type::Param`], [`Type::Apply`] and [`Type::Custom`] without a fallback are always considered satisfiable
+  pub fn is_satisfiable(&self) -> bool {
+    match self {
+      Type::Never => false,
+      Type::Custom(custom) => match custom.fallback() {
+        Some(fallback) => fallback.is_satisfiable(),
+        None => true,
+      },
+      Type::Tuple(tuple) => tuple.items.iter().all(|item| item.is_satisfiable()),
+      Type::Object(object) => object.properties.values().all(|property| property.optional || property.ty.is_satisfiable()),
+      Type::Or(items) => items.iter().any(|item| item.is_satisfiable()),
+      Type::And(items) => and_is_satisfiable(items),
+      _ => true,
+    }
+  }
+}
+
+fn kinds(ty: &Type) -> u8 {
+  match ty {
+    Type::Null => NULL,
+    Type::Undefined => UNDEFINED,
+    Type::Never => 0,
+    Type::Boolean | Type::Literal(Literal::Boolean(_)) => BOOLEAN,
+    Type::Number | Type::Literal(Literal::Number(_)) => NUMBER,
+    Type::String | Type::Literal(Literal::String(_)) => STRING,
+    Type::Bytes => BYTES,
+    Type::Tuple(_) | Type::Array(_) => ARRAY,
+    Type::Object(_) | Type::Record(_) => OBJECT,
+    Type::And(items) => items.iter().fold(ANY, |kinds, item| kinds & self::kinds(item)),
+    Type::Or(items) => items.iter().fold(0, |kinds, item| kinds | self::kinds(item)),
+    Type::Custom(custom) => custom.fallback().map_or(ANY, self::kinds),
+    Type::Unknown | Type::Param(_) | Type::Apply(_) => ANY,
+  }
+}
+
+fn and_is_satisfiable(items: &[Arc<Type>]) -> bool {
+  if !items.iter().all(|item| item.is_satisfiable()) {
+    return false;
+  }
+
+  if items.iter().fold(ANY, |kinds, item| kinds & self::kinds(item)) == 0 {
+    return false;
+  }
+
+  // nested intersections are the same as a flat one
+  let mut flat = vec![];
+  flatten(items, &mut flat);
+
+  let mut literal = None;
+  for item in &flat {
+    if let Type::Literal(l) = item {
+      match literal {
+        None => literal = Some(l),
+        Some(prev) if prev != l => return false,
+        Some(_) => {},
+      }
+    }
+  }
+
+  // a property that is in more than one object must satisfy all of them
+  let objects = flat.iter().filter_map(|item| match item {
+    Type::Object(object) => Some(object),
+    _ => None,
+  }).collect::<Vec<_>>();
+
+  for (i, object) in objects.iter().enumerate() {
+    for (name, property) in &object.properties {
+      let others = objects[i + 1..].iter().filter_map(|other| other.properties.get(name)).collect::<Vec<_>>();
+      if others.is_empty() {
+        continue;
+      }
+
+      let required = !property.optional || others.iter().any(|other| !other.optional);
+      let mut tys = vec![property.ty.clone()];
+      tys.extend(others.iter().map(|other| other.ty.clone()));
+
+      if required && !and_is_satisfiable(&tys) {
+        return false;
+      }
+    }
+  }
+
+  true
+}
+
+fn flatten<'a>(items: &'a [Arc<Type>], flat: &mut Vec<&'a Type>) {
+  for item in items {
+    match &**item {
+      Type::And(items) => flatten(items, flat),
+      item => flat.push(item),
+    }
+  }
+}
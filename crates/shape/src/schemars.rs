@@ -0,0 +1,234 @@
+//! Adapters between [`Shape`] and [`::schemars::JsonSchema`], enabled with the `schemars` feature
+//!
+//! ```ignore
+//! #[derive(Shape)]
+//! struct User {
+//!   #[shape(with = "shape::schemars::Schemars<Address>")]
+//!   address: Address,
+//! }
+//!
+//! #[derive(JsonSchema)]
+//! struct Order {
+//!   #[schemars(with = "shape::schemars::Shaped<User>")]
+//!   user: User,
+//! }
+//! ```
+
+use std::{borrow::Cow, marker::PhantomData};
+
+use ::schemars::{generate::SchemaSettings, JsonSchema, Schema, SchemaGenerator};
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+
+use crate::{
+  layout, parse_json_schema, parse_json_schema_declarations, Declarations, JsonSchemaError, Literal, Object, RecordKey, Shape, ShapeOptions,
+  ToTypescript, Type,
+};
+
+/// The shape of a type that implements [`JsonSchema`], Eg: `#[shape(with = "shape::schemars::Schemars<Address>")]`
+///
+/// The named schemas are declarations, a schema that can't be parsed is [`Type::Unknown`], see [`parse_schemars`]
+pub struct Schemars<T: ?Sized>(PhantomData<T>);
+
+impl<T: JsonSchema + ?Sized> Shape for Schemars<T> {
+  fn shape(options: &ShapeOptions) -> Type {
+    match generate::<T>(options) {
+      Ok((ty, _)) if options.use_declarations => ty,
+      Ok((ty, declarations)) => declarations.expand(&ty),
+      Err(_) => Type::Unknown,
+    }
+  }
+
+  fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+    if let Ok((_, schemas)) = generate::<T>(options) {
+      for declaration in &schemas {
+        if !declarations.contains(&declaration.name) {
+          declarations.insert(declaration.clone());
+        }
+      }
+    }
+  }
+}
+
+/// The shape of the JSON Schema of `T`, generated for the serialization or the deserialization like the options
+pub fn parse_schemars<T: JsonSchema + ?Sized>(options: &ShapeOptions) -> Result<Type, JsonSchemaError> {
+  let (ty, declarations) = generate::<T>(options)?;
+  Ok(declarations.expand(&ty))
+}
+
+/// The type that references `T` and the declarations of the named schemas
+fn generate<T: JsonSchema + ?Sized>(options: &ShapeOptions) -> Result<(Type, Declarations), JsonSchemaError> {
+  let mut settings = SchemaSettings::draft2020_12();
+  settings.contract = match options.is_deserialize() {
+    true => ::schemars::generate::Contract::Deserialize,
+    false => ::schemars::generate::Contract::Serialize,
+  };
+
+  let mut generator = settings.into_generator();
+  let schema = generator.subschema_for::<T>();
+  let definitions = generator.take_definitions(true);
+
+  let mut declarations = Declarations::new(options.clone());
+  for declaration in parse_json_schema_declarations(&json!({ "$defs": definitions }))? {
+    declarations.insert(declaration);
+  }
+
+  Ok((parse_json_schema(schema.as_value())?, declarations))
+}
+
+/// The JSON Schema of a type that implements [`Shape`], Eg: `#[schemars(with = "shape::schemars::Shaped<User>")]`
+///
+/// Derived types are definitions of the generator and generic instances are named like in TypeScript Eg: `Page<User>`
+pub struct Shaped<T: ?Sized>(PhantomData<T>);
+
+impl<T: Shape + ?Sized> JsonSchema for Shaped<T> {
+  fn inline_schema() -> bool {
+    true
+  }
+
+  fn schema_name() -> Cow<'static, str> {
+    Cow::Borrowed(std::any::type_name::<T>())
+  }
+
+  fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+    let options = match generator.contract().is_deserialize() {
+      true => ShapeOptions::for_deserialize(),
+      false => ShapeOptions::for_serialize(),
+    };
+
+    let mut declarations = Declarations::new(options);
+    let ty = declarations.add::<T>();
+
+    let mut emitter = Emitter {
+      declarations: &declarations,
+      prefix: format!("#{}/", generator.settings().definitions_path.trim_end_matches('/')),
+      definitions: IndexMap::new(),
+    };
+    let schema = emitter.schema(&ty);
+
+    let definitions = emitter.definitions;
+    for (name, schema) in definitions {
+      if let Some(schema) = schema {
+        generator.definitions_mut().entry(name).or_insert(schema);
+      }
+    }
+
+    to_schema(schema)
+  }
+}
+
+fn to_schema(value: Value) -> Schema {
+  match value {
+    Value::Bool(value) => Schema::from(value),
+    Value::Object(map) => Schema::from(map),
+    _ => Schema::default(),
+  }
+}
+
+fn escape(name: &str) -> String {
+  name.replace('~', "~0").replace('/', "~1")
+}
+
+struct Emitter<'a> {
+  declarations: &'a Declarations,
+  // JSON pointer to the definitions Eg: `#/$defs/`
+  prefix: String,
+  // `None` while the definition is being emitted
+  definitions: IndexMap<String, Option<Value>>,
+}
+
+impl Emitter<'_> {
+  fn schema(&mut self, ty: &Type) -> Value {
+    match ty {
+      Type::Null => json!({ "type": "null" }),
+      Type::Undefined | Type::Unknown | Type::Param(_) => json!(true),
+      Type::Never => json!(false),
+      Type::Boolean => json!({ "type": "boolean" }),
+      Type::Number => json!({ "type": "number" }),
+      Type::String => json!({ "type": "string" }),
+      Type::Bytes => json!({ "type": "array", "items": { "type": "integer", "minimum": 0, "maximum": 255 } }),
+      Type::Literal(literal) => json!({ "const": literal_value(literal) }),
+      Type::Tuple(tuple) => {
+        let items = tuple.items.iter().map(|item| self.schema(item)).collect::<Vec<_>>();
+        let rest = match &tuple.rest {
+          Some(rest) => self.schema(rest),
+          None => json!(false),
+        };
+        json!({ "type": "array", "prefixItems": items, "items": rest, "minItems": tuple.items.len() })
+      },
+      Type::Array(array) => json!({ "type": "array", "items": self.schema(&array.item) }),
+      Type::Object(object) => self.object(object),
+      Type::Record(record) => {
+        let mut schema = json!({ "type": "object", "additionalProperties": self.schema(&record.value) });
+        let names = match &record.key {
+          RecordKey::String => None,
+          RecordKey::Number => Some(json!({ "pattern": "^-?[0-9]+(\\.[0-9]+)?$" })),
+          RecordKey::Integer => Some(json!({ "pattern": "^-?[0-9]+$" })),
+          RecordKey::Boolean => Some(json!({ "enum": ["true", "false"] })),
+          RecordKey::Type(key) => Some(self.schema(key)),
+          // a map with keys that can't be serialized is always empty
+          RecordKey::Unsupported(_) => Some(json!(false)),
+        };
+        if let Some(names) = names {
+          schema["propertyNames"] = names;
+        }
+        schema
+      },
+      Type::Or(items) if items.iter().all(|item| matches!(**item, Type::Literal(_))) => {
+        let values = items.iter().filter_map(|item| match &**item {
+          Type::Literal(literal) => Some(literal_value(literal)),
+          _ => None,
+        });
+        json!({ "enum": values.collect::<Vec<_>>() })
+      },
+      Type::Or(items) => json!({ "anyOf": items.iter().map(|item| self.schema(item)).collect::<Vec<_>>() }),
+      Type::And(items) => json!({ "allOf": items.iter().map(|item| self.schema(item)).collect::<Vec<_>>() }),
+      Type::Custom(custom) => match (custom.json_schema(), custom.fallback()) {
+        (Some(schema), _) => schema,
+        (None, Some(fallback)) => self.schema(fallback),
+        (None, None) => json!(true),
+      },
+      Type::Apply(apply) => {
+        // generic instances use the typescript syntax Eg: `Page<User>`
+        let name = if apply.args.is_empty() { apply.name.to_string() } else { ty.to_typescript() };
+        if !self.definitions.contains_key(&name) {
+          if let Some(declaration) = self.declarations.get(&apply.name) {
+            self.definitions.insert(name.clone(), None);
+            let ty = layout::substitute(&declaration.ty, &declaration.params, &apply.args);
+            let schema = self.schema(&ty);
+            self.definitions.insert(name.clone(), Some(schema));
+          }
+        }
+        json!({ "$ref": format!("{}{}", self.prefix, escape(&name)) })
+      },
+    }
+  }
+
+  fn object(&mut self, object: &Object) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for (key, property) in &object.properties {
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      let mut schema = self.schema(&ty);
+      if property.readonly {
+        if let Some(schema) = schema.as_object_mut() {
+          schema.insert(String::from("readOnly"), json!(true));
+        }
+      }
+      if !property.optional && !undefined {
+        required.push(key.clone());
+      }
+      properties.insert(key.to_string(), schema);
+    }
+    json!({ "type": "object", "properties": properties, "required": required })
+  }
+}
+
+fn literal_value(literal: &Literal) -> Value {
+  match literal {
+    Literal::Boolean(value) => json!(value),
+    Literal::Number(value) => json!(value),
+    Literal::String(value) => json!(value),
+  }
+}
+
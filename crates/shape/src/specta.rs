@@ -0,0 +1,288 @@
+//! Adapters between [`Shape`] and [`::specta::Type`], enabled with the `specta` feature
+//!
+//! ```ignore
+//! #[derive(Shape)]
+//! struct User {
+//!   #[shape(with = "shape::specta::Specta<Address>")]
+//!   address: Address,
+//! }
+//!
+//! // specta resolves the generic arguments of the type, an alias hides them
+//! type UserType = shape::specta::Shaped<User>;
+//!
+//! #[derive(specta::Type)]
+//! struct Order {
+//!   #[specta(type = UserType)]
+//!   user: User,
+//! }
+//! ```
+
+use std::{borrow::Cow, collections::HashMap, marker::PhantomData, sync::Arc};
+
+use ::specta::datatype::{
+  DataType, EnumRepr, EnumType, EnumVariant, EnumVariants, Field, GenericType, LiteralType, NamedDataType, PrimitiveType, StructFields,
+};
+use ::specta::internal::construct;
+use ::specta::{Generics, TypeCollection};
+
+use crate::{layout, Apply, Declaration, Declarations, Literal, Object, Property, RecordKey, Shape, ShapeOptions, Type};
+
+/// The specta [`DataType`] of the values of a shape
+///
+/// specta has no `undefined` or intersections, `undefined` is `null` and intersections are structs
+/// with flattened fields. References to declarations and custom types without a fallback are unknown
+pub trait ToSpecta {
+  fn to_specta(&self) -> DataType;
+}
+
+impl ToSpecta for Type {
+  fn to_specta(&self) -> DataType {
+    data_type(self)
+  }
+}
+
+/// The shape of a type that implements [`specta::Type`](::specta::Type), Eg: `#[shape(with = "shape::specta::Specta<Address>")]`
+///
+/// The named types are declarations, specta has the same type for the serialization and the deserialization
+pub struct Specta<T: ?Sized>(PhantomData<T>);
+
+impl<T: ::specta::Type + ?Sized> Shape for Specta<T> {
+  fn shape(options: &ShapeOptions) -> Type {
+    let (ty, declarations) = generate::<T>(options);
+    match options.use_declarations {
+      true => ty,
+      false => declarations.expand(&ty),
+    }
+  }
+
+  fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+    let (_, named) = generate::<T>(options);
+    for declaration in &named {
+      if !declarations.contains(&declaration.name) {
+        declarations.insert(declaration.clone());
+      }
+    }
+  }
+}
+
+/// A reference to `T` and the declarations of the named types
+fn generate<T: ::specta::Type + ?Sized>(options: &ShapeOptions) -> (Type, Declarations) {
+  let mut types = TypeCollection::default();
+  let reference = T::reference(&mut types, &[]).inner;
+
+  let mut declarations = Declarations::new(options.clone());
+  for (_, named) in &types {
+    declarations.insert(declaration(named));
+  }
+
+  (ty(&reference), declarations)
+}
+
+fn declaration(named: &NamedDataType) -> Declaration {
+  let params = named.inner.generics().into_iter().flatten().map(|generic| generic.to_string()).collect();
+  Declaration { name: named.name().to_string().into(), params, ty: ty(&named.inner) }
+}
+
+fn ty(data_type: &DataType) -> Type {
+  match data_type {
+    DataType::Any | DataType::Unknown => Type::Unknown,
+    DataType::Primitive(PrimitiveType::bool) => Type::Boolean,
+    DataType::Primitive(PrimitiveType::char | PrimitiveType::String) => Type::String,
+    DataType::Primitive(_) => Type::Number,
+    DataType::Literal(literal) => match literal {
+      LiteralType::i8(value) => Type::literal(*value as f64),
+      LiteralType::i16(value) => Type::literal(*value as f64),
+      LiteralType::i32(value) => Type::literal(*value as f64),
+      LiteralType::u8(value) => Type::literal(*value as f64),
+      LiteralType::u16(value) => Type::literal(*value as f64),
+      LiteralType::u32(value) => Type::literal(*value as f64),
+      LiteralType::f32(value) => Type::literal(*value as f64),
+      LiteralType::f64(value) => Type::literal(*value),
+      LiteralType::bool(value) => Type::literal(*value),
+      LiteralType::String(value) => Type::literal(value.clone()),
+      LiteralType::char(value) => Type::literal(value.to_string()),
+      LiteralType::None => Type::Null,
+      _ => Type::Unknown,
+    },
+    DataType::List(list) => match list.length() {
+      Some(length) => Type::tuple(std::iter::repeat_n(ty(list.ty()), length)),
+      None => Type::array(ty(list.ty())),
+    },
+    DataType::Map(map) => {
+      let key = RecordKey::coerce(ty(map.key_ty()));
+      Type::Record(crate::Record { optional: key.is_variants(), readonly: false, key, value: Arc::new(ty(map.value_ty())) })
+    },
+    DataType::Nullable(data_type) => ty(data_type).or_null(),
+    DataType::Struct(structure) => match structure.fields() {
+      StructFields::Unit => Type::Null,
+      StructFields::Unnamed(fields) => unnamed(fields.fields()),
+      StructFields::Named(fields) => named(fields.fields(), fields.tag().as_ref().map(|tag| (tag, structure.name()))),
+    },
+    DataType::Enum(enumeration) => enumeration_type(enumeration),
+    DataType::Tuple(tuple) if tuple.elements().is_empty() => Type::Null,
+    DataType::Tuple(tuple) => Type::tuple(tuple.elements().iter().map(ty)),
+    DataType::Reference(reference) => Type::Apply(Apply {
+      name: reference.name().clone(),
+      args: reference.generics().iter().map(|(_, arg)| Arc::new(ty(arg))).collect(),
+    }),
+    DataType::Generic(generic) => Type::Param(generic.to_string().into()),
+  }
+}
+
+/// A newtype is its field and other tuple structs are tuples, skipped fields have no type
+fn unnamed(fields: &[Field]) -> Type {
+  let mut items = fields.iter().filter_map(|field| field.ty()).map(ty).collect::<Vec<_>>();
+  match (fields.len(), items.len()) {
+    (1, 1) => items.remove(0),
+    _ => Type::tuple(items),
+  }
+}
+
+/// An object with the tag of the struct first, flattened fields are intersected
+fn named(fields: &[(Cow<'static, str>, Field)], tag: Option<(&Cow<'static, str>, &Cow<'static, str>)>) -> Type {
+  let mut object = Type::object();
+  if let Some((tag, name)) = tag {
+    object = object.field(tag.to_string(), Type::literal(name.to_string()));
+  }
+
+  let mut flattened = vec![];
+  for (key, field) in fields {
+    let Some(data_type) = field.ty() else {
+      continue;
+    };
+    match field.flatten() {
+      true => flattened.push(ty(data_type)),
+      false => {
+        object = object.property(key.to_string(), Property { optional: field.optional(), readonly: false, ty: Arc::new(ty(data_type)) });
+      },
+    }
+  }
+
+  match flattened.is_empty() {
+    true => Type::Object(object),
+    false => Type::and([Type::Object(object)].into_iter().chain(flattened)),
+  }
+}
+
+fn enumeration_type(enumeration: &EnumType) -> Type {
+  let items = enumeration
+    .variants()
+    .iter()
+    .filter(|(_, variant)| !variant.skip())
+    .map(|(name, variant)| {
+      let content = match variant.inner() {
+        EnumVariants::Unit => None,
+        EnumVariants::Unnamed(fields) => Some(unnamed(fields.fields())),
+        EnumVariants::Named(fields) => Some(named(fields.fields(), None)),
+      };
+      let tag = |tag: &str| Type::object().field(tag.to_string(), Type::literal(name.to_string()));
+      match (enumeration.repr(), content) {
+        (EnumRepr::Untagged, None) => Type::Null,
+        (EnumRepr::Untagged, Some(content)) => content,
+        (EnumRepr::External, None) => Type::literal(name.to_string()),
+        (EnumRepr::External, Some(content)) => Type::object().field(name.to_string(), content).into(),
+        (EnumRepr::Internal { tag: key }, None) => tag(key).into(),
+        (EnumRepr::Internal { tag: key }, Some(content)) => Type::and([tag(key).into(), content]),
+        (EnumRepr::Adjacent { tag: key, .. }, None) => tag(key).into(),
+        (EnumRepr::Adjacent { tag: key, content: field }, Some(content)) => tag(key).field(field.to_string(), content).into(),
+      }
+    });
+
+  let mut items = items.collect::<Vec<_>>();
+  match items.len() {
+    0 => Type::Never,
+    1 if !matches!(items[0], Type::Literal(_)) => items.remove(0),
+    _ => Type::or(items),
+  }
+}
+
+/// The type of a type that implements [`Shape`], Eg: `#[specta(type = UserType)]` with `type UserType = Shaped<User>;`
+///
+/// The shape is the one of the serialization and derived types are inlined
+pub struct Shaped<T: ?Sized>(PhantomData<T>);
+
+impl<T: Shape + ?Sized> ::specta::Type for Shaped<T> {
+  fn inline(_: &mut TypeCollection, _: Generics) -> DataType {
+    T::shape(&ShapeOptions::for_serialize()).to_specta()
+  }
+}
+
+fn field(optional: bool, flatten: bool, data_type: DataType) -> Field {
+  construct::field(optional, flatten, None, Cow::Borrowed(""), Some(data_type))
+}
+
+fn structure(fields: Vec<(Cow<'static, str>, Field)>) -> DataType {
+  construct::r#struct(Cow::Borrowed(""), None, vec![], construct::struct_named(fields, None)).into()
+}
+
+/// An untagged enum with a newtype variant for each item, like specta's own unions
+fn untagged(items: impl IntoIterator<Item = DataType>) -> DataType {
+  let variants = items
+    .into_iter()
+    .map(|item| (Cow::Borrowed(""), variant(construct::enum_variant_unnamed(vec![field(false, false, item)]))))
+    .collect();
+  construct::r#enum(Cow::Borrowed(""), construct::sid("shape::Type", "untagged"), EnumRepr::Untagged, false, vec![], variants).into()
+}
+
+fn variant(inner: EnumVariants) -> EnumVariant {
+  construct::enum_variant(false, None, Cow::Borrowed(""), inner)
+}
+
+/// specta has no public constructors of lists and maps, the ones of `Vec` and `HashMap` take their items as generics
+fn list(item: DataType) -> DataType {
+  <Vec<()> as ::specta::Type>::inline(&mut TypeCollection::default(), Generics::Provided(&[item]))
+}
+
+fn map(key: DataType, value: DataType) -> DataType {
+  <HashMap<String, ()> as ::specta::Type>::inline(&mut TypeCollection::default(), Generics::Provided(&[key, value]))
+}
+
+fn data_type(ty: &Type) -> DataType {
+  match ty {
+    Type::Null | Type::Undefined => DataType::Literal(LiteralType::None),
+    Type::Never => untagged([]),
+    Type::Unknown | Type::Apply(_) => DataType::Unknown,
+    Type::Boolean => DataType::Primitive(PrimitiveType::bool),
+    Type::Number => DataType::Primitive(PrimitiveType::f64),
+    Type::String => DataType::Primitive(PrimitiveType::String),
+    Type::Bytes => list(DataType::Primitive(PrimitiveType::u8)),
+    Type::Literal(Literal::Boolean(value)) => DataType::Literal(LiteralType::bool(*value)),
+    Type::Literal(Literal::Number(value)) => DataType::Literal(LiteralType::f64(*value)),
+    Type::Literal(Literal::String(value)) => DataType::Literal(LiteralType::String(value.to_string())),
+    Type::Tuple(tuple) => match &tuple.rest {
+      None => construct::tuple(tuple.items.iter().map(|item| data_type(item)).collect()).into(),
+      // specta tuples have a fixed length
+      Some(rest) => list(untagged(tuple.items.iter().chain([rest]).map(|item| data_type(item)))),
+    },
+    Type::Array(array) => list(data_type(&array.item)),
+    Type::Object(object) => structure(properties(object)),
+    Type::Record(record) => {
+      let key = match &record.key {
+        RecordKey::String => DataType::Primitive(PrimitiveType::String),
+        RecordKey::Number => DataType::Primitive(PrimitiveType::f64),
+        RecordKey::Integer => DataType::Primitive(PrimitiveType::i64),
+        RecordKey::Boolean => DataType::Primitive(PrimitiveType::bool),
+        RecordKey::Type(key) | RecordKey::Unsupported(key) => data_type(key),
+      };
+      map(key, data_type(&record.value))
+    },
+    Type::Or(items) => untagged(items.iter().map(|item| data_type(item))),
+    Type::And(items) => match layout::object(ty) {
+      Some(object) => structure(properties(&object)),
+      None => structure(items.iter().enumerate().map(|(i, item)| (Cow::Owned(i.to_string()), field(false, true, data_type(item)))).collect()),
+    },
+    Type::Custom(custom) => custom.fallback().map_or(DataType::Unknown, data_type),
+    Type::Param(name) => DataType::Generic(GenericType::from(name.clone())),
+  }
+}
+
+fn properties(object: &Object) -> Vec<(Cow<'static, str>, Field)> {
+  object
+    .properties
+    .iter()
+    .map(|(key, property)| {
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      (key.clone(), field(property.optional || undefined, false, data_type(&ty)))
+    })
+    .collect()
+}
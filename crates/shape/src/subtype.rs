@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use crate::is_assignable::key_matches;
+use crate::layout;
+use crate::{Literal, Object, Record, RecordKey, Tuple, Type};
+
+impl Type {
+  /// Whether every value of `self` is a value of `other`, Eg: `{ id: number; name: string; }` is a subtype of `{ id: number; }`
+  ///
+  /// The check is structural and conservative, it can answer `false` for some subtypes Eg: a union that
+  /// is only covered by the intersection of other unions. References are compared by name and arguments
+  pub fn is_subtype_of(&self, other: &Type) -> bool {
+    subtype(self, other)
+  }
+
+  /// Whether both types have the same values, see [`Type::is_subtype_of`]
+  pub fn is_equivalent(&self, other: &Type) -> bool {
+    subtype(self, other) && subtype(other, self)
+  }
+}
+
+fn subtype(sub: &Type, sup: &Type) -> bool {
+  match (sub, sup) {
+    (Type::Never, _) | (_, Type::Unknown) => true,
+    (Type::Or(items), sup) => items.iter().all(|item| subtype(item, sup)),
+    (sub, Type::And(items)) => items.iter().all(|item| subtype(sub, item)),
+    // an intersection of objects is a single object with all the properties
+    (Type::And(items), sup) => match layout::object(sub) {
+      Some(object) => subtype(&Type::Object(object), sup),
+      None => items.iter().any(|item| subtype(item, sup)) || or(sub, sup),
+    },
+    (sub, sup @ Type::Or(_)) => or(sub, sup),
+
+    (Type::Null, Type::Null)
+    | (Type::Undefined, Type::Undefined)
+    | (Type::Boolean, Type::Boolean)
+    | (Type::Number, Type::Number)
+    | (Type::String, Type::String)
+    | (Type::Bytes, Type::Bytes) => true,
+
+    (Type::Literal(a), Type::Literal(b)) => a == b,
+    (Type::Literal(Literal::Boolean(_)), Type::Boolean)
+    | (Type::Literal(Literal::Number(_)), Type::Number)
+    | (Type::Literal(Literal::String(_)), Type::String) => true,
+
+    (Type::Tuple(sub), Type::Tuple(sup)) => tuple(sub, sup),
+    (Type::Tuple(sub), Type::Array(sup)) => sub.items.iter().chain(&sub.rest).all(|item| subtype(item, &sup.item)),
+    (Type::Array(sub), Type::Tuple(sup)) => sup.items.is_empty() && sup.rest.as_deref().is_some_and(|rest| subtype(&sub.item, rest)),
+    (Type::Array(sub), Type::Array(sup)) => subtype(&sub.item, &sup.item),
+
+    (Type::Object(sub), Type::Object(sup)) => object(sub, sup),
+    (Type::Object(sub), Type::Record(sup)) => sub.properties.iter().all(|(key, property)| {
+      key_matches(&sup.key, key) && subtype(&property.ty, &sup.value)
+    }) && (sup.optional || !sup.key.is_variants()),
+    // only the empty record is always a value of an object without required properties
+    (Type::Record(sub), Type::Object(sup)) => sup.properties.values().all(|property| property.optional && subtype(&sub.value, &property.ty)),
+    (Type::Record(sub), Type::Record(sup)) => record(sub, sup),
+
+    (Type::Custom(a), Type::Custom(b)) if a.name() == b.name() => true,
+    (Type::Custom(custom), sup) => custom.fallback().is_some_and(|fallback| subtype(fallback, sup)),
+    (Type::Param(a), Type::Param(b)) => a == b,
+    // generic arguments are compared in both directions, the declarations are not resolved
+    (Type::Apply(a), Type::Apply(b)) => a.name == b.name && a.args.len() == b.args.len() && a.args.iter().zip(&b.args).all(|(a, b)| a.is_equivalent(b)),
+
+    _ => false,
+  }
+}
+
+/// `sub` is a subtype of one of the members of the union `sup`
+fn or(sub: &Type, sup: &Type) -> bool {
+  match sup {
+    Type::Or(items) => items.iter().any(|item| subtype(sub, item)) || boolean(sub, items),
+    _ => false,
+  }
+}
+
+/// `boolean` is `true | false`
+fn boolean(sub: &Type, items: &[Arc<Type>]) -> bool {
+  *sub == Type::Boolean && [true, false].iter().all(|value| items.iter().any(|item| subtype(&Type::Literal(Literal::Boolean(*value)), item)))
+}
+
+fn tuple(sub: &Tuple, sup: &Tuple) -> bool {
+  if sub.items.len() < sup.items.len() || (sub.items.len() > sup.items.len() && sup.rest.is_none()) {
+    return false;
+  }
+
+  let items = sub.items.iter().enumerate().all(|(i, item)| match sup.items.get(i) {
+    Some(sup) => subtype(item, sup),
+    None => sup.rest.as_deref().is_some_and(|rest| subtype(item, rest)),
+  });
+
+  let rest = match (&sub.rest, &sup.rest) {
+    (None, _) => true,
+    (Some(_), None) => false,
+    (Some(sub), Some(sup)) => subtype(sub, sup),
+  };
+
+  items && rest
+}
+
+/// Extra properties of `sub` are allowed, like in TypeScript
+fn object(sub: &Object, sup: &Object) -> bool {
+  sup.properties.iter().all(|(key, property)| match sub.properties.get(key) {
+    Some(sub) => (property.optional || !sub.optional) && subtype(&sub.ty, &property.ty),
+    None => property.optional,
+  })
+}
+
+fn record(sub: &Record, sup: &Record) -> bool {
+  // the subtypes of a record that requires all its keys require the same keys
+  let required = !sup.optional && sup.key.is_variants();
+  key(&sub.key, &sup.key) && subtype(&sub.value, &sup.value) && (!required || (!sub.optional && sub.key == sup.key))
+}
+
+fn key(sub: &RecordKey, sup: &RecordKey) -> bool {
+  match (sub, sup) {
+    // a record with keys that can't be serialized is always empty
+    (RecordKey::Unsupported(_), _) => true,
+    (_, RecordKey::String) => true,
+    (RecordKey::Integer | RecordKey::Number, RecordKey::Number) => true,
+    (RecordKey::Integer, RecordKey::Integer) | (RecordKey::Boolean, RecordKey::Boolean) => true,
+    (RecordKey::Type(sub), RecordKey::Type(sup)) => subtype(sub, sup),
+    (RecordKey::Type(sub), sup) => subtype(sub, &sup.to_type()),
+    _ => false,
+  }
+}
@@ -0,0 +1,407 @@
+use std::{fmt, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::{layout::{self, Layout}, Apply, Declarations, Literal, Object, Property, Type};
+
+/// GraphQL SDL for the declarations, objects are `type`s with serialize options and `input`s with
+/// deserialize options, unit only enums are `enum`s and tagged enums are `union`s of a type per variant
+///
+/// GraphQL has no generics, every use of a generic declaration gets its own type Eg: `PageUser`
+///
+/// The errors of all the declarations are reported together, the first one of each declaration
+pub trait ToGraphql {
+  fn to_graphql(&self) -> Result<String, Vec<GraphqlError>>;
+}
+
+/// A shape that can't be represented in GraphQL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphqlError {
+  /// the declaration and the properties that lead to the shape Eg: `User.location`
+  pub path: String,
+  pub kind: GraphqlErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphqlErrorKind {
+  Tuple,
+  Record,
+  Bytes,
+  Never,
+  /// a value of any type Eg: `serde_json::Value`
+  Unknown,
+  /// `null` or `undefined` that is not part of a union Eg: `()`
+  Null,
+  /// an object without properties
+  Empty,
+  /// an intersection with something that is not an object
+  Intersection,
+  /// a union that is not an enum, a tagged union or a union of objects
+  Union,
+  /// a union in an input type, only externally tagged enums can be `@oneOf` inputs
+  InputUnion,
+  /// a generic parameter outside of a declaration
+  Generic,
+  /// a property or enum value that is not a valid GraphQL name
+  Name(String),
+}
+
+impl fmt::Display for GraphqlErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Tuple => write!(f, "tuples can't be represented in GraphQL"),
+      Self::Record => write!(f, "records with arbitrary keys can't be represented in GraphQL"),
+      Self::Bytes => write!(f, "byte strings can't be represented in GraphQL"),
+      Self::Never => write!(f, "never can't be represented in GraphQL"),
+      Self::Unknown => write!(f, "values of any type can't be represented in GraphQL"),
+      Self::Null => write!(f, "null can only be represented as a nullable field in GraphQL"),
+      Self::Empty => write!(f, "GraphQL objects must have at least one field"),
+      Self::Intersection => write!(f, "intersections with non object types can't be represented in GraphQL"),
+      Self::Union => write!(f, "GraphQL unions must be unit only enums, tagged enums or unions of objects"),
+      Self::InputUnion => write!(f, "GraphQL input types can't be unions, only externally tagged enums can be @oneOf inputs"),
+      Self::Generic => write!(f, "generic parameters can't be represented in GraphQL"),
+      Self::Name(name) => write!(f, "`{name}` is not a valid GraphQL name"),
+    }
+  }
+}
+
+impl fmt::Display for GraphqlError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.path, self.kind)
+  }
+}
+
+impl std::error::Error for GraphqlError {}
+
+impl ToGraphql for Declarations {
+  fn to_graphql(&self) -> Result<String, Vec<GraphqlError>> {
+    let mut emitter = Emitter {
+      declarations: self,
+      input: !self.options().is_serialize(),
+      definitions: IndexMap::new(),
+    };
+
+    let mut errors = vec![];
+    for declaration in self.iter().filter(|declaration| declaration.params.is_empty()) {
+      if let Err(error) = emitter.apply(&Apply { name: declaration.name.clone(), args: vec![] }, &declaration.name) {
+        errors.push(error);
+      }
+    }
+
+    if !errors.is_empty() {
+      return Err(errors);
+    }
+
+    let mut out = emitter.definitions.into_values().collect::<Vec<_>>().join("\n\n");
+    out.push('\n');
+    Ok(out)
+  }
+}
+
+struct Emitter<'a> {
+  declarations: &'a Declarations,
+  input: bool,
+  /// the definition of each named type, in order of first reference
+  definitions: IndexMap<String, String>,
+}
+
+/// A GraphQL type reference without the non null `!`
+struct Field {
+  ty: String,
+  nullable: bool,
+}
+
+impl fmt::Display for Field {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", self.ty, if self.nullable { "" } else { "!" })
+  }
+}
+
+fn error<T>(path: &str, kind: GraphqlErrorKind) -> Result<T, GraphqlError> {
+  Err(GraphqlError { path: path.to_string(), kind })
+}
+
+impl Emitter<'_> {
+  fn keyword(&self) -> &'static str {
+    if self.input { "input" } else { "type" }
+  }
+
+  /// `name` is the name of the type declared for the shape if it needs one
+  fn field(&mut self, ty: &Type, name: &str, path: &str) -> Result<Field, GraphqlError> {
+    let ty = match ty {
+      Type::Null | Type::Undefined => return error(path, GraphqlErrorKind::Null),
+      Type::Never => return error(path, GraphqlErrorKind::Never),
+      Type::Unknown => return error(path, GraphqlErrorKind::Unknown),
+      Type::Boolean | Type::Literal(Literal::Boolean(_)) => String::from("Boolean"),
+      Type::Number | Type::Literal(Literal::Number(_)) => String::from("Float"),
+      Type::String | Type::Literal(Literal::String(_)) => String::from("String"),
+      Type::Bytes => return error(path, GraphqlErrorKind::Bytes),
+      Type::Tuple(_) => return error(path, GraphqlErrorKind::Tuple),
+      Type::Record(_) => return error(path, GraphqlErrorKind::Record),
+      Type::Array(array) => format!("[{}]", self.field(&array.item, &format!("{name}Item"), &format!("{path}[]"))?),
+      Type::Object(_) | Type::And(_) => self.object(ty, name, path)?,
+      Type::Or(items) => {
+        let present = items.iter().filter(|item| !matches!(***item, Type::Null | Type::Undefined)).cloned().collect::<Vec<_>>();
+        let nullable = present.len() != items.len();
+        return match present.len() {
+          0 => error(path, GraphqlErrorKind::Null),
+          1 => {
+            let field = self.field(&present[0], name, path)?;
+            Ok(Field { nullable: field.nullable || nullable, ..field })
+          },
+          _ => Ok(Field { ty: self.union(&present, name, path)?, nullable }),
+        };
+      },
+      // a custom scalar
+      Type::Custom(custom) => custom.name().to_string(),
+      Type::Param(_) => return error(path, GraphqlErrorKind::Generic),
+      Type::Apply(apply) => return self.apply(apply, path),
+    };
+
+    Ok(Field { ty, nullable: false })
+  }
+
+  /// A reference to a declaration, declarations that are not objects or unions are inlined
+  fn apply(&mut self, apply: &Apply, path: &str) -> Result<Field, GraphqlError> {
+    let Some(declaration) = self.declarations.get(&apply.name) else {
+      // not a declared type, a custom scalar
+      if !apply.args.is_empty() {
+        return error(path, GraphqlErrorKind::Generic);
+      }
+      return Ok(Field { ty: apply.name.to_string(), nullable: false });
+    };
+
+    let mut name = apply.name.to_string();
+    for arg in &apply.args {
+      let field = self.field(arg, &format!("{name}Arg"), path)?;
+      name.push_str(&field.ty.replace('[', "List").replace([']', '!'], ""));
+    }
+
+    let ty = layout::substitute(&declaration.ty, &declaration.params, &apply.args);
+    let (named, nullable) = match &ty {
+      Type::Object(_) | Type::And(_) => (true, false),
+      Type::Or(items) => {
+        let present = items.iter().filter(|item| !matches!(***item, Type::Null | Type::Undefined)).count();
+        (present > 1, present != items.len())
+      },
+      _ => (false, false),
+    };
+
+    if !named {
+      return self.field(&ty, &name, path);
+    }
+
+    // recursive types are referenced while they are being defined
+    if !self.definitions.contains_key(&name) {
+      self.field(&ty, &name, &name)?;
+    }
+
+    Ok(Field { ty: name, nullable })
+  }
+
+  /// The properties of a type that is always an object, including the referenced declarations
+  fn properties(&self, ty: &Type) -> Option<Object> {
+    match ty {
+      Type::Object(object) => Some(object.clone()),
+      Type::And(items) => {
+        let mut properties = IndexMap::new();
+        for item in items {
+          properties.extend(self.properties(item)?.properties);
+        }
+        Some(Object { properties })
+      },
+      Type::Apply(apply) => {
+        let declaration = self.declarations.get(&apply.name)?;
+        self.properties(&layout::substitute(&declaration.ty, &declaration.params, &apply.args))
+      },
+      _ => None,
+    }
+  }
+
+  fn object(&mut self, ty: &Type, name: &str, path: &str) -> Result<String, GraphqlError> {
+    let Some(object) = self.properties(ty) else {
+      return error(path, GraphqlErrorKind::Intersection);
+    };
+    self.define_object(&object, name, path)
+  }
+
+  fn define_object(&mut self, object: &Object, name: &str, path: &str) -> Result<String, GraphqlError> {
+    if object.properties.is_empty() {
+      return error(path, GraphqlErrorKind::Empty);
+    }
+
+    self.reserve(name);
+    let mut fields = vec![];
+    for (key, property) in &object.properties {
+      let path = format!("{path}.{key}");
+      if !is_name(key) {
+        return error(&path, GraphqlErrorKind::Name(key.to_string()));
+      }
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      let field = self.field(&ty, &format!("{name}{}", layout::pascal_case(key)), &path)?;
+      let field = Field { nullable: field.nullable || property.optional || undefined, ..field };
+      fields.push(format!("  {key}: {field}"));
+    }
+
+    self.define(name, format!("{} {name} {{\n{}\n}}", self.keyword(), fields.join("\n")));
+    Ok(name.to_string())
+  }
+
+  /// Types are defined before the types nested in them, and recursive references stop here
+  fn reserve(&mut self, name: &str) {
+    self.definitions.entry(name.to_string()).or_default();
+  }
+
+  fn define(&mut self, name: &str, definition: String) {
+    self.definitions.insert(name.to_string(), definition);
+  }
+
+  fn union(&mut self, items: &[Arc<Type>], name: &str, path: &str) -> Result<String, GraphqlError> {
+    self.reserve(name);
+    let values = items.iter().map(|item| match &**item {
+      Type::Literal(Literal::String(value)) => Some(value),
+      _ => None,
+    }).collect::<Option<Vec<_>>>();
+
+    if let Some(values) = values {
+      for value in &values {
+        if !is_name(value) || matches!(value.as_ref(), "true" | "false" | "null") {
+          return error(&format!("{path}.{value}"), GraphqlErrorKind::Name(value.to_string()));
+        }
+      }
+      let values = values.iter().map(|value| format!("  {value}")).collect::<Vec<_>>();
+      self.define(name, format!("enum {name} {{\n{}\n}}", values.join("\n")));
+      return Ok(name.to_string());
+    }
+
+    let members = match layout::layout(items) {
+      Layout::External(variants) => {
+        if variants.iter().any(|variant| variant.content.is_none()) {
+          return error(path, GraphqlErrorKind::Union);
+        }
+
+        if self.input {
+          // Eg: input Status @oneOf { Banned: StatusBanned }
+          let mut fields = vec![];
+          for variant in variants {
+            let path = format!("{path}.{}", variant.name);
+            if !is_name(&variant.name) {
+              return error(&path, GraphqlErrorKind::Name(variant.name.to_string()));
+            }
+            let content = variant.content.unwrap();
+            let field = self.field(&content, &format!("{name}{}", layout::pascal_case(&variant.name)), &path)?;
+            fields.push(format!("  {}: {}", variant.name, field.ty));
+          }
+          self.define(name, format!("input {name} @oneOf {{\n{}\n}}", fields.join("\n")));
+          return Ok(name.to_string());
+        }
+
+        // Eg: { "Banned": { ... } } in `Status` is `StatusBannedVariant` and its content is `StatusBanned`
+        let mut members = vec![];
+        for variant in variants {
+          let pascal = layout::pascal_case(&variant.name);
+          let object = Object {
+            properties: [(variant.name.clone(), Property { optional: false, readonly: false, ty: variant.content.unwrap() })].into_iter().collect(),
+          };
+          let ty = self.variant_object(&object, &format!("{name}{pascal}Variant"), &format!("{name}{pascal}"), path)?;
+          members.push(ty);
+        }
+        members
+      },
+
+      Layout::Internal { tag, variants } => {
+        if self.input {
+          return error(path, GraphqlErrorKind::InputUnion);
+        }
+
+        let mut members = vec![];
+        for variant in variants {
+          let path = format!("{path}.{}", variant.name);
+          let mut object = Object { properties: IndexMap::new() };
+          object.properties.insert(tag.clone(), tag_property(&variant.name));
+          if let Some(content) = &variant.content {
+            let Some(content) = self.properties(content) else {
+              return error(&path, GraphqlErrorKind::Union);
+            };
+            object.properties.extend(content.properties);
+          }
+          let variant_name = format!("{name}{}", layout::pascal_case(&variant.name));
+          members.push(self.define_object(&object, &variant_name, &path)?);
+        }
+        members
+      },
+
+      Layout::Adjacent { tag, content, variants } => {
+        if self.input {
+          return error(path, GraphqlErrorKind::InputUnion);
+        }
+
+        let mut members = vec![];
+        for variant in variants {
+          let mut object = Object { properties: IndexMap::new() };
+          object.properties.insert(tag.clone(), tag_property(&variant.name));
+          if let Some(ty) = variant.content {
+            object.properties.insert(content.clone(), Property { optional: false, readonly: false, ty });
+          }
+          let variant_name = format!("{name}{}", layout::pascal_case(&variant.name));
+          members.push(self.define_object(&object, &variant_name, &format!("{path}.{}", variant.name))?);
+        }
+        members
+      },
+
+      Layout::Untagged(items) => {
+        if self.input {
+          return error(path, GraphqlErrorKind::InputUnion);
+        }
+
+        let mut members = vec![];
+        for (i, item) in items.iter().enumerate() {
+          if self.properties(item).is_none() {
+            return error(path, GraphqlErrorKind::Union);
+          }
+          let field = match &**item {
+            Type::Apply(apply) => self.apply(apply, path)?,
+            item => self.field(item, &format!("{name}{}", i + 1), path)?,
+          };
+          members.push(field.ty);
+        }
+        members
+      },
+
+      Layout::Optional(_) => return error(path, GraphqlErrorKind::Union),
+    };
+
+    self.define(name, format!("union {name} = {}", members.join(" | ")));
+    Ok(name.to_string())
+  }
+
+  /// An object whose nested types are named after `nested` instead of its own name
+  fn variant_object(&mut self, object: &Object, name: &str, nested: &str, path: &str) -> Result<String, GraphqlError> {
+    self.reserve(name);
+    let mut fields = vec![];
+    for (key, property) in &object.properties {
+      let path = format!("{path}.{key}");
+      if !is_name(key) {
+        return error(&path, GraphqlErrorKind::Name(key.to_string()));
+      }
+      let field = self.field(&property.ty, nested, &path)?;
+      fields.push(format!("  {key}: {field}"));
+    }
+    self.define(name, format!("type {name} {{\n{}\n}}", fields.join("\n")));
+    Ok(name.to_string())
+  }
+}
+
+fn tag_property(value: &str) -> Property {
+  Property { optional: false, readonly: false, ty: Arc::new(Type::Literal(Literal::String(value.to_string().into()))) }
+}
+
+/// `/[_A-Za-z][_0-9A-Za-z]*/` and not reserved for introspection
+fn is_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  match chars.next() {
+    Some(first) if first.is_ascii_alphabetic() || first == '_' => {},
+    _ => return false,
+  }
+  chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && !name.starts_with("__")
+}
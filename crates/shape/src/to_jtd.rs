@@ -0,0 +1,473 @@
+use std::{fmt, mem, sync::Arc};
+
+use indexmap::{IndexMap, IndexSet};
+use serde_json::{json, Map, Value};
+
+use crate::{layout, Apply, Declarations, Literal, Object, Record, RecordKey, ToTypescript, Type};
+
+/// A JSON Type Definition (RFC 8927) schema, tagged enums are `discriminator` forms
+///
+/// Every shape that can't be represented is reported, in the order they are found
+pub trait ToJtd {
+  fn to_jtd(&self) -> Result<Value, Vec<JtdError>>;
+}
+
+/// A shape that can't be represented in JTD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JtdError {
+  /// JSON pointer to the schema that would describe the shape Eg: `/definitions/Point/properties/location`
+  pub path: String,
+  pub kind: JtdErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JtdErrorKind {
+  Tuple,
+  Bytes,
+  /// a map with keys that serde_json can't serialize Eg: tuples
+  Key,
+  /// an intersection with something that is not an object
+  Intersection,
+  /// a union that is not a string enum and has no discriminator Eg: an untagged enum
+  Untagged,
+  /// a number or boolean literal, JTD enums only have strings
+  Literal,
+  /// `null` or `undefined` that is not part of a union Eg: `()`
+  Null,
+  Never,
+  /// a generic parameter outside of a declaration
+  Generic,
+  /// a reference to a declaration without the declarations
+  Reference(String),
+}
+
+impl fmt::Display for JtdErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Tuple => write!(f, "tuples can't be represented in JTD"),
+      Self::Bytes => write!(f, "byte strings can't be represented in JTD"),
+      Self::Key => write!(f, "serde_json can't serialize the keys of this map"),
+      Self::Intersection => write!(f, "intersections with non object types can't be represented in JTD"),
+      Self::Untagged => write!(f, "unions without a discriminator can't be represented in JTD"),
+      Self::Literal => write!(f, "only string literals can be represented in JTD"),
+      Self::Null => write!(f, "null can only be represented as a nullable schema in JTD"),
+      Self::Never => write!(f, "never can't be represented in JTD"),
+      Self::Generic => write!(f, "generic parameters can't be represented in JTD"),
+      Self::Reference(name) => write!(f, "the declaration of `{name}` is needed to reference it"),
+    }
+  }
+}
+
+impl fmt::Display for JtdError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", if self.path.is_empty() { "/" } else { &self.path }, self.kind)
+  }
+}
+
+impl std::error::Error for JtdError {}
+
+impl ToJtd for Type {
+  fn to_jtd(&self) -> Result<Value, Vec<JtdError>> {
+    let mut emitter = Emitter {
+      declarations: None,
+      additional_properties: false,
+      definitions: IndexMap::new(),
+      errors: vec![],
+    };
+    let schema = emitter.schema(self, "");
+    emitter.finish(schema)
+  }
+}
+
+/// The root of the schema references the first declaration that is not generic,
+/// the other declarations and the instances of generic declarations are `definitions`
+///
+/// With deserialize options objects allow `additionalProperties`, serde ignores unknown fields by default
+impl ToJtd for Declarations {
+  fn to_jtd(&self) -> Result<Value, Vec<JtdError>> {
+    let mut emitter = Emitter {
+      declarations: Some(self),
+      additional_properties: !self.options().is_serialize(),
+      definitions: IndexMap::new(),
+      errors: vec![],
+    };
+
+    let mut root = None;
+    for declaration in self.iter().filter(|declaration| declaration.params.is_empty()) {
+      let schema = emitter.schema(&Type::Apply(Apply { name: declaration.name.clone(), args: vec![] }), "");
+      root.get_or_insert(schema);
+    }
+
+    emitter.finish(root.unwrap_or_else(|| json!({})))
+  }
+}
+
+struct Emitter<'a> {
+  declarations: Option<&'a Declarations>,
+  additional_properties: bool,
+  /// `None` while the definition is being emitted
+  definitions: IndexMap<String, Option<Value>>,
+  errors: Vec<JtdError>,
+}
+
+/// Escape a JSON pointer reference token
+fn escape(token: &str) -> String {
+  token.replace('~', "~0").replace('/', "~1")
+}
+
+impl Emitter<'_> {
+  fn finish(self, schema: Value) -> Result<Value, Vec<JtdError>> {
+    if !self.errors.is_empty() {
+      return Err(self.errors);
+    }
+
+    let mut schema = match schema {
+      Value::Object(schema) => schema,
+      _ => unreachable!(),
+    };
+
+    if !self.definitions.is_empty() {
+      let definitions = self.definitions.into_iter().map(|(name, schema)| (name, schema.unwrap())).collect::<Map<_, _>>();
+      schema.insert(String::from("definitions"), Value::Object(definitions));
+    }
+
+    Ok(Value::Object(schema))
+  }
+
+  /// Report an error, the schema is replaced by the empty form
+  fn error(&mut self, path: &str, kind: JtdErrorKind) -> Value {
+    self.errors.push(JtdError { path: path.to_string(), kind });
+    json!({})
+  }
+
+  fn schema(&mut self, ty: &Type, path: &str) -> Value {
+    match ty {
+      Type::Null | Type::Undefined => self.error(path, JtdErrorKind::Null),
+      Type::Never => self.error(path, JtdErrorKind::Never),
+      // the empty form accepts any value
+      Type::Unknown => json!({}),
+      Type::Boolean => json!({ "type": "boolean" }),
+      Type::Number => json!({ "type": "float64" }),
+      Type::String => json!({ "type": "string" }),
+      Type::Literal(Literal::String(value)) => json!({ "enum": [value] }),
+      Type::Literal(_) => self.error(path, JtdErrorKind::Literal),
+      Type::Bytes => self.error(path, JtdErrorKind::Bytes),
+      Type::Tuple(_) => self.error(path, JtdErrorKind::Tuple),
+      Type::Array(array) => json!({ "elements": self.schema(&array.item, &format!("{path}/elements")) }),
+      Type::Record(Record { key: RecordKey::Unsupported(_), .. }) => self.error(path, JtdErrorKind::Key),
+      // JSON object keys are always strings
+      Type::Record(record) => json!({ "values": self.schema(&record.value, &format!("{path}/values")) }),
+      Type::Object(object) => self.properties_form(object, path),
+      Type::And(_) => match self.properties(ty) {
+        Some(object) => self.properties_form(&object, path),
+        None => self.error(path, JtdErrorKind::Intersection),
+      },
+      Type::Or(items) => self.union(items, path),
+      // JTD has no custom types, the fallback or the empty form describe the values
+      Type::Custom(custom) => {
+        let mut schema = match custom.fallback() {
+          Some(fallback) => self.schema(fallback, path),
+          None => json!({}),
+        };
+        if let Some(schema) = schema.as_object_mut() {
+          schema.insert(String::from("metadata"), json!({ "type": custom.name() }));
+        }
+        schema
+      },
+      Type::Param(_) => self.error(path, JtdErrorKind::Generic),
+      Type::Apply(apply) => {
+        let Some(declaration) = self.declarations.and_then(|declarations| declarations.get(&apply.name)) else {
+          return self.error(path, JtdErrorKind::Reference(apply.name.to_string()));
+        };
+
+        // definitions can have any name, generic instances use the typescript syntax Eg: `Page<User>`
+        let name = if apply.args.is_empty() { apply.name.to_string() } else { ty.to_typescript() };
+        if !self.definitions.contains_key(&name) {
+          self.definitions.insert(name.clone(), None);
+          let ty = layout::substitute(&declaration.ty, &declaration.params, &apply.args);
+          let schema = self.schema(&ty, &format!("/definitions/{}", escape(&name)));
+          self.definitions.insert(name.clone(), Some(schema));
+        }
+
+        json!({ "ref": name })
+      },
+    }
+  }
+
+  fn union(&mut self, items: &[Arc<Type>], path: &str) -> Value {
+    let present = items.iter().filter(|item| !matches!(***item, Type::Null | Type::Undefined)).collect::<Vec<_>>();
+    let nullable = present.len() != items.len();
+
+    let mut schema = match present.as_slice() {
+      [] => return self.error(path, JtdErrorKind::Null),
+      [item] => self.schema(item, path),
+      _ => {
+        let values = present.iter().map(|item| match &***item {
+          Type::Literal(Literal::String(value)) => Some(value),
+          _ => None,
+        }).collect::<Option<Vec<_>>>();
+
+        match values {
+          Some(values) => json!({ "enum": values.into_iter().collect::<IndexSet<_>>().into_iter().collect::<Vec<_>>() }),
+          None => self.discriminator(&present, path),
+        }
+      },
+    };
+
+    if nullable {
+      schema.as_object_mut().unwrap().insert(String::from("nullable"), Value::Bool(true));
+    }
+
+    schema
+  }
+
+  /// Internally and adjacently tagged enums, the content of an adjacently tagged variant is a property
+  fn discriminator(&mut self, items: &[&Arc<Type>], path: &str) -> Value {
+    let objects = items.iter().map(|item| self.properties(item).map(|object| Arc::new(Type::Object(object)))).collect::<Option<Vec<_>>>();
+    let Some(discriminated) = objects.as_deref().and_then(layout::discriminated) else {
+      return self.error(path, JtdErrorKind::Untagged);
+    };
+
+    let mut mapping = Map::new();
+    for (value, mut object) in discriminated.variants {
+      object.properties.shift_remove(&discriminated.tag);
+      let schema = self.properties_form(&object, &format!("{path}/mapping/{}", escape(&value)));
+      mapping.insert(value.into_owned(), schema);
+    }
+
+    json!({ "discriminator": discriminated.tag, "mapping": mapping })
+  }
+
+  /// The properties of a type that is always an object, including the referenced declarations
+  fn properties(&self, ty: &Type) -> Option<Object> {
+    match ty {
+      Type::Object(object) => Some(object.clone()),
+      Type::And(items) => {
+        let mut properties = IndexMap::new();
+        for item in items {
+          properties.extend(self.properties(item)?.properties);
+        }
+        Some(Object { properties })
+      },
+      Type::Apply(apply) => {
+        let declaration = self.declarations?.get(&apply.name)?;
+        self.properties(&layout::substitute(&declaration.ty, &declaration.params, &apply.args))
+      },
+      _ => None,
+    }
+  }
+
+  fn properties_form(&mut self, object: &Object, path: &str) -> Value {
+    let mut required = Map::new();
+    let mut optional = Map::new();
+    for (key, property) in &object.properties {
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      if property.optional || undefined {
+        let schema = self.schema(&ty, &format!("{path}/optionalProperties/{}", escape(key)));
+        optional.insert(key.to_string(), schema);
+      } else {
+        let schema = self.schema(&ty, &format!("{path}/properties/{}", escape(key)));
+        required.insert(key.to_string(), schema);
+      }
+    }
+
+    let mut schema = Map::new();
+    // the properties form needs at least one of the two keywords
+    if !required.is_empty() || optional.is_empty() {
+      schema.insert(String::from("properties"), Value::Object(required));
+    }
+    if !optional.is_empty() {
+      schema.insert(String::from("optionalProperties"), Value::Object(optional));
+    }
+    if self.additional_properties {
+      schema.insert(String::from("additionalProperties"), Value::Bool(true));
+    }
+
+    Value::Object(schema)
+  }
+}
+
+/// An error indicator of RFC 8927 validation, the JSON pointers to the rejected value
+/// and to the part of the schema that rejected it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JtdValidationError {
+  pub instance_path: String,
+  pub schema_path: String,
+}
+
+/// Validate `instance` against the JTD `schema` as specified in RFC 8927 section 3.3,
+/// the errors are in the order the standard finds them, empty if the instance is valid
+///
+/// The schema is assumed to be valid, Eg: one emitted by [`ToJtd`]
+pub fn validate_jtd(schema: &Value, instance: &Value) -> Vec<JtdValidationError> {
+  let mut validator = Validator { root: schema, instance_path: vec![], schema_path: vec![], errors: vec![] };
+  validator.validate(schema, instance, None);
+  validator.errors
+}
+
+struct Validator<'a> {
+  root: &'a Value,
+  instance_path: Vec<String>,
+  schema_path: Vec<String>,
+  errors: Vec<JtdValidationError>,
+}
+
+fn pointer(tokens: &[String]) -> String {
+  tokens.iter().map(|token| format!("/{}", escape(token))).collect()
+}
+
+/// Whether `value` is a number without a fractional part in the range
+fn is_integer(value: &Value, min: f64, max: f64) -> bool {
+  value.as_f64().is_some_and(|value| value.fract() == 0.0 && (min..=max).contains(&value))
+}
+
+/// A RFC 3339 date time, Eg: `1985-04-12T23:20:50.52Z`
+fn is_timestamp(value: &Value) -> bool {
+  let Some(value) = value.as_str() else {
+    return false;
+  };
+
+  let digits = |s: &str| !s.is_empty() && s.bytes().all(|byte| byte.is_ascii_digit());
+  let Some((date, time)) = value.split_once(['T', 't']) else {
+    return false;
+  };
+  let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+    Some(i) => time.split_at(i),
+    None => return false,
+  };
+  let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+
+  let date = date.split('-').collect::<Vec<_>>();
+  let time = time.split(':').collect::<Vec<_>>();
+  matches!(date.as_slice(), [y, m, d] if y.len() == 4 && m.len() == 2 && d.len() == 2 && date.iter().all(|part| digits(part)))
+    && matches!(time.as_slice(), [h, m, s] if h.len() == 2 && m.len() == 2 && s.len() == 2 && time.iter().all(|part| digits(part)))
+    && digits(fraction)
+    && (offset.eq_ignore_ascii_case("z") || matches!(offset[1..].split_once(':'), Some((h, m)) if h.len() == 2 && m.len() == 2 && digits(h) && digits(m)))
+}
+
+impl<'a> Validator<'a> {
+  fn error(&mut self, schema_token: Option<&str>) {
+    let mut schema_path = pointer(&self.schema_path);
+    if let Some(token) = schema_token {
+      schema_path.push_str(&format!("/{}", escape(token)));
+    }
+    self.errors.push(JtdValidationError { instance_path: pointer(&self.instance_path), schema_path });
+  }
+
+  /// `tag` is the discriminator of the parent schema, it is not an additional property of the mapping
+  fn validate(&mut self, schema: &'a Value, instance: &Value, tag: Option<&str>) {
+    if schema["nullable"] == Value::Bool(true) && instance.is_null() {
+      return;
+    }
+
+    if let Some(name) = schema["ref"].as_str() {
+      // the errors of a definition have the path of the definition
+      let schema_path = mem::replace(&mut self.schema_path, vec![String::from("definitions"), name.to_string()]);
+      self.validate(&self.root["definitions"][name], instance, None);
+      self.schema_path = schema_path;
+    } else if let Some(ty) = schema["type"].as_str() {
+      let ok = match ty {
+        "boolean" => instance.is_boolean(),
+        "float32" | "float64" => instance.is_number(),
+        "int8" => is_integer(instance, -128.0, 127.0),
+        "uint8" => is_integer(instance, 0.0, 255.0),
+        "int16" => is_integer(instance, -32768.0, 32767.0),
+        "uint16" => is_integer(instance, 0.0, 65535.0),
+        "int32" => is_integer(instance, -2147483648.0, 2147483647.0),
+        "uint32" => is_integer(instance, 0.0, 4294967295.0),
+        "string" => instance.is_string(),
+        "timestamp" => is_timestamp(instance),
+        _ => false,
+      };
+      if !ok {
+        self.error(Some("type"));
+      }
+    } else if let Some(values) = schema["enum"].as_array() {
+      if !instance.as_str().is_some_and(|instance| values.iter().any(|value| value == instance)) {
+        self.error(Some("enum"));
+      }
+    } else if let Some(elements) = schema.get("elements") {
+      let Some(items) = instance.as_array() else {
+        return self.error(Some("elements"));
+      };
+      self.schema_path.push(String::from("elements"));
+      for (i, item) in items.iter().enumerate() {
+        self.instance_path.push(i.to_string());
+        self.validate(elements, item, None);
+        self.instance_path.pop();
+      }
+      self.schema_path.pop();
+    } else if schema.get("properties").is_some() || schema.get("optionalProperties").is_some() {
+      self.properties(schema, instance, tag);
+    } else if let Some(values) = schema.get("values") {
+      let Some(object) = instance.as_object() else {
+        return self.error(Some("values"));
+      };
+      self.schema_path.push(String::from("values"));
+      for (key, value) in object {
+        self.instance_path.push(key.clone());
+        self.validate(values, value, None);
+        self.instance_path.pop();
+      }
+      self.schema_path.pop();
+    } else if let Some(tag) = schema["discriminator"].as_str() {
+      let Some(object) = instance.as_object() else {
+        return self.error(Some("discriminator"));
+      };
+      let Some(value) = object.get(tag) else {
+        return self.error(Some("discriminator"));
+      };
+      self.instance_path.push(tag.to_string());
+      let mapping = value.as_str().and_then(|value| Some((value, schema["mapping"].get(value)?)));
+      match (value.as_str(), mapping) {
+        (None, _) => self.error(Some("discriminator")),
+        (Some(_), None) => self.error(Some("mapping")),
+        (Some(value), Some((_, mapping))) => {
+          self.instance_path.pop();
+          self.schema_path.extend([String::from("mapping"), value.to_string()]);
+          self.validate(mapping, instance, Some(tag));
+          self.schema_path.truncate(self.schema_path.len() - 2);
+          return;
+        },
+      }
+      self.instance_path.pop();
+    }
+  }
+
+  fn properties(&mut self, schema: &'a Value, instance: &Value, tag: Option<&str>) {
+    let Some(object) = instance.as_object() else {
+      let keyword = if schema.get("properties").is_some() { "properties" } else { "optionalProperties" };
+      return self.error(Some(keyword));
+    };
+
+    for (keyword, required) in [("properties", true), ("optionalProperties", false)] {
+      let Some(properties) = schema[keyword].as_object() else {
+        continue;
+      };
+      for (key, property) in properties {
+        self.schema_path.extend([keyword.to_string(), key.clone()]);
+        match object.get(key) {
+          Some(value) => {
+            self.instance_path.push(key.clone());
+            self.validate(property, value, None);
+            self.instance_path.pop();
+          },
+          None if required => self.error(None),
+          None => {},
+        }
+        self.schema_path.truncate(self.schema_path.len() - 2);
+      }
+    }
+
+    if schema["additionalProperties"] != Value::Bool(true) {
+      for key in object.keys() {
+        let known = ["properties", "optionalProperties"].iter().any(|keyword| schema[keyword].get(key).is_some());
+        if !known && Some(key.as_str()) != tag {
+          self.instance_path.push(key.clone());
+          self.error(None);
+          self.instance_path.pop();
+        }
+      }
+    }
+  }
+}
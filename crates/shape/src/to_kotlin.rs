@@ -0,0 +1,366 @@
+use std::sync::Arc;
+
+use crate::{layout::{self, Layout, Variant}, Apply, Declaration, Declarations, Literal, Object, Type};
+
+/// Kotlin `kotlinx.serialization` classes for the JSON described by the declarations, nested objects
+/// become classes named after the declaration Eg: `UserAddress`, and tagged enums become sealed classes
+///
+/// Tuples, and generic enums that are externally tagged or untagged, are `JsonElement`s
+pub trait ToKotlin {
+  fn to_kotlin(&self) -> String;
+}
+
+impl ToKotlin for Declaration {
+  fn to_kotlin(&self) -> String {
+    let mut emitter = Emitter::default();
+    emitter.declaration(self);
+    emitter.statements.join("\n\n")
+  }
+}
+
+impl ToKotlin for Declarations {
+  fn to_kotlin(&self) -> String {
+    let mut emitter = Emitter::default();
+    for declaration in self {
+      emitter.declaration(declaration);
+    }
+
+    let header = [
+      "@file:OptIn(ExperimentalSerializationApi::class)",
+      "",
+      "import kotlinx.serialization.*",
+      "import kotlinx.serialization.descriptors.*",
+      "import kotlinx.serialization.encoding.*",
+      "import kotlinx.serialization.json.*",
+    ];
+
+    format!("{}\n\n{}\n", header.join("\n"), emitter.statements.join("\n\n"))
+  }
+}
+
+#[derive(Default)]
+struct Emitter {
+  statements: Vec<String>,
+}
+
+impl Emitter {
+  fn declaration(&mut self, declaration: &Declaration) {
+    let name = &declaration.name;
+    let params = &declaration.params;
+    match &declaration.ty {
+      Type::Object(_) | Type::And(_) if layout::object(&declaration.ty).is_some() => {
+        self.class(&declaration.ty, name, params);
+      },
+      Type::Or(items) if !matches!(layout::layout(items), Layout::Optional(_)) => {
+        self.enumeration(items, name, params);
+      },
+      ty => {
+        let ty = self.kotlin_type(ty, name);
+        self.statements.push(format!("typealias {name}{} = {ty}", type_params(params)));
+      },
+    }
+  }
+
+  fn kotlin_type(&mut self, ty: &Type, name: &str) -> String {
+    match ty {
+      Type::Null | Type::Undefined => String::from("JsonNull"),
+      Type::Never => String::from("Nothing"),
+      Type::Unknown => String::from("JsonElement"),
+      Type::Boolean | Type::Literal(Literal::Boolean(_)) => String::from("Boolean"),
+      Type::Number | Type::Literal(Literal::Number(_)) => String::from("Double"),
+      Type::String | Type::Literal(Literal::String(_)) => String::from("String"),
+      Type::Bytes => String::from("ByteArray"),
+      Type::Tuple(_) => String::from("JsonArray"),
+      Type::Array(array) => format!("List<{}>", self.kotlin_type(&array.item, &format!("{name}Item"))),
+      Type::Record(record) => format!("Map<String, {}>", self.kotlin_type(&record.value, &format!("{name}Value"))),
+      Type::Object(_) | Type::And(_) => match layout::object(ty) {
+        Some(_) => {
+          let mut params = vec![];
+          layout::params(ty, &mut params);
+          self.class(ty, name, &params)
+        },
+        // Eg: a flattened field of a generic type
+        None => String::from("JsonObject"),
+      },
+      Type::Or(items) => match layout::layout(items) {
+        Layout::Optional(inner) => optional(self.kotlin_type(&inner, name)),
+        _ => {
+          let mut params = vec![];
+          layout::params(ty, &mut params);
+          self.enumeration(items, name, &params)
+        },
+      },
+      Type::Custom(custom) => match custom.fallback() {
+        Some(fallback) => self.kotlin_type(fallback, name),
+        None => custom.name().to_string(),
+      },
+      Type::Param(param) => param.to_string(),
+      Type::Apply(apply) => self.apply(apply),
+    }
+  }
+
+  fn apply(&mut self, apply: &Apply) -> String {
+    if apply.args.is_empty() {
+      apply.name.to_string()
+    } else {
+      let args = apply.args.iter().map(|arg| self.kotlin_type(arg, &apply.name)).collect::<Vec<_>>();
+      format!("{}<{}>", apply.name, args.join(", "))
+    }
+  }
+
+  /// The constructor parameters of a class for the properties of an object
+  fn parameters(&mut self, object: &Object, name: &str) -> Vec<String> {
+    object.properties.iter().map(|(key, property)| {
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      let ty = self.kotlin_type(&ty, &format!("{name}{}", layout::pascal_case(key)));
+      let field = identifier(&layout::camel_case(key));
+      let serial_name = if unescape(&field) == key { String::new() } else { format!("@SerialName({}) ", string(key)) };
+      if property.optional || undefined {
+        format!("{serial_name}val {field}: {} = null", optional(ty))
+      } else {
+        format!("{serial_name}val {field}: {ty}")
+      }
+    }).collect()
+  }
+
+  fn class(&mut self, ty: &Type, name: &str, params: &[String]) -> String {
+    let object = layout::object(ty).unwrap_or(Object { properties: Default::default() });
+    let parameters = self.parameters(&object, name);
+    let class = if parameters.is_empty() {
+      format!("@Serializable\nclass {name}{}", type_params(params))
+    } else {
+      format!("@Serializable\ndata class {name}{}(\n{}\n)", type_params(params), constructor(&parameters, ""))
+    };
+    self.statements.push(class);
+    format!("{name}{}", type_params(params))
+  }
+
+  fn enumeration(&mut self, items: &[Arc<Type>], name: &str, params: &[String]) -> String {
+    let layout = layout::layout(items);
+
+    // the custom serializers are objects, they can't be generic
+    if !params.is_empty() && matches!(layout, Layout::External(_) | Layout::Untagged(_)) {
+      return String::from("JsonElement");
+    }
+
+    let enumeration = match layout {
+      Layout::Optional(inner) => return optional(self.kotlin_type(&inner, name)),
+
+      // Eg: enum Color { Red, Green }
+      Layout::External(variants) if variants.iter().all(|variant| variant.content.is_none()) => {
+        let entries = variants.iter().map(|variant| {
+          format!("    @SerialName({})\n    {},", string(&variant.name), identifier(&layout::pascal_case(&variant.name)))
+        }).collect::<Vec<_>>();
+        format!("@Serializable\nenum class {name} {{\n{}\n}}", entries.join("\n"))
+      },
+
+      Layout::External(variants) => {
+        let cases = self.cases(&variants, name);
+        let subclasses = cases.iter().map(|(class, _, ty)| match ty {
+          None => format!("    data object {class} : {name}()"),
+          Some(ty) => format!("    data class {class}(val value: {ty}) : {name}()"),
+        }).collect::<Vec<_>>();
+
+        let mut deserialize = vec![
+          String::from("val input = decoder as JsonDecoder"),
+          String::from("val element = input.decodeJsonElement()"),
+        ];
+
+        let units = cases.iter().filter(|(_, _, ty)| ty.is_none()).collect::<Vec<_>>();
+        if !units.is_empty() {
+          deserialize.push(String::from("if (element is JsonPrimitive && element.isString) {"));
+          deserialize.push(String::from("    when (element.content) {"));
+          for (class, variant, _) in &units {
+            deserialize.push(format!("        {} -> return {name}.{class}", string(variant)));
+          }
+          deserialize.push(String::from("    }"));
+          deserialize.push(String::from("}"));
+        }
+
+        if units.len() != cases.len() {
+          deserialize.push(String::from("if (element is JsonObject && element.size == 1) {"));
+          deserialize.push(String::from("    val (key, value) = element.entries.first()"));
+          deserialize.push(String::from("    when (key) {"));
+          for (class, variant, ty) in &cases {
+            if let Some(ty) = ty {
+              deserialize.push(format!("        {} -> return {name}.{class}(input.json.decodeFromJsonElement<{ty}>(value))", string(variant)));
+            }
+          }
+          deserialize.push(String::from("    }"));
+          deserialize.push(String::from("}"));
+        }
+        deserialize.push(format!("throw SerializationException(\"unknown variant of {name}\")"));
+
+        let mut serialize = vec![
+          String::from("val output = encoder as JsonEncoder"),
+          String::from("val element = when (value) {"),
+        ];
+        for (class, variant, ty) in &cases {
+          match ty {
+            None => serialize.push(format!("    is {name}.{class} -> JsonPrimitive({})", string(variant))),
+            Some(_) => serialize.push(format!(
+              "    is {name}.{class} -> buildJsonObject {{ put({}, output.json.encodeToJsonElement(value.value)) }}",
+              string(variant)
+            )),
+          }
+        }
+        serialize.push(String::from("}"));
+        serialize.push(String::from("output.encodeJsonElement(element)"));
+
+        self.statements.push(serializer(name, &deserialize, &serialize));
+        format!("@Serializable(with = {name}Serializer::class)\nsealed class {name} {{\n{}\n}}", subclasses.join("\n"))
+      },
+
+      Layout::Internal { tag, variants } => {
+        let subclasses = variants.iter().map(|variant| {
+          let class = identifier(&layout::pascal_case(&variant.name));
+          let object = variant.content.as_deref().and_then(layout::object).unwrap_or(Object { properties: Default::default() });
+          let parameters = self.parameters(&object, &format!("{name}{}", layout::pascal_case(&variant.name)));
+          subclass(name, params, &class, &variant.name, &parameters)
+        }).collect::<Vec<_>>();
+
+        format!(
+          "@Serializable\n@JsonClassDiscriminator({})\nsealed class {name}{} {{\n{}\n}}",
+          string(&tag), variance(params), subclasses.join("\n\n")
+        )
+      },
+
+      Layout::Adjacent { tag, content, variants } => {
+        let subclasses = variants.iter().map(|variant| {
+          let class = identifier(&layout::pascal_case(&variant.name));
+          let parameters = match &variant.content {
+            None => vec![],
+            Some(ty) => {
+              let ty = self.kotlin_type(ty, &format!("{name}{}", layout::pascal_case(&variant.name)));
+              vec![format!("@SerialName({}) val content: {ty}", string(&content))]
+            },
+          };
+          subclass(name, params, &class, &variant.name, &parameters)
+        }).collect::<Vec<_>>();
+
+        format!(
+          "@Serializable\n@JsonClassDiscriminator({})\nsealed class {name}{} {{\n{}\n}}",
+          string(&tag), variance(params), subclasses.join("\n\n")
+        )
+      },
+
+      // the first variant that can be decoded is used
+      Layout::Untagged(items) => {
+        let cases = items.iter().enumerate().map(|(i, item)| {
+          (format!("Variant{}", i + 1), self.kotlin_type(item, &format!("{name}Variant{}", i + 1)))
+        }).collect::<Vec<_>>();
+
+        let subclasses = cases.iter()
+          .map(|(class, ty)| format!("    data class {class}(val value: {ty}) : {name}()"))
+          .collect::<Vec<_>>();
+
+        let mut deserialize = vec![
+          String::from("val input = decoder as JsonDecoder"),
+          String::from("val element = input.decodeJsonElement()"),
+        ];
+        for (class, ty) in &cases {
+          deserialize.push(format!("runCatching {{ return {name}.{class}(input.json.decodeFromJsonElement<{ty}>(element)) }}"));
+        }
+        deserialize.push(format!("throw SerializationException(\"no variant of {name} matches\")"));
+
+        let mut serialize = vec![
+          String::from("val output = encoder as JsonEncoder"),
+          String::from("val element = when (value) {"),
+        ];
+        for (class, _) in &cases {
+          serialize.push(format!("    is {name}.{class} -> output.json.encodeToJsonElement(value.value)"));
+        }
+        serialize.push(String::from("}"));
+        serialize.push(String::from("output.encodeJsonElement(element)"));
+
+        self.statements.push(serializer(name, &deserialize, &serialize));
+        format!("@Serializable(with = {name}Serializer::class)\nsealed class {name} {{\n{}\n}}", subclasses.join("\n"))
+      },
+    };
+
+    self.statements.push(enumeration);
+    format!("{name}{}", type_params(params))
+  }
+
+  /// The class name, the serialized name and the type of the content of each variant
+  fn cases(&mut self, variants: &[Variant], name: &str) -> Vec<(String, String, Option<String>)> {
+    variants.iter().map(|variant| {
+      let ty = variant.content.as_ref().map(|content| self.kotlin_type(content, &format!("{name}{}", layout::pascal_case(&variant.name))));
+      (identifier(&layout::pascal_case(&variant.name)), variant.name.to_string(), ty)
+    }).collect()
+  }
+}
+
+/// A subclass of a sealed class with a discriminator, unit variants are data objects
+fn subclass(parent: &str, params: &[String], class: &str, serial_name: &str, parameters: &[String]) -> String {
+  let annotations = format!("    @Serializable\n    @SerialName({})", string(serial_name));
+  if parameters.is_empty() {
+    let nothing = if params.is_empty() {
+      String::new()
+    } else {
+      format!("<{}>", vec!["Nothing"; params.len()].join(", "))
+    };
+    format!("{annotations}\n    data object {class} : {parent}{nothing}()")
+  } else {
+    format!(
+      "{annotations}\n    data class {class}{params}(\n{}\n    ) : {parent}{params}()",
+      constructor(parameters, "    "),
+      params = type_params(params),
+    )
+  }
+}
+
+fn serializer(name: &str, deserialize: &[String], serialize: &[String]) -> String {
+  let indent = |lines: &[String]| lines.iter().map(|line| format!("        {line}")).collect::<Vec<_>>().join("\n");
+  format!(
+    "object {name}Serializer : KSerializer<{name}> {{\n    override val descriptor: SerialDescriptor = JsonElement.serializer().descriptor\n\n    override fun deserialize(decoder: Decoder): {name} {{\n{}\n    }}\n\n    override fun serialize(encoder: Encoder, value: {name}) {{\n{}\n    }}\n}}",
+    indent(deserialize),
+    indent(serialize),
+  )
+}
+
+fn constructor(parameters: &[String], indent: &str) -> String {
+  parameters.iter().map(|parameter| format!("{indent}    {parameter},")).collect::<Vec<_>>().join("\n")
+}
+
+fn optional(ty: String) -> String {
+  if ty.ends_with('?') { ty } else { format!("{ty}?") }
+}
+
+fn type_params(params: &[String]) -> String {
+  if params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", params.join(", "))
+  }
+}
+
+/// the unit variants of a generic sealed class extend it with `Nothing` parameters
+fn variance(params: &[String]) -> String {
+  if params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", params.iter().map(|param| format!("out {param}")).collect::<Vec<_>>().join(", "))
+  }
+}
+
+fn string(value: &str) -> String {
+  serde_json::to_string(value).unwrap().replace('$', "\\$")
+}
+
+const KEYWORDS: &[&str] = &[
+  "as", "break", "class", "continue", "do", "else", "false", "for", "fun", "if", "in", "interface", "is", "null", "object",
+  "package", "return", "super", "this", "throw", "true", "try", "typealias", "typeof", "val", "var", "when", "while",
+];
+
+fn identifier(name: &str) -> String {
+  if KEYWORDS.contains(&name) {
+    format!("`{name}`")
+  } else {
+    name.to_string()
+  }
+}
+
+fn unescape(name: &str) -> &str {
+  name.trim_matches('`')
+}
@@ -0,0 +1,337 @@
+use std::sync::Arc;
+
+use indexmap::IndexSet;
+
+use crate::{layout, Apply, Declaration, Declarations, Literal, Object, RecordKey, Type};
+
+/// The kind of Python classes generated for objects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PythonStyle {
+  /// `typing.TypedDict` classes, the values stay plain dicts
+  TypedDict,
+  /// pydantic v2 `BaseModel` classes, with discriminated unions for tagged enums
+  Pydantic,
+}
+
+/// Python type hints for the JSON described by the declarations, nested objects and
+/// the variants of enums become classes named after the declaration Eg: `EventCreated`
+///
+/// The keys of a `TypedDict` that are not identifiers use the functional syntax, in a base class
+/// `_UserKeys` when the class has bases or is generic, their types can't use the parameters
+///
+/// The number and boolean keys of maps are `str` in a `TypedDict`, only pydantic coerces them
+pub trait ToPython {
+  fn to_python(&self, style: PythonStyle) -> String;
+}
+
+impl ToPython for Declaration {
+  fn to_python(&self, style: PythonStyle) -> String {
+    let mut emitter = Emitter { style, statements: vec![] };
+    emitter.declaration(self);
+    emitter.statements.join("\n\n\n")
+  }
+}
+
+impl ToPython for Declarations {
+  fn to_python(&self, style: PythonStyle) -> String {
+    let mut out = String::from("from __future__ import annotations\n\n");
+    match style {
+      PythonStyle::TypedDict => {
+        out.push_str("from typing import Any, Generic, Literal, Never, NotRequired, TypedDict, TypeVar, Union\n");
+      },
+      PythonStyle::Pydantic => {
+        out.push_str("from typing import Annotated, Any, Generic, Literal, Never, Optional, TypeVar, Union\n\n");
+        out.push_str("from pydantic import BaseModel, Field\n");
+      },
+    }
+
+    let params = self.iter().flat_map(|declaration| declaration.params.iter()).collect::<IndexSet<_>>();
+    if !params.is_empty() {
+      out.push('\n');
+      for param in params {
+        out.push_str(&format!("{param} = TypeVar(\"{param}\")\n"));
+      }
+    }
+
+    // the referenced types are declared after the types that reference them,
+    // but python evaluates base classes and type aliases when they are defined
+    let mut emitter = Emitter { style, statements: vec![] };
+    for declaration in self.iter().collect::<Vec<_>>().into_iter().rev() {
+      emitter.declaration(declaration);
+    }
+
+    for statement in emitter.statements {
+      out.push_str("\n\n");
+      out.push_str(&statement);
+      out.push('\n');
+    }
+
+    out
+  }
+}
+
+struct Emitter {
+  style: PythonStyle,
+  statements: Vec<String>,
+}
+
+impl Emitter {
+  fn declaration(&mut self, declaration: &Declaration) {
+    let name = &declaration.name;
+    if matches!(declaration.ty, Type::Object(_) | Type::And(_)) && self.class(&declaration.ty, name, &declaration.params).is_some() {
+      return;
+    }
+
+    let annotation = self.annotation(&declaration.ty, name);
+    self.statements.push(format!("{name} = {annotation}"));
+  }
+
+  fn annotation(&mut self, ty: &Type, name: &str) -> String {
+    match ty {
+      Type::Null | Type::Undefined => String::from("None"),
+      Type::Never => String::from("Never"),
+      Type::Unknown => String::from("Any"),
+      Type::Boolean => String::from("bool"),
+      Type::Number => String::from("float"),
+      Type::String => String::from("str"),
+      Type::Bytes => String::from("bytes"),
+      Type::Literal(literal) => python_literal(literal),
+      Type::Tuple(tuple) => {
+        let mut items = tuple.items.iter().enumerate()
+          .map(|(i, item)| self.annotation(item, &format!("{name}{i}")))
+          .collect::<Vec<_>>();
+
+        if let Some(rest) = &tuple.rest {
+          let rest = self.annotation(rest, &format!("{name}Item"));
+          if items.is_empty() {
+            return format!("tuple[{rest}, ...]");
+          }
+          items.push(format!("*tuple[{rest}, ...]"));
+        }
+
+        if items.is_empty() {
+          String::from("tuple[()]")
+        } else {
+          format!("tuple[{}]", items.join(", "))
+        }
+      },
+      Type::Array(array) => format!("list[{}]", self.annotation(&array.item, &format!("{name}Item"))),
+      Type::Record(record) => {
+        let key = match &record.key {
+          // the keys of the JSON objects stay strings, only pydantic coerces them to numbers and booleans
+          RecordKey::String => String::from("str"),
+          RecordKey::Number | RecordKey::Integer | RecordKey::Boolean if self.style == PythonStyle::TypedDict => String::from("str"),
+          RecordKey::Number => String::from("float"),
+          RecordKey::Integer => String::from("int"),
+          RecordKey::Boolean => String::from("bool"),
+          RecordKey::Type(key) | RecordKey::Unsupported(key) => self.annotation(key, &format!("{name}Key")),
+        };
+        let value = self.annotation(&record.value, &format!("{name}Value"));
+        format!("dict[{key}, {value}]")
+      },
+      Type::Object(_) | Type::And(_) => {
+        let mut params = vec![];
+        layout::params(ty, &mut params);
+        self.class(ty, name, &params).unwrap_or_else(|| String::from("Any"))
+      },
+      Type::Or(items) => self.union(items, name),
+      Type::Custom(custom) => match custom.fallback() {
+        Some(fallback) => self.annotation(fallback, name),
+        None => custom.name().to_string(),
+      },
+      Type::Param(param) => param.to_string(),
+      Type::Apply(apply) => self.apply(apply),
+    }
+  }
+
+  fn apply(&mut self, apply: &Apply) -> String {
+    if apply.args.is_empty() {
+      apply.name.to_string()
+    } else {
+      let args = apply.args.iter().map(|arg| self.annotation(arg, &apply.name)).collect::<Vec<_>>();
+      format!("{}[{}]", apply.name, args.join(", "))
+    }
+  }
+
+  fn union(&mut self, items: &[Arc<Type>], name: &str) -> String {
+    if let Some(discriminated) = layout::discriminated(items) {
+      let mut variants = vec![];
+      for (value, object) in &discriminated.variants {
+        let mut params = vec![];
+        layout::params(&Type::Object(object.clone()), &mut params);
+        variants.push(self.object_class(&format!("{name}{}", layout::pascal_case(value)), &[], object, &params));
+      }
+
+      let union = format!("Union[{}]", variants.join(", "));
+      return match self.style {
+        PythonStyle::Pydantic if variants.len() > 1 => {
+          format!("Annotated[{union}, Field(discriminator=\"{}\")]", field_name(&discriminated.tag))
+        },
+        _ => union,
+      };
+    }
+
+    let mut variants = IndexSet::new();
+    for (i, item) in items.iter().enumerate() {
+      // Eg: { "Banned": { ... } } in an externally tagged enum `Status` is `StatusBannedVariant`
+      // and its content is `StatusBanned`
+      if let Type::Object(object) = &**item {
+        if let Some(key) = object.properties.keys().next().filter(|_| object.properties.len() == 1) {
+          let mut params = vec![];
+          layout::params(item, &mut params);
+          let class = format!("{name}{}Variant", layout::pascal_case(key));
+          variants.insert(self.object_class_nested(&class, name, &[], object, &params));
+          continue;
+        }
+      }
+      variants.insert(self.annotation(item, &format!("{name}{}", i + 1)));
+    }
+
+    match variants.len() {
+      0 => String::from("Never"),
+      1 => variants.pop().unwrap(),
+      _ => format!("Union[{}]", variants.into_iter().collect::<Vec<_>>().join(", ")),
+    }
+  }
+
+  /// Declare a class for an object or an intersection of objects and named types, returns the reference to the class
+  fn class(&mut self, ty: &Type, name: &str, params: &[String]) -> Option<String> {
+    if let Some(object) = layout::object(ty) {
+      return Some(self.object_class(name, &[], &object, params));
+    }
+
+    // Eg: a flattened field of a generic type, the named types are base classes
+    let Type::And(items) = ty else {
+      return None;
+    };
+
+    let mut bases = vec![];
+    let mut properties = Object { properties: Default::default() };
+    for item in items {
+      match &**item {
+        Type::Apply(apply) => bases.push(self.apply(apply)),
+        item => properties.properties.extend(layout::object(item)?.properties),
+      }
+    }
+
+    Some(self.object_class(name, &bases, &properties, params))
+  }
+
+  fn object_class(&mut self, name: &str, bases: &[String], object: &Object, params: &[String]) -> String {
+    self.object_class_nested(name, name, bases, object, params)
+  }
+
+  /// `nested` is the prefix of the names of the classes declared for the properties
+  fn object_class_nested(&mut self, name: &str, nested: &str, bases: &[String], object: &Object, params: &[String]) -> String {
+    let mut fields = vec![];
+    for (key, property) in &object.properties {
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      let annotation = self.annotation(&ty, &format!("{nested}{}", layout::pascal_case(key)));
+      let mut uses_params = vec![];
+      layout::params(&ty, &mut uses_params);
+      fields.push((key, annotation, property.optional || undefined, !uses_params.is_empty()));
+    }
+
+    let mut bases = bases.to_vec();
+    // keys that are not identifiers need the functional syntax, that does not support base classes,
+    // so with bases or parameters they are declared in a functional base of the class
+    let functional = self.style == PythonStyle::TypedDict && fields.iter().any(|(key, ..)| !is_identifier(key));
+    if functional {
+      let generic = !bases.is_empty() || !params.is_empty();
+      let keys = if generic { format!("_{name}Keys") } else { name.to_string() };
+      let items = fields.iter().filter(|(key, ..)| !generic || !is_identifier(key)).map(|(key, annotation, optional, uses_params)| {
+        // functional TypedDicts can't be generic
+        let annotation = if *uses_params { "Any" } else { annotation };
+        let annotation = if *optional { format!("NotRequired[{annotation}]") } else { annotation.to_string() };
+        format!("{}: {annotation}", serde_json::to_string(key).unwrap())
+      }).collect::<Vec<_>>();
+      self.statements.push(format!("{keys} = TypedDict(\"{keys}\", {{{}}})", items.join(", ")));
+
+      if !generic {
+        return name.to_string();
+      }
+      fields.retain(|(key, ..)| is_identifier(key));
+      bases.insert(0, keys);
+    }
+
+    if bases.is_empty() {
+      bases.push(String::from(match self.style {
+        PythonStyle::TypedDict => "TypedDict",
+        PythonStyle::Pydantic => "BaseModel",
+      }));
+    }
+
+    if !params.is_empty() {
+      bases.push(format!("Generic[{}]", params.join(", ")));
+    }
+
+    let mut class = format!("class {name}({}):\n", bases.join(", "));
+    for (key, annotation, optional, _) in &fields {
+      let line = match self.style {
+        PythonStyle::TypedDict if *optional => format!("    {key}: NotRequired[{annotation}]\n"),
+        PythonStyle::TypedDict => format!("    {key}: {annotation}\n"),
+        PythonStyle::Pydantic => {
+          let field = field_name(key);
+          let alias = if field == **key { String::new() } else { format!("alias={}", serde_json::to_string(key).unwrap()) };
+          match (*optional, alias.is_empty()) {
+            (false, true) => format!("    {field}: {annotation}\n"),
+            (false, false) => format!("    {field}: {annotation} = Field({alias})\n"),
+            (true, true) => format!("    {field}: Optional[{annotation}] = None\n"),
+            (true, false) => format!("    {field}: Optional[{annotation}] = Field(default=None, {alias})\n"),
+          }
+        },
+      };
+      class.push_str(&line);
+    }
+
+    if fields.is_empty() {
+      class.push_str("    pass\n");
+    }
+
+    self.statements.push(class.trim_end().to_string());
+
+    if params.is_empty() {
+      name.to_string()
+    } else {
+      format!("{name}[{}]", params.join(", "))
+    }
+  }
+}
+
+fn python_literal(literal: &Literal) -> String {
+  match literal {
+    Literal::String(value) => format!("Literal[{}]", serde_json::to_string(value).unwrap()),
+    Literal::Boolean(value) => String::from(if *value { "Literal[True]" } else { "Literal[False]" }),
+    // python only has integer literal types
+    Literal::Number(value) if value.is_finite() && value.fract() == 0.0 => format!("Literal[{value}]"),
+    Literal::Number(_) => String::from("float"),
+  }
+}
+
+const KEYWORDS: &[&str] = &[
+  "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+  "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise",
+  "return", "try", "while", "with", "yield",
+];
+
+fn is_identifier(key: &str) -> bool {
+  let mut chars = key.chars();
+  match chars.next() {
+    Some(first) if first.is_ascii_alphabetic() || first == '_' => {},
+    _ => return false,
+  }
+  chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && !KEYWORDS.contains(&key)
+}
+
+/// The name of a pydantic field for a property, the property is its alias if they differ
+fn field_name(key: &str) -> String {
+  let mut name = key.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect::<String>();
+  // pydantic treats fields that start with an underscore as private
+  if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+    name = format!("field{}{name}", if name.starts_with('_') { "" } else { "_" });
+  }
+  if KEYWORDS.contains(&name.as_str()) {
+    name.push('_');
+  }
+  name
+}
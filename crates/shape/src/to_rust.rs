@@ -0,0 +1,480 @@
+use std::sync::Arc;
+
+use crate::{layout::{self, Layout, Variant}, Apply, Declaration, Declarations, Literal, Object, RecordKey, Tuple, Type};
+
+/// Rust structs and enums for the JSON described by the declarations, with the `#[serde(...)]` attributes
+/// that make `#[derive(Shape)]` give back the shape of the declaration for [`crate::ShapeOptions::for_serialize`].
+/// Nested objects become structs named after the declaration Eg: `UserAddress`
+///
+/// Number and boolean literals are `f64` and `bool`, internally tagged enums are intersections of the tag and
+/// the fields, and tuples with a rest, number keys and nested custom types are not kept
+pub trait ToRust {
+  fn to_rust(&self) -> String;
+}
+
+impl ToRust for Declaration {
+  fn to_rust(&self) -> String {
+    let mut emitter = Emitter::default();
+    emitter.declaration(self);
+    emitter.statements.join("\n\n")
+  }
+}
+
+impl ToRust for Declarations {
+  fn to_rust(&self) -> String {
+    let mut emitter = Emitter::default();
+    for declaration in self {
+      emitter.declaration(declaration);
+    }
+    format!("use serde::{{Deserialize, Serialize}};\nuse shape::Shape;\n\n{}\n", emitter.statements.join("\n\n"))
+  }
+}
+
+const DERIVE: &str = "#[derive(Debug, Clone, Serialize, Deserialize, Shape)]";
+
+/// enums with only unit variants can be the keys of maps
+const UNIT_DERIVE: &str = "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Shape)]";
+
+#[derive(Default)]
+struct Emitter {
+  statements: Vec<String>,
+}
+
+impl Emitter {
+  fn declaration(&mut self, declaration: &Declaration) {
+    let name = &declaration.name;
+    let params = &declaration.params;
+    match &declaration.ty {
+      ty @ (Type::Object(_) | Type::And(_)) => {
+        self.structure(ty, name, params);
+      },
+      Type::Or(items) if !matches!(layout::layout(items), Layout::Optional(_)) => {
+        self.enumeration(items, name, params);
+      },
+      ty @ (Type::Literal(Literal::String(_)) | Type::Never) => {
+        self.enumeration(&[Arc::new(ty.clone())], name, params);
+      },
+      ty => {
+        let ty = self.rust_type(ty, name);
+        self.statements.push(format!("pub type {name}{} = {ty};", type_params(params)));
+      },
+    }
+  }
+
+  fn rust_type(&mut self, ty: &Type, name: &str) -> String {
+    match ty {
+      Type::Null | Type::Undefined => String::from("()"),
+      Type::Unknown => String::from("serde_json::Value"),
+      Type::Boolean | Type::Literal(Literal::Boolean(_)) => String::from("bool"),
+      Type::Number | Type::Literal(Literal::Number(_)) => String::from("f64"),
+      Type::String => String::from("String"),
+      // a field of bytes is `#[serde(with = "serde_bytes")]`, see `Emitter::fields`
+      Type::Bytes => String::from("Vec<u8>"),
+      Type::Tuple(tuple) => self.tuple(tuple, name),
+      Type::Array(array) => format!("Vec<{}>", self.rust_type(&array.item, &format!("{name}Item"))),
+      Type::Record(record) => {
+        let key = match &record.key {
+          RecordKey::Integer => String::from("i64"),
+          RecordKey::Boolean => String::from("bool"),
+          RecordKey::Type(key) => self.rust_type(key, &format!("{name}Key")),
+          // `f64` is not `Hash`, and the other keys fail to serialize
+          RecordKey::String | RecordKey::Number | RecordKey::Unsupported(_) => String::from("String"),
+        };
+        let value = self.rust_type(&record.value, &format!("{name}Value"));
+        format!("std::collections::HashMap<{key}, {value}>")
+      },
+      Type::Object(_) | Type::And(_) => {
+        let mut params = vec![];
+        layout::params(ty, &mut params);
+        self.structure(ty, name, &params)
+      },
+      Type::Or(items) => match layout::layout(items) {
+        Layout::Optional(inner) => format!("Option<{}>", self.rust_type(&inner, name)),
+        _ => {
+          let mut params = vec![];
+          layout::params(ty, &mut params);
+          self.enumeration(items, name, &params)
+        },
+      },
+      Type::Literal(Literal::String(_)) | Type::Never => self.enumeration(&[Arc::new(ty.clone())], name, &[]),
+      Type::Custom(custom) => match custom.fallback() {
+        Some(fallback) => self.rust_type(fallback, name),
+        None => String::from("serde_json::Value"),
+      },
+      Type::Param(param) => param.to_string(),
+      Type::Apply(apply) => self.apply(apply),
+    }
+  }
+
+  fn tuple(&mut self, tuple: &Tuple, name: &str) -> String {
+    // `()` is `null`, and a tuple can't have a rest
+    if tuple.items.is_empty() || tuple.rest.is_some() {
+      return String::from("serde_json::Value");
+    }
+
+    let items = tuple.items.iter().enumerate().map(|(i, item)| self.rust_type(item, &format!("{name}{i}"))).collect::<Vec<_>>();
+    match items.as_slice() {
+      [item] => format!("({item},)"),
+      items => format!("({})", items.join(", ")),
+    }
+  }
+
+  fn apply(&mut self, apply: &Apply) -> String {
+    if apply.args.is_empty() {
+      apply.name.to_string()
+    } else {
+      let args = apply.args.iter().map(|arg| self.rust_type(arg, &apply.name)).collect::<Vec<_>>();
+      format!("{}<{}>", apply.name, args.join(", "))
+    }
+  }
+
+  /// The fields of a struct, or of a struct variant, for the properties of an object
+  fn fields(&mut self, object: &Object, name: &str) -> Fields {
+    let mut idents = Vec::<String>::new();
+    let mut fields = vec![];
+    for (key, property) in &object.properties {
+      let mut ident = field_ident(key);
+      while idents.contains(&ident) {
+        ident.push('_');
+      }
+      idents.push(ident.clone());
+
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      let optional = property.optional || undefined;
+      let mut attrs = vec![];
+
+      let rust = match &ty {
+        Type::Custom(custom) => {
+          attrs.push(format!("#[shape(type = {})]", string(custom.name())));
+          match custom.fallback() {
+            Some(fallback) => self.rust_type(fallback, &format!("{name}{}", layout::pascal_case(key))),
+            None => String::from("serde_json::Value"),
+          }
+        },
+        Type::Bytes => {
+          attrs.push(String::from("#[serde(with = \"serde_bytes\")]"));
+          attrs.push(String::from("#[shape(with = \"std::ffi::CString\")]"));
+          String::from("Vec<u8>")
+        },
+        ty => self.rust_type(ty, &format!("{name}{}", layout::pascal_case(key))),
+      };
+
+      let rust = if optional {
+        attrs.push(String::from("#[serde(default, skip_serializing_if = \"Option::is_none\")]"));
+        if rust.starts_with("Option<") {
+          rust
+        } else {
+          // the missing field is not `null`
+          if attrs.iter().all(|attr| !attr.starts_with("#[shape(")) {
+            attrs.push(format!("#[shape(with = {})]", string(&rust)));
+          }
+          format!("Option<{rust}>")
+        }
+      } else {
+        rust
+      };
+
+      fields.push(Field { ident, key: key.to_string(), attrs, ty: rust });
+    }
+
+    let rename_all = rename_all(&fields.iter().map(|field| (field.ident.as_str(), field.key.as_str())).collect::<Vec<_>>(), FIELD_RULES, rename_field);
+    Fields { rename_all, fields }
+  }
+
+  /// A struct with the merged properties of the objects of an intersection, the other members are flattened
+  fn structure(&mut self, ty: &Type, name: &str, params: &[String]) -> String {
+    let mut items = vec![];
+    flatten(ty, &mut items);
+
+    let mut object = Object { properties: Default::default() };
+    let mut flattened = vec![];
+    for item in items {
+      match layout::object(item) {
+        Some(item) => object.properties.extend(item.properties),
+        None => flattened.push(item),
+      }
+    }
+
+    let fields = self.fields(&object, name);
+    let mut lines = fields.lines("    ", true);
+    for (i, item) in flattened.into_iter().enumerate() {
+      let ident = match item {
+        Type::Apply(apply) => field_ident(&apply.name),
+        Type::Record(_) => String::from("rest"),
+        _ => format!("flatten{i}"),
+      };
+      let ty = self.rust_type(item, &format!("{name}Flatten{i}"));
+      lines.push(format!("    #[serde(flatten)]\n    pub {ident}: {ty},"));
+    }
+
+    let mut statement = vec![String::from(DERIVE)];
+    statement.extend(fields.rename_all.map(|rule| format!("#[serde(rename_all = {})]", string(rule))));
+    if lines.is_empty() {
+      statement.push(format!("pub struct {name}{} {{}}", type_params(params)));
+    } else {
+      statement.push(format!("pub struct {name}{} {{\n{}\n}}", type_params(params), lines.join("\n")));
+    }
+
+    self.statements.push(statement.join("\n"));
+    format!("{name}{}", type_params(params))
+  }
+
+  fn enumeration(&mut self, items: &[Arc<Type>], name: &str, params: &[String]) -> String {
+    let mut attrs = vec![];
+    let variants = match layout::layout(items) {
+      Layout::Optional(inner) => return format!("Option<{}>", self.rust_type(&inner, name)),
+      // an enum without variants has no values
+      _ if items.iter().all(|item| **item == Type::Never) => vec![],
+      Layout::External(variants) => variants,
+      Layout::Internal { tag, variants } => {
+        attrs.push(format!("#[serde(tag = {})]", string(&tag)));
+        variants
+      },
+      Layout::Adjacent { tag, content, variants } => {
+        attrs.push(format!("#[serde(tag = {}, content = {})]", string(&tag), string(&content)));
+        variants
+      },
+      Layout::Untagged(items) => {
+        attrs.push(String::from("#[serde(untagged)]"));
+        let variants = items.into_iter().enumerate().map(|(i, item)| Variant {
+          name: format!("Variant{}", i + 1).into(),
+          content: (!matches!(*item, Type::Null)).then_some(item),
+        }).collect::<Vec<_>>();
+
+        let lines = variants.iter().map(|variant| {
+          self.variant(&variant.name, variant.content.as_deref(), &format!("{name}{}", variant.name))
+        }).collect::<Vec<_>>();
+        return self.push_enum(name, params, attrs, lines, false);
+      },
+    };
+
+    let mut idents = Vec::<String>::new();
+    for variant in &variants {
+      let mut ident = variant_ident(&variant.name);
+      while idents.contains(&ident) {
+        ident.push('_');
+      }
+      idents.push(ident);
+    }
+
+    let keys = idents.iter().zip(&variants).map(|(ident, variant)| (ident.as_str(), variant.name.as_ref())).collect::<Vec<_>>();
+    let rule = rename_all(&keys, VARIANT_RULES, rename_variant);
+    attrs.extend(rule.map(|rule| format!("#[serde(rename_all = {})]", string(rule))));
+
+    let units = variants.iter().all(|variant| variant.content.is_none());
+    let lines = idents.iter().zip(&variants).map(|(ident, variant)| {
+      let line = self.variant(ident, variant.content.as_deref(), &format!("{name}{ident}"));
+      if renamed(rule, ident, rename_variant).as_deref() == Some(variant.name.as_ref()) {
+        line
+      } else {
+        format!("    #[serde(rename = {})]\n{line}", string(&variant.name))
+      }
+    }).collect::<Vec<_>>();
+
+    self.push_enum(name, params, attrs, lines, units)
+  }
+
+  fn push_enum(&mut self, name: &str, params: &[String], attrs: Vec<String>, lines: Vec<String>, units: bool) -> String {
+    let mut statement = vec![String::from(if units { UNIT_DERIVE } else { DERIVE })];
+    statement.extend(attrs);
+    if lines.is_empty() {
+      statement.push(format!("pub enum {name}{} {{}}", type_params(params)));
+    } else {
+      statement.push(format!("pub enum {name}{} {{\n{}\n}}", type_params(params), lines.join("\n")));
+    }
+
+    self.statements.push(statement.join("\n"));
+    format!("{name}{}", type_params(params))
+  }
+
+  /// A unit variant, a struct variant for an object, or a newtype variant
+  fn variant(&mut self, ident: &str, content: Option<&Type>, name: &str) -> String {
+    let Some(content) = content else {
+      return format!("    {ident},");
+    };
+
+    match content {
+      Type::Object(_) | Type::And(_) if layout::object(content).is_some() => {
+        let fields = self.fields(&layout::object(content).unwrap(), name);
+        let lines = fields.lines("        ", false);
+        let rename_all = fields.rename_all.map(|rule| format!("    #[serde(rename_all = {})]\n", string(rule))).unwrap_or_default();
+        if lines.is_empty() {
+          format!("{rename_all}    {ident} {{}},")
+        } else {
+          format!("{rename_all}    {ident} {{\n{}\n    }},", lines.join("\n"))
+        }
+      },
+      ty => format!("    {ident}({}),", self.rust_type(ty, name)),
+    }
+  }
+}
+
+struct Field {
+  ident: String,
+  key: String,
+  attrs: Vec<String>,
+  ty: String,
+}
+
+struct Fields {
+  rename_all: Option<&'static str>,
+  fields: Vec<Field>,
+}
+
+impl Fields {
+  fn lines(&self, indent: &str, public: bool) -> Vec<String> {
+    let visibility = if public { "pub " } else { "" };
+    self.fields.iter().map(|field| {
+      let mut attrs = field.attrs.clone();
+      if renamed(self.rename_all, &field.ident, rename_field).as_ref() != Some(&field.key) {
+        attrs.insert(0, format!("#[serde(rename = {})]", string(&field.key)));
+      }
+      let attrs = attrs.iter().map(|attr| format!("{indent}{attr}\n")).collect::<String>();
+      format!("{attrs}{indent}{visibility}{}: {},", field.ident, field.ty)
+    }).collect()
+  }
+}
+
+/// The members of nested intersections
+fn flatten<'a>(ty: &'a Type, out: &mut Vec<&'a Type>) {
+  match ty {
+    Type::And(items) => items.iter().for_each(|item| flatten(item, out)),
+    ty => out.push(ty),
+  }
+}
+
+const FIELD_RULES: &[&str] = &["camelCase", "PascalCase", "kebab-case", "SCREAMING_SNAKE_CASE"];
+
+const VARIANT_RULES: &[&str] = &["lowercase", "camelCase", "snake_case", "kebab-case", "SCREAMING_SNAKE_CASE"];
+
+/// The first `rename_all` rule that gives the most keys, if it gives more than the identifiers themselves.
+/// Only identifiers of plain words are counted, serde and the derive of `Shape` rename them in the same way
+fn rename_all(keys: &[(&str, &str)], rules: &[&'static str], rename: fn(&str, &str) -> String) -> Option<&'static str> {
+  let keys = keys.iter().filter(|(ident, _)| plain(ident)).collect::<Vec<_>>();
+  let count = |rename: &dyn Fn(&str) -> String| keys.iter().filter(|(ident, key)| rename(ident) == *key).count();
+
+  let mut best = (None, count(&|ident| ident.to_string()));
+  for rule in rules {
+    let count = count(&|ident| rename(rule, ident));
+    if count > best.1 {
+      best = (Some(*rule), count);
+    }
+  }
+  best.0
+}
+
+/// An identifier of words without digits or acronyms
+fn plain(ident: &str) -> bool {
+  ident.split('_').all(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphabetic()))
+    && !ident.chars().zip(ident.chars().skip(1)).any(|(a, b)| a.is_ascii_uppercase() && b.is_ascii_uppercase())
+}
+
+/// The name that serde gives to an identifier, `None` if the derive of `Shape` could rename it in a different way
+fn renamed(rule: Option<&str>, ident: &str, rename: fn(&str, &str) -> String) -> Option<String> {
+  match rule {
+    None => Some(ident.to_string()),
+    Some(rule) if plain(ident) => Some(rename(rule, ident)),
+    Some(_) => None,
+  }
+}
+
+/// serde's rename rules for the `snake_case` name of a field
+fn rename_field(rule: &str, ident: &str) -> String {
+  let pascal = ident.split('_').map(|word| {
+    let mut chars = word.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+  }).collect::<String>();
+
+  match rule {
+    "PascalCase" => pascal,
+    "camelCase" => {
+      let mut chars = pascal.chars();
+      chars.next().map(|first| first.to_lowercase().chain(chars).collect()).unwrap_or_default()
+    },
+    "kebab-case" => ident.replace('_', "-"),
+    "SCREAMING_SNAKE_CASE" => ident.to_uppercase(),
+    _ => ident.to_string(),
+  }
+}
+
+/// serde's rename rules for the `PascalCase` name of a variant
+fn rename_variant(rule: &str, ident: &str) -> String {
+  let mut snake = String::new();
+  for (i, c) in ident.chars().enumerate() {
+    if c.is_uppercase() && i > 0 {
+      snake.push('_');
+    }
+    snake.extend(c.to_lowercase());
+  }
+
+  match rule {
+    "lowercase" => ident.to_lowercase(),
+    "camelCase" => {
+      let mut chars = ident.chars();
+      chars.next().map(|first| first.to_lowercase().chain(chars).collect()).unwrap_or_default()
+    },
+    "snake_case" => snake,
+    "kebab-case" => snake.replace('_', "-"),
+    "SCREAMING_SNAKE_CASE" => snake.to_uppercase(),
+    _ => ident.to_string(),
+  }
+}
+
+/// Eg: `createdAt` and `created-at` are `created_at`
+fn field_ident(key: &str) -> String {
+  let mut ident = String::with_capacity(key.len());
+  let mut lower = false;
+  for c in key.chars() {
+    if c.is_alphanumeric() {
+      if c.is_uppercase() && lower {
+        ident.push('_');
+      }
+      lower = !c.is_uppercase();
+      ident.extend(c.to_lowercase());
+    } else {
+      if !ident.is_empty() && !ident.ends_with('_') {
+        ident.push('_');
+      }
+      lower = false;
+    }
+  }
+
+  let ident = ident.trim_end_matches('_');
+  match ident.chars().next() {
+    None => String::from("field"),
+    Some(first) if first.is_ascii_digit() => format!("_{ident}"),
+    // the derive of `Shape` doesn't unraw `r#type`
+    _ if KEYWORDS.contains(&ident) => format!("{ident}_"),
+    _ => ident.to_string(),
+  }
+}
+
+/// Eg: `created` and `in-progress` are `Created` and `InProgress`
+fn variant_ident(name: &str) -> String {
+  let ident = layout::pascal_case(name);
+  match ident.chars().next() {
+    None => String::from("Empty"),
+    Some(first) if first.is_ascii_digit() => format!("V{ident}"),
+    _ if ident == "Self" => String::from("Self_"),
+    _ => ident,
+  }
+}
+
+fn type_params(params: &[String]) -> String {
+  if params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", params.join(", "))
+  }
+}
+
+fn string(value: &str) -> String {
+  serde_json::to_string(value).unwrap()
+}
+
+const KEYWORDS: &[&str] = &[
+  "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+  "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "static",
+  "struct", "super", "trait", "true", "try", "type", "unsafe", "use", "where", "while", "abstract", "become", "box",
+  "do", "final", "macro", "override", "priv", "typeof", "unsized", "virtual", "yield",
+];
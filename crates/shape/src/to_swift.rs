@@ -0,0 +1,606 @@
+use std::sync::Arc;
+
+use crate::{layout::{self, Layout, Variant}, Apply, Declaration, Declarations, Literal, Object, Tuple, Type};
+
+/// Swift `Codable` types for the JSON described by the declarations, nested objects and
+/// the variants of enums become types named after the declaration Eg: `EventCreated`
+pub trait ToSwift {
+  fn to_swift(&self) -> String;
+}
+
+impl ToSwift for Declaration {
+  fn to_swift(&self) -> String {
+    let mut emitter = Emitter::default();
+    emitter.declaration(self);
+    emitter.finish()
+  }
+}
+
+impl ToSwift for Declarations {
+  fn to_swift(&self) -> String {
+    let mut emitter = Emitter::default();
+    for declaration in self {
+      emitter.declaration(declaration);
+    }
+    format!("import Foundation\n\n{}\n", emitter.finish())
+  }
+}
+
+/// `null` has no Codable type in Swift
+const JSON_NULL: &str = r#"struct JSONNull: Codable, Hashable {
+    init() {}
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if !container.decodeNil() {
+            throw DecodingError.typeMismatch(JSONNull.self, DecodingError.Context(codingPath: decoder.codingPath, debugDescription: "expected null"))
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        try container.encodeNil()
+    }
+}"#;
+
+/// Any JSON value, Eg: a `serde_json::Value`
+const JSON_VALUE: &str = r#"indirect enum JSONValue: Codable, Hashable {
+    case null
+    case bool(Bool)
+    case number(Double)
+    case string(String)
+    case array([JSONValue])
+    case object([String: JSONValue])
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if container.decodeNil() {
+            self = .null
+        } else if let value = try? container.decode(Bool.self) {
+            self = .bool(value)
+        } else if let value = try? container.decode(Double.self) {
+            self = .number(value)
+        } else if let value = try? container.decode(String.self) {
+            self = .string(value)
+        } else if let value = try? container.decode([JSONValue].self) {
+            self = .array(value)
+        } else {
+            self = .object(try container.decode([String: JSONValue].self))
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .null: try container.encodeNil()
+        case .bool(let value): try container.encode(value)
+        case .number(let value): try container.encode(value)
+        case .string(let value): try container.encode(value)
+        case .array(let value): try container.encode(value)
+        case .object(let value): try container.encode(value)
+        }
+    }
+}"#;
+
+#[derive(Default)]
+struct Emitter {
+  statements: Vec<String>,
+  uses_null: bool,
+  uses_value: bool,
+}
+
+struct Field {
+  name: String,
+  key: String,
+  ty: String,
+}
+
+impl Emitter {
+  fn finish(mut self) -> String {
+    if self.uses_null {
+      self.statements.push(JSON_NULL.to_string());
+    }
+    if self.uses_value {
+      self.statements.push(JSON_VALUE.to_string());
+    }
+    self.statements.join("\n\n")
+  }
+
+  fn declaration(&mut self, declaration: &Declaration) {
+    let name = &declaration.name;
+    let params = &declaration.params;
+    match &declaration.ty {
+      Type::Object(_) | Type::And(_) => {
+        self.structure(&declaration.ty, name, params);
+      },
+      Type::Or(items) if !matches!(layout::layout(items), Layout::Optional(_)) => {
+        self.enumeration(items, name, params);
+      },
+      ty => {
+        let ty = self.swift_type(ty, name);
+        self.statements.push(format!("typealias {name}{} = {ty}", reference_params(params)));
+      },
+    }
+  }
+
+  fn swift_type(&mut self, ty: &Type, name: &str) -> String {
+    match ty {
+      Type::Null | Type::Undefined => {
+        self.uses_null = true;
+        String::from("JSONNull")
+      },
+      Type::Never => String::from("Never"),
+      Type::Unknown => {
+        self.uses_value = true;
+        String::from("JSONValue")
+      },
+      Type::Boolean | Type::Literal(Literal::Boolean(_)) => String::from("Bool"),
+      Type::Number | Type::Literal(Literal::Number(_)) => String::from("Double"),
+      Type::String | Type::Literal(Literal::String(_)) => String::from("String"),
+      Type::Bytes => String::from("Data"),
+      Type::Tuple(tuple) => match (tuple.items.is_empty(), &tuple.rest) {
+        (true, Some(rest)) => format!("[{}]", self.swift_type(rest, &format!("{name}Item"))),
+        (true, None) => {
+          self.uses_null = true;
+          String::from("[JSONNull]")
+        },
+        (false, _) => {
+          let mut params = vec![];
+          layout::params(ty, &mut params);
+          self.tuple(tuple, name, &params)
+        },
+      },
+      Type::Array(array) => format!("[{}]", self.swift_type(&array.item, &format!("{name}Item"))),
+      Type::Record(record) => format!("[String: {}]", self.swift_type(&record.value, &format!("{name}Value"))),
+      Type::Object(_) | Type::And(_) => {
+        let mut params = vec![];
+        layout::params(ty, &mut params);
+        self.structure(ty, name, &params)
+      },
+      Type::Or(items) => match layout::layout(items) {
+        Layout::Optional(inner) => optional(self.swift_type(&inner, name)),
+        _ => {
+          let mut params = vec![];
+          layout::params(ty, &mut params);
+          self.enumeration(items, name, &params)
+        },
+      },
+      Type::Custom(custom) => match custom.fallback() {
+        Some(fallback) => self.swift_type(fallback, name),
+        None => custom.name().to_string(),
+      },
+      Type::Param(param) => param.to_string(),
+      Type::Apply(apply) => self.apply(apply),
+    }
+  }
+
+  fn apply(&mut self, apply: &Apply) -> String {
+    if apply.args.is_empty() {
+      apply.name.to_string()
+    } else {
+      let args = apply.args.iter().map(|arg| self.swift_type(arg, &apply.name)).collect::<Vec<_>>();
+      format!("{}<{}>", apply.name, args.join(", "))
+    }
+  }
+
+  fn fields(&mut self, object: &Object, name: &str) -> Vec<Field> {
+    object.properties.iter().map(|(key, property)| {
+      let (ty, undefined) = layout::strip_undefined(&property.ty);
+      let ty = self.swift_type(&ty, &format!("{name}{}", layout::pascal_case(key)));
+      Field {
+        name: identifier(&layout::camel_case(key)),
+        key: key.to_string(),
+        ty: if property.optional || undefined { optional(ty) } else { ty },
+      }
+    }).collect()
+  }
+
+  /// A struct for an object, the parts of an intersection that are not objects are flattened properties
+  fn structure(&mut self, ty: &Type, name: &str, params: &[String]) -> String {
+    let mut fields = vec![];
+    let mut flattened = vec![];
+
+    match layout::object(ty) {
+      Some(object) => fields = self.fields(&object, name),
+      None => {
+        let items = match ty {
+          Type::And(items) => items.clone(),
+          ty => vec![Arc::new(ty.clone())],
+        };
+
+        for (i, item) in items.iter().enumerate() {
+          match layout::object(item) {
+            Some(object) => fields.extend(self.fields(&object, name)),
+            None => {
+              let field = match &**item {
+                Type::Apply(apply) => layout::camel_case(&apply.name),
+                Type::Param(param) => layout::camel_case(param),
+                _ => format!("flatten{i}"),
+              };
+              let ty = self.swift_type(item, &format!("{name}{}", layout::pascal_case(&field)));
+              flattened.push((identifier(&field), ty));
+            },
+          }
+        }
+      },
+    }
+
+    let mut body = vec![];
+    body.push(
+      fields.iter().map(|field| format!("    let {}: {}", field.name, field.ty))
+        .chain(flattened.iter().map(|(field, ty)| format!("    let {field}: {ty}")))
+        .collect::<Vec<_>>()
+        .join("\n")
+    );
+
+    let renamed = fields.iter().any(|field| unescape(&field.name) != field.key);
+    if !fields.is_empty() && (renamed || !flattened.is_empty()) {
+      body.push(coding_keys("CodingKeys", &fields));
+    }
+
+    if !flattened.is_empty() {
+      let mut decode = vec![];
+      let mut encode = vec![];
+      if !fields.is_empty() {
+        decode.push(String::from("let container = try decoder.container(keyedBy: CodingKeys.self)"));
+        encode.push(String::from("var container = encoder.container(keyedBy: CodingKeys.self)"));
+      }
+
+      for field in &fields {
+        let key = unescape(&field.name);
+        match field.ty.strip_suffix('?') {
+          Some(ty) => {
+            decode.push(format!("{} = try container.decodeIfPresent({ty}.self, forKey: .{key})", field.name));
+            encode.push(format!("try container.encodeIfPresent({}, forKey: .{key})", field.name));
+          },
+          None => {
+            decode.push(format!("{} = try container.decode({}.self, forKey: .{key})", field.name, field.ty));
+            encode.push(format!("try container.encode({}, forKey: .{key})", field.name));
+          },
+        }
+      }
+
+      for (field, ty) in &flattened {
+        match ty.strip_suffix('?') {
+          Some(ty) => {
+            decode.push(format!("{field} = try? {ty}(from: decoder)"));
+            encode.push(format!("try {field}?.encode(to: encoder)"));
+          },
+          None => {
+            decode.push(format!("{field} = try {ty}(from: decoder)"));
+            encode.push(format!("try {field}.encode(to: encoder)"));
+          },
+        }
+      }
+
+      body.push(method("init(from decoder: Decoder) throws", &decode));
+      body.push(method("func encode(to encoder: Encoder) throws", &encode));
+    }
+
+    self.statements.push(format!("struct {name}{}: Codable {{\n{}\n}}", declaration_params(params), body.join("\n\n")));
+    format!("{name}{}", reference_params(params))
+  }
+
+  /// Swift tuples are not Codable, they are structs with an unkeyed container
+  fn tuple(&mut self, tuple: &Tuple, name: &str, params: &[String]) -> String {
+    let items = tuple.items.iter().enumerate()
+      .map(|(i, item)| self.swift_type(item, &format!("{name}{i}")))
+      .collect::<Vec<_>>();
+    let rest = tuple.rest.as_ref().map(|rest| self.swift_type(rest, &format!("{name}Item")));
+
+    let mut properties = items.iter().enumerate().map(|(i, ty)| format!("    let _{i}: {ty}")).collect::<Vec<_>>();
+    let mut decode = vec![String::from("var container = try decoder.unkeyedContainer()")];
+    let mut encode = vec![String::from("var container = encoder.unkeyedContainer()")];
+
+    for (i, ty) in items.iter().enumerate() {
+      decode.push(format!("_{i} = try container.decode({ty}.self)"));
+      encode.push(format!("try container.encode(_{i})"));
+    }
+
+    if let Some(rest) = rest {
+      properties.push(format!("    let rest: [{rest}]"));
+      decode.push(format!("var rest: [{rest}] = []"));
+      decode.push(String::from("while !container.isAtEnd {"));
+      decode.push(format!("    rest.append(try container.decode({rest}.self))"));
+      decode.push(String::from("}"));
+      decode.push(String::from("self.rest = rest"));
+      encode.push(String::from("try container.encode(contentsOf: rest)"));
+    }
+
+    let body = [
+      properties.join("\n"),
+      method("init(from decoder: Decoder) throws", &decode),
+      method("func encode(to encoder: Encoder) throws", &encode),
+    ];
+
+    self.statements.push(format!("struct {name}{}: Codable {{\n{}\n}}", declaration_params(params), body.join("\n\n")));
+    format!("{name}{}", reference_params(params))
+  }
+
+  fn enumeration(&mut self, items: &[Arc<Type>], name: &str, params: &[String]) -> String {
+    let generics = declaration_params(params);
+    let enumeration = match layout::layout(items) {
+      Layout::Optional(inner) => return optional(self.swift_type(&inner, name)),
+
+      // Eg: enum Color { Red, Green }
+      Layout::External(variants) if variants.iter().all(|variant| variant.content.is_none()) => {
+        let cases = variants.iter().map(|variant| {
+          format!("    case {} = {}", case_name(&variant.name), string(&variant.name))
+        }).collect::<Vec<_>>();
+        format!("enum {name}: String, Codable {{\n{}\n}}", cases.join("\n"))
+      },
+
+      Layout::External(variants) => {
+        let cases = self.cases(&variants, name);
+        let keyed = cases.iter().filter(|(_, _, ty)| ty.is_some())
+          .map(|(case, variant, _)| Field { name: case.clone(), key: variant.clone(), ty: String::new() })
+          .collect::<Vec<_>>();
+
+        let mut decode = vec![];
+        let mut encode = vec![String::from("switch self {")];
+
+        let units = cases.iter().filter(|(_, _, ty)| ty.is_none()).collect::<Vec<_>>();
+        if !units.is_empty() {
+          decode.push(String::from("if let container = try? decoder.singleValueContainer(), let value = try? container.decode(String.self) {"));
+          decode.push(String::from("    switch value {"));
+          for (case, variant, _) in &units {
+            decode.push(format!("    case {}:", string(variant)));
+            decode.push(format!("        self = .{case}"));
+            decode.push(String::from("        return"));
+          }
+          decode.push(String::from("    default:"));
+          decode.push(String::from("        break"));
+          decode.push(String::from("    }"));
+          decode.push(String::from("}"));
+        }
+
+        decode.push(String::from("let container = try decoder.container(keyedBy: CodingKeys.self)"));
+        for (case, _, ty) in &cases {
+          let key = unescape(case);
+          match ty {
+            None => {},
+            Some(ty) => {
+              decode.push(format!("if container.contains(.{key}) {{"));
+              decode.push(format!("    self = .{case}(try container.decode({ty}.self, forKey: .{key}))"));
+              decode.push(String::from("    return"));
+              decode.push(String::from("}"));
+            },
+          }
+        }
+        decode.push(format!("throw DecodingError.dataCorrupted(DecodingError.Context(codingPath: decoder.codingPath, debugDescription: \"unknown variant of {name}\"))"));
+
+        for (case, variant, ty) in &cases {
+          match ty {
+            None => {
+              encode.push(format!("case .{case}:"));
+              encode.push(String::from("    var container = encoder.singleValueContainer()"));
+              encode.push(format!("    try container.encode({})", string(variant)));
+            },
+            Some(_) => {
+              encode.push(format!("case .{case}(let value):"));
+              encode.push(String::from("    var container = encoder.container(keyedBy: CodingKeys.self)"));
+              encode.push(format!("    try container.encode(value, forKey: .{})", unescape(case)));
+            },
+          }
+        }
+        encode.push(String::from("}"));
+
+        let body = [
+          case_declarations(&cases),
+          private(coding_keys("CodingKeys", &keyed)),
+          method("init(from decoder: Decoder) throws", &decode),
+          method("func encode(to encoder: Encoder) throws", &encode),
+        ];
+        format!("enum {name}{generics}: Codable {{\n{}\n}}", body.join("\n\n"))
+      },
+
+      Layout::Internal { tag, variants } => {
+        let cases = self.cases(&variants, name);
+        let keys = [Field { name: String::from("tag"), key: tag.to_string(), ty: String::new() }];
+
+        let mut decode = vec![
+          String::from("let container = try decoder.container(keyedBy: TagCodingKeys.self)"),
+          String::from("let tag = try container.decode(String.self, forKey: .tag)"),
+          String::from("switch tag {"),
+        ];
+        let mut encode = vec![
+          String::from("var container = encoder.container(keyedBy: TagCodingKeys.self)"),
+          String::from("switch self {"),
+        ];
+
+        for (case, variant, ty) in &cases {
+          decode.push(format!("case {}:", string(variant)));
+          match ty {
+            None => {
+              decode.push(format!("    self = .{case}"));
+              encode.push(format!("case .{case}:"));
+              encode.push(format!("    try container.encode({}, forKey: .tag)", string(variant)));
+            },
+            Some(ty) => {
+              decode.push(format!("    self = .{case}(try {ty}(from: decoder))"));
+              encode.push(format!("case .{case}(let value):"));
+              encode.push(format!("    try container.encode({}, forKey: .tag)", string(variant)));
+              encode.push(String::from("    try value.encode(to: encoder)"));
+            },
+          }
+        }
+
+        decode.push(String::from("default:"));
+        decode.push(format!("    throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: \"unknown variant \\(tag) of {name}\")"));
+        decode.push(String::from("}"));
+        encode.push(String::from("}"));
+
+        let body = [
+          case_declarations(&cases),
+          private(coding_keys("TagCodingKeys", &keys)),
+          method("init(from decoder: Decoder) throws", &decode),
+          method("func encode(to encoder: Encoder) throws", &encode),
+        ];
+        format!("enum {name}{generics}: Codable {{\n{}\n}}", body.join("\n\n"))
+      },
+
+      Layout::Adjacent { tag, content, variants } => {
+        let cases = self.cases(&variants, name);
+        let keys = [
+          Field { name: String::from("tag"), key: tag.to_string(), ty: String::new() },
+          Field { name: String::from("content"), key: content.to_string(), ty: String::new() },
+        ];
+
+        let mut decode = vec![
+          String::from("let container = try decoder.container(keyedBy: CodingKeys.self)"),
+          String::from("let tag = try container.decode(String.self, forKey: .tag)"),
+          String::from("switch tag {"),
+        ];
+        let mut encode = vec![
+          String::from("var container = encoder.container(keyedBy: CodingKeys.self)"),
+          String::from("switch self {"),
+        ];
+
+        for (case, variant, ty) in &cases {
+          decode.push(format!("case {}:", string(variant)));
+          match ty {
+            None => {
+              decode.push(format!("    self = .{case}"));
+              encode.push(format!("case .{case}:"));
+              encode.push(format!("    try container.encode({}, forKey: .tag)", string(variant)));
+            },
+            Some(ty) => {
+              decode.push(format!("    self = .{case}(try container.decode({ty}.self, forKey: .content))"));
+              encode.push(format!("case .{case}(let value):"));
+              encode.push(format!("    try container.encode({}, forKey: .tag)", string(variant)));
+              encode.push(String::from("    try container.encode(value, forKey: .content)"));
+            },
+          }
+        }
+
+        decode.push(String::from("default:"));
+        decode.push(format!("    throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: \"unknown variant \\(tag) of {name}\")"));
+        decode.push(String::from("}"));
+        encode.push(String::from("}"));
+
+        let body = [
+          case_declarations(&cases),
+          private(coding_keys("CodingKeys", &keys)),
+          method("init(from decoder: Decoder) throws", &decode),
+          method("func encode(to encoder: Encoder) throws", &encode),
+        ];
+        format!("enum {name}{generics}: Codable {{\n{}\n}}", body.join("\n\n"))
+      },
+
+      // the first variant that can be decoded is used
+      Layout::Untagged(items) => {
+        let cases = items.iter().enumerate().map(|(i, item)| {
+          (format!("variant{}", i + 1), self.swift_type(item, &format!("{name}Variant{}", i + 1)))
+        }).collect::<Vec<_>>();
+
+        let mut decode = vec![String::from("let container = try decoder.singleValueContainer()")];
+        let mut encode = vec![String::from("var container = encoder.singleValueContainer()"), String::from("switch self {")];
+
+        for (case, ty) in &cases {
+          decode.push(format!("if let value = try? container.decode({ty}.self) {{"));
+          decode.push(format!("    self = .{case}(value)"));
+          decode.push(String::from("    return"));
+          decode.push(String::from("}"));
+          encode.push(format!("case .{case}(let value):"));
+          encode.push(String::from("    try container.encode(value)"));
+        }
+
+        decode.push(format!("throw DecodingError.dataCorruptedError(in: container, debugDescription: \"no variant of {name} matches\")"));
+        encode.push(String::from("}"));
+
+        let body = [
+          cases.iter().map(|(case, ty)| format!("    case {case}({ty})")).collect::<Vec<_>>().join("\n"),
+          method("init(from decoder: Decoder) throws", &decode),
+          method("func encode(to encoder: Encoder) throws", &encode),
+        ];
+        format!("enum {name}{generics}: Codable {{\n{}\n}}", body.join("\n\n"))
+      },
+    };
+
+    self.statements.push(enumeration);
+    format!("{name}{}", reference_params(params))
+  }
+
+  /// The case name, the serialized name and the type of the content of each variant
+  fn cases(&mut self, variants: &[Variant], name: &str) -> Vec<(String, String, Option<String>)> {
+    variants.iter().map(|variant| {
+      let ty = variant.content.as_ref().map(|content| self.swift_type(content, &format!("{name}{}", layout::pascal_case(&variant.name))));
+      (case_name(&variant.name), variant.name.to_string(), ty)
+    }).collect()
+  }
+}
+
+fn case_declarations(cases: &[(String, String, Option<String>)]) -> String {
+  cases.iter().map(|(case, _, ty)| match ty {
+    None => format!("    case {case}"),
+    Some(ty) => format!("    case {case}({ty})"),
+  }).collect::<Vec<_>>().join("\n")
+}
+
+fn coding_keys(name: &str, fields: &[Field]) -> String {
+  let cases = fields.iter().map(|field| {
+    if unescape(&field.name) == field.key {
+      format!("        case {}", field.name)
+    } else {
+      format!("        case {} = {}", field.name, string(&field.key))
+    }
+  }).collect::<Vec<_>>();
+  format!("    enum {name}: String, CodingKey {{\n{}\n    }}", cases.join("\n"))
+}
+
+fn private(declaration: String) -> String {
+  declaration.replacen("    enum", "    private enum", 1)
+}
+
+fn method(signature: &str, lines: &[String]) -> String {
+  let lines = lines.iter().map(|line| format!("        {line}")).collect::<Vec<_>>();
+  format!("    {signature} {{\n{}\n    }}", lines.join("\n"))
+}
+
+fn optional(ty: String) -> String {
+  if ty.ends_with('?') { ty } else { format!("{ty}?") }
+}
+
+fn declaration_params(params: &[String]) -> String {
+  if params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", params.iter().map(|param| format!("{param}: Codable")).collect::<Vec<_>>().join(", "))
+  }
+}
+
+fn reference_params(params: &[String]) -> String {
+  if params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", params.join(", "))
+  }
+}
+
+fn string(value: &str) -> String {
+  serde_json::to_string(value).unwrap()
+}
+
+fn case_name(variant: &str) -> String {
+  identifier(&layout::camel_case(variant))
+}
+
+const KEYWORDS: &[&str] = &[
+  "associatedtype", "class", "deinit", "enum", "extension", "fileprivate", "func", "import", "init", "inout", "internal",
+  "let", "open", "operator", "private", "protocol", "public", "rethrows", "static", "struct", "subscript", "typealias",
+  "var", "break", "case", "continue", "default", "defer", "do", "else", "fallthrough", "for", "guard", "if", "in",
+  "repeat", "return", "switch", "where", "while", "as", "catch", "false", "is", "nil", "super", "self", "Self", "throw",
+  "throws", "true", "try", "Any", "Type",
+];
+
+fn identifier(name: &str) -> String {
+  if KEYWORDS.contains(&name) {
+    format!("`{name}`")
+  } else {
+    name.to_string()
+  }
+}
+
+fn unescape(name: &str) -> &str {
+  name.trim_matches('`')
+}
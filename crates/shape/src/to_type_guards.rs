@@ -0,0 +1,156 @@
+use crate::{Declaration, Declarations, Literal, Record, RecordKey, ToTypescript, Type};
+
+/// TypeScript type guards that accept the same values as [`crate::IsAsignable`]
+/// Eg: `export function isUser(v: unknown): v is User`
+///
+/// Generic declarations take a guard for each parameter Eg: `isPage(v, isUser)`,
+/// custom types without a [`crate::CustomType::type_guard`] or a fallback call a guard that must be declared
+/// next to the generated ones Eg: `isDate(v)`
+pub trait ToTypeGuards {
+  fn to_type_guards(&self) -> String;
+}
+
+/// The helpers used by the guards, [`Declarations`] include them once before the guards
+pub const TYPE_GUARDS_PRELUDE: &str = r#"const isObject = (v: unknown): v is { [key: string]: unknown } => typeof v === "object" && v !== null && !Array.isArray(v);
+const hasProperty = (v: { [key: string]: unknown }, key: string, check: (v: unknown) => boolean): boolean => Object.prototype.hasOwnProperty.call(v, key) && check(v[key]);
+const optionalProperty = (v: { [key: string]: unknown }, key: string, check: (v: unknown) => boolean): boolean => !Object.prototype.hasOwnProperty.call(v, key) || check(v[key]);
+// the keys that serde_json reads as a number, finite JSON numbers
+const isNumberKey = (k: string): boolean => /^-?(0|[1-9]\d*)(\.\d+)?([eE][+-]?\d+)?$/.test(k) && Number.isFinite(Number(k));
+// the keys that serde_json writes for integers
+const isIntegerKey = (k: string): boolean => /^-?(0|[1-9]\d*)$/.test(k);"#;
+
+impl ToTypeGuards for Declaration {
+  fn to_type_guards(&self) -> String {
+    let name = &self.name;
+    let mut params = vec![String::from("v: unknown")];
+    params.extend(self.params.iter().map(|param| format!("is{param}: (v: unknown) => v is {param}")));
+
+    let generics = if self.params.is_empty() { String::new() } else { format!("<{}>", self.params.join(", ")) };
+
+    format!(
+      "export function is{name}{generics}({}): v is {name}{generics} {{\n  return {};\n}}",
+      params.join(", "),
+      guard(&self.ty, "v", 0),
+    )
+  }
+}
+
+/// The declarations from [`ToTypescript`], each followed by its guard
+impl ToTypeGuards for Declarations {
+  fn to_type_guards(&self) -> String {
+    let mut out = vec![TYPE_GUARDS_PRELUDE.to_string()];
+    for declaration in self {
+      out.push(format!("{}\n\n{}", declaration.to_typescript(), declaration.to_type_guards()));
+    }
+    out.join("\n\n")
+  }
+}
+
+/// `v` is an identifier or an element of an array, `depth` names the parameters of nested callbacks
+fn guard(ty: &Type, v: &str, depth: usize) -> String {
+  let item = format!("v{}", depth + 1);
+  match ty {
+    Type::String => format!("typeof {v} === \"string\""),
+    Type::Number => format!("typeof {v} === \"number\""),
+    Type::Boolean => format!("typeof {v} === \"boolean\""),
+    Type::Bytes => format!("{v} instanceof Uint8Array"),
+    Type::Null => format!("{v} === null"),
+    Type::Undefined => format!("{v} === undefined"),
+    Type::Never => String::from("false"),
+    Type::Unknown => String::from("true"),
+    Type::Literal(literal) => format!("{v} === {}", literal_value(literal)),
+    Type::Tuple(tuple) => {
+      let mut checks = vec![format!("Array.isArray({v})")];
+      for (i, ty) in tuple.items.iter().enumerate() {
+        checks.push(guard(ty, &format!("{v}[{i}]"), depth));
+      }
+      if let Some(rest) = &tuple.rest {
+        checks.push(format!("{v}.slice({}).every(({item}) => {})", tuple.items.len(), guard(rest, &item, depth + 1)));
+      }
+      format!("({})", checks.join(" && "))
+    },
+    Type::Array(array) => format!("(Array.isArray({v}) && {v}.every(({item}) => {}))", guard(&array.item, &item, depth + 1)),
+    Type::Object(object) => {
+      let mut checks = vec![format!("isObject({v})")];
+      for (key, property) in &object.properties {
+        let helper = if property.optional { "optionalProperty" } else { "hasProperty" };
+        let key = serde_json::to_string(key).unwrap();
+        checks.push(format!("{helper}({v}, {key}, ({item}) => {})", guard(&property.ty, &item, depth + 1)));
+      }
+      format!("({})", checks.join(" && "))
+    },
+    Type::Record(record) => self::record(record, v, depth),
+    Type::And(items) if items.is_empty() => String::from("true"),
+    Type::And(items) => format!("({})", items.iter().map(|ty| guard(ty, v, depth)).collect::<Vec<_>>().join(" && ")),
+    Type::Or(items) if items.is_empty() => String::from("false"),
+    Type::Or(items) => format!("({})", items.iter().map(|ty| guard(ty, v, depth)).collect::<Vec<_>>().join(" || ")),
+    Type::Custom(custom) => match (custom.type_guard(v), custom.fallback()) {
+      (Some(guard), _) => format!("({guard})"),
+      (None, Some(fallback)) => guard(fallback, v, depth),
+      (None, None) => format!("is{}({v})", custom.name()),
+    },
+    Type::Param(param) => format!("is{param}({v})"),
+    Type::Apply(apply) => {
+      let mut args = vec![v.to_string()];
+      for arg in &apply.args {
+        args.push(match &**arg {
+          Type::Apply(apply) if apply.args.is_empty() => format!("is{}", apply.name),
+          Type::Param(param) => format!("is{param}"),
+          arg => format!("({item}: unknown): {item} is {} => {}", arg.to_typescript(), guard(arg, &item, depth + 1)),
+        });
+      }
+      format!("is{}({})", apply.name, args.join(", "))
+    },
+  }
+}
+
+fn literal_value(literal: &Literal) -> String {
+  match literal {
+    Literal::String(value) => serde_json::to_string(value).unwrap(),
+    Literal::Boolean(value) => value.to_string(),
+    Literal::Number(value) if value.is_nan() => String::from("NaN"),
+    Literal::Number(value) if value.is_infinite() => String::from(if *value > 0.0 { "Infinity" } else { "-Infinity" }),
+    Literal::Number(value) => value.to_string(),
+  }
+}
+
+fn record(record: &Record, v: &str, depth: usize) -> String {
+  let item = format!("v{}", depth + 1);
+  let check = guard(&record.value, &item, depth + 1);
+  let key = match &record.key {
+    RecordKey::Type(key) if !record.optional => return format!("(isObject({v}) && {})", record_key(key, &record.value, v, depth)),
+    RecordKey::Unsupported(_) => return format!("(isObject({v}) && Object.keys({v}).length === 0)"),
+    RecordKey::String => return format!("(isObject({v}) && Object.values({v}).every(({item}) => {check}))"),
+    RecordKey::Number => String::from("isNumberKey(k)"),
+    RecordKey::Integer => String::from("isIntegerKey(k)"),
+    RecordKey::Boolean => String::from("(k === \"true\" || k === \"false\")"),
+    // the present keys of an optional record must be keys of the record
+    RecordKey::Type(key) => guard(key, "(k as unknown)", depth + 1),
+  };
+  format!("(isObject({v}) && Object.entries({v}).every(([k, {item}]) => {key} && {check}))")
+}
+
+/// The check of the entries of a record by the type of its keys
+fn record_key(key: &Type, value: &Type, v: &str, depth: usize) -> String {
+  let item = format!("v{}", depth + 1);
+  let check = guard(value, &item, depth + 1);
+  match key {
+    Type::String => format!("Object.values({v}).every(({item}) => {check})"),
+    Type::Number => format!("Object.entries({v}).every(([k, {item}]) => isNumberKey(k) && {check})"),
+    Type::And(keys) => {
+      // the key is widened, typescript rejects comparisons of a string with other literals
+      let keys = keys.iter().map(|key| guard(key, "(k as unknown)", depth + 1)).collect::<Vec<_>>();
+      let keys = if keys.is_empty() { String::from("true") } else { keys.join(" && ") };
+      format!("Object.entries({v}).every(([k, {item}]) => !({keys}) || {check})")
+    },
+    Type::Or(keys) if keys.is_empty() => String::from("true"),
+    Type::Or(keys) => format!("({})", keys.iter().map(|key| record_key(key, value, v, depth)).collect::<Vec<_>>().join(" && ")),
+    Type::Literal(Literal::String(key)) => {
+      format!("hasProperty({v}, {}, ({item}) => {check})", serde_json::to_string(key).unwrap())
+    },
+    Type::Literal(Literal::Number(key)) => {
+      format!("Object.entries({v}).some(([k, {item}]) => isNumberKey(k) && Number(k) === {} && {check})", literal_value(&Literal::Number(*key)))
+    },
+    _ => String::from("false"),
+  }
+}
@@ -1,4 +1,4 @@
-use crate::{Array, Literal, Object, Record, Tuple, Type};
+use crate::{Apply, Array, Declaration, Declarations, Literal, Object, Record, RecordKey, Tuple, Type};
 
 pub trait ToTypescript {
   fn to_typescript(&self) -> String;
@@ -32,7 +32,7 @@ impl ToTypescript for Object {
             {
                quote!(key)
             } else {
-              String::from(key)
+              key.to_string()
             }
           }
         }
@@ -54,11 +54,19 @@ impl ToTypescript for Object {
 
 impl ToTypescript for Record {
   fn to_typescript(&self) -> String {
-    format!(
-      "{{ [key: {key}]: {value} }}",
-      key = self.key.to_typescript(),
-      value = self.value.to_typescript()
-    )
+    let value = self.value.to_typescript();
+    let optional = if self.optional { "?" } else { "" };
+    match &self.key {
+      RecordKey::String => format!("{{ [key: string]: {value} }}"),
+      RecordKey::Number | RecordKey::Integer => format!("{{ [key: number]: {value} }}"),
+      // literal keys need a mapped type
+      RecordKey::Boolean => format!("{{ [key in \"true\" | \"false\"]?: {value} }}"),
+      RecordKey::Type(key) if self.key.is_variants() => format!("{{ [key in {}]{optional}: {value} }}", key.to_typescript()),
+      // index signatures only accept string and number keys, Eg: a generic parameter or a declared enum
+      RecordKey::Type(key) => format!("{{ [key in {}]?: {value} }}", key.to_typescript()),
+      // only empty maps can be serialized
+      RecordKey::Unsupported(_) => format!("{{ [key in never]: {value} }}"),
+    }
   }
 }
 
@@ -83,11 +91,13 @@ impl ToTypescript for Type {
   fn to_typescript(&self) -> String {
     match self {
       Type::String => String::from("string"),
+      Type::Bytes => String::from("Uint8Array"),
       Type::Number => String::from("number"),
       Type::Boolean => String::from("boolean"),
       Type::Null => String::from("null"),
       Type::Undefined => String::from("undefined"),
       Type::Never => String::from("never"),
+      Type::Unknown => String::from("unknown"),
       Type::Literal(literal) => literal.to_typescript(),
       Type::Tuple(tuple) => tuple.to_typescript(),
       Type::Array(array) => array.to_typescript(),
@@ -107,7 +117,37 @@ impl ToTypescript for Type {
         let inner = types.iter().map(|t| t.to_typescript()).collect::<Vec<String>>().join(" | ");
         format!("({})", inner)
       }
-      Type::Custom(custom) => custom.clone(),
+      Type::Custom(custom) => custom.typescript(),
+      Type::Param(name) => name.to_string(),
+      Type::Apply(apply) => apply.to_typescript(),
     }
   }
+}
+
+impl ToTypescript for Apply {
+  fn to_typescript(&self) -> String {
+    if self.args.is_empty() {
+      self.name.to_string()
+    } else {
+      let args = self.args.iter().map(|t| t.to_typescript()).collect::<Vec<String>>().join(", ");
+      format!("{}<{}>", self.name, args)
+    }
+  }
+}
+
+impl ToTypescript for Declaration {
+  fn to_typescript(&self) -> String {
+    let params = if self.params.is_empty() {
+      String::new()
+    } else {
+      format!("<{}>", self.params.join(", "))
+    };
+    format!("export type {}{} = {};", self.name, params, self.ty.to_typescript())
+  }
+}
+
+impl ToTypescript for Declarations {
+  fn to_typescript(&self) -> String {
+    self.iter().map(|d| d.to_typescript()).collect::<Vec<String>>().join("\n\n")
+  }
 }
\ No newline at end of file
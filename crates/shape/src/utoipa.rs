@@ -0,0 +1,223 @@
+//! Adapters between [`Shape`] and [`::utoipa::ToSchema`], enabled with the `utoipa` feature
+//!
+//! ```ignore
+//! #[derive(Shape)]
+//! struct User {
+//!   #[shape(with = "shape::utoipa::Utoipa<Address>")]
+//!   address: Address,
+//! }
+//!
+//! #[derive(ToSchema)]
+//! struct Order {
+//!   #[schema(schema_with = shape::utoipa::schema::<User>)]
+//!   user: User,
+//! }
+//! ```
+
+use std::marker::PhantomData;
+
+use ::utoipa::openapi::schema::{
+  self, AdditionalProperties, AllOfBuilder, AnyOfBuilder, ArrayBuilder, ArrayItems, ObjectBuilder, Ref, Schema, SchemaType,
+};
+use ::utoipa::openapi::RefOr;
+use ::utoipa::ToSchema;
+use serde_json::{json, Map, Value};
+
+use crate::{
+  layout, parse_json_schema_declarations, Apply, Declarations, JsonSchemaError, Literal, Object, RecordKey, Shape, ShapeOptions, ToTypescript, Type,
+};
+
+/// An OpenAPI 3.1 schema of the values of a shape
+///
+/// References are schemas of the components with the name of the declaration, generic instances
+/// are named like in TypeScript Eg: `Page<User>`
+pub trait ToUtoipa {
+  fn to_utoipa(&self) -> RefOr<Schema>;
+}
+
+impl ToUtoipa for Type {
+  fn to_utoipa(&self) -> RefOr<Schema> {
+    to_schema(self)
+  }
+}
+
+/// The shape of a type that implements [`ToSchema`], Eg: `#[shape(with = "shape::utoipa::Utoipa<Address>")]`
+///
+/// The component schemas are declarations, a schema that can't be parsed is [`Type::Unknown`], see [`parse_utoipa`]
+pub struct Utoipa<T: ?Sized>(PhantomData<T>);
+
+impl<T: ToSchema + ?Sized> Shape for Utoipa<T> {
+  fn shape(options: &ShapeOptions) -> Type {
+    match generate::<T>(options) {
+      Ok((ty, _)) if options.use_declarations => ty,
+      Ok((ty, declarations)) => declarations.expand(&ty),
+      Err(_) => Type::Unknown,
+    }
+  }
+
+  fn declare(options: &ShapeOptions, declarations: &mut Declarations) {
+    if let Ok((_, schemas)) = generate::<T>(options) {
+      for declaration in &schemas {
+        if !declarations.contains(&declaration.name) {
+          declarations.insert(declaration.clone());
+        }
+      }
+    }
+  }
+}
+
+/// The shape of the schema of `T`, OpenAPI has the same schema for the serialization and the deserialization
+pub fn parse_utoipa<T: ToSchema + ?Sized>(options: &ShapeOptions) -> Result<Type, JsonSchemaError> {
+  let (ty, declarations) = generate::<T>(options)?;
+  Ok(declarations.expand(&ty))
+}
+
+/// A reference to `T` and the declarations of the component schemas
+fn generate<T: ToSchema + ?Sized>(options: &ShapeOptions) -> Result<(Type, Declarations), JsonSchemaError> {
+  let mut schemas = vec![(T::name().into_owned(), T::schema())];
+  T::schemas(&mut schemas);
+
+  let mut definitions = Map::new();
+  for (name, schema) in schemas {
+    let mut schema = serde_json::to_value(schema).unwrap_or(Value::Bool(true));
+    components_to_defs(&mut schema);
+    definitions.entry(name).or_insert(schema);
+  }
+
+  let mut declarations = Declarations::new(options.clone());
+  for declaration in parse_json_schema_declarations(&json!({ "$defs": definitions }))? {
+    declarations.insert(declaration);
+  }
+
+  Ok((Type::Apply(Apply { name: T::name().into_owned().into(), args: vec![] }), declarations))
+}
+
+/// `#/components/schemas/Name` references are `#/$defs/Name` references in JSON Schema
+fn components_to_defs(value: &mut Value) {
+  match value {
+    Value::Object(map) => {
+      for (key, value) in map.iter_mut() {
+        match value {
+          Value::String(reference) if key == "$ref" => {
+            if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+              *reference = format!("#/$defs/{name}");
+            }
+          },
+          value => components_to_defs(value),
+        }
+      }
+    },
+    Value::Array(values) => values.iter_mut().for_each(components_to_defs),
+    _ => {},
+  }
+}
+
+/// The schema of a type that implements [`Shape`], Eg: `#[schema(schema_with = shape::utoipa::schema::<User>)]`
+///
+/// The shape is the one of the serialization and derived types are inlined
+pub fn schema<T: Shape + ?Sized>() -> RefOr<Schema> {
+  T::shape(&ShapeOptions::for_serialize()).to_utoipa()
+}
+
+fn any() -> ObjectBuilder {
+  ObjectBuilder::new().schema_type(SchemaType::AnyValue)
+}
+
+fn typed(ty: schema::Type) -> ObjectBuilder {
+  ObjectBuilder::new().schema_type(ty)
+}
+
+fn to_schema(ty: &Type) -> RefOr<Schema> {
+  match ty {
+    Type::Null => typed(schema::Type::Null).into(),
+    Type::Undefined | Type::Unknown | Type::Param(_) => any().into(),
+    Type::Never => any().enum_values(Some(Vec::<Value>::new())).into(),
+    Type::Boolean => typed(schema::Type::Boolean).into(),
+    Type::Number => typed(schema::Type::Number).into(),
+    Type::String => typed(schema::Type::String).into(),
+    Type::Bytes => ArrayBuilder::new().items(typed(schema::Type::Integer).minimum(Some(0)).maximum(Some(255))).into(),
+    Type::Literal(literal) => literals(std::slice::from_ref(literal)).into(),
+    Type::Tuple(tuple) => {
+      let items = match &tuple.rest {
+        Some(rest) => ArrayItems::from(to_schema(rest)),
+        None => ArrayItems::False,
+      };
+      ArrayBuilder::new()
+        .prefix_items(tuple.items.iter().map(|item| to_schema(item)))
+        .items(items)
+        .min_items(Some(tuple.items.len()))
+        .into()
+    },
+    Type::Array(array) => ArrayBuilder::new().items(to_schema(&array.item)).into(),
+    Type::Object(object) => properties(object).into(),
+    Type::Record(record) => {
+      let names = match &record.key {
+        RecordKey::String => None,
+        RecordKey::Number => Some(typed(schema::Type::String).pattern(Some("^-?[0-9]+(\\.[0-9]+)?$")).into()),
+        RecordKey::Integer => Some(typed(schema::Type::String).pattern(Some("^-?[0-9]+$")).into()),
+        RecordKey::Boolean => Some(typed(schema::Type::String).enum_values(Some(["true", "false"])).into()),
+        RecordKey::Type(key) => Some(Schema::from(to_schema(key))),
+        // a map with keys that can't be serialized is always empty
+        RecordKey::Unsupported(_) => Some(any().enum_values(Some(Vec::<Value>::new())).into()),
+      };
+      typed(schema::Type::Object)
+        .additional_properties(Some(AdditionalProperties::RefOr(to_schema(&record.value))))
+        .property_names(names)
+        .into()
+    },
+    Type::Or(items) => {
+      let values = items
+        .iter()
+        .map(|item| match &**item {
+          Type::Literal(literal) => Some(literal.clone()),
+          _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+      match values {
+        Some(values) => literals(&values).into(),
+        None => items.iter().fold(AnyOfBuilder::new(), |any_of, item| any_of.item(to_schema(item))).into(),
+      }
+    },
+    Type::And(items) => items.iter().fold(AllOfBuilder::new(), |all_of, item| all_of.item(to_schema(item))).into(),
+    Type::Custom(custom) => {
+      let json_schema = custom.json_schema().and_then(|value| serde_json::from_value::<Schema>(value).ok());
+      match (json_schema, custom.fallback()) {
+        (Some(json_schema), _) => RefOr::T(json_schema),
+        (None, Some(fallback)) => to_schema(fallback),
+        (None, None) => any().into(),
+      }
+    },
+    Type::Apply(apply) => {
+      // generic instances use the typescript syntax Eg: `Page<User>`
+      let name = if apply.args.is_empty() { apply.name.to_string() } else { ty.to_typescript() };
+      Ref::from_schema_name(name).into()
+    },
+  }
+}
+
+fn literals(literals: &[Literal]) -> ObjectBuilder {
+  let values = literals.iter().map(|literal| match literal {
+    Literal::Boolean(value) => json!(value),
+    Literal::Number(value) => json!(value),
+    Literal::String(value) => json!(value),
+  });
+  any().enum_values(Some(values))
+}
+
+fn properties(object: &Object) -> ObjectBuilder {
+  let mut builder = typed(schema::Type::Object);
+  for (key, property) in &object.properties {
+    let (ty, undefined) = layout::strip_undefined(&property.ty);
+    let mut schema = to_schema(&ty);
+    if let RefOr::T(Schema::Object(object)) = &mut schema {
+      if property.readonly {
+        object.read_only = Some(true);
+      }
+    }
+    builder = builder.property(key.as_ref(), schema);
+    if !property.optional && !undefined {
+      builder = builder.required(key.as_ref());
+    }
+  }
+  builder
+}
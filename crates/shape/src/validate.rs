@@ -0,0 +1,791 @@
+use std::{
+  cell::{Cell, RefCell},
+  fmt,
+  io::{self, BufRead, Read},
+  marker::PhantomData,
+  mem,
+};
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::check::{self, conjunction, is_wide, Alt, Map, Seq};
+use crate::{IsAsignable, Type};
+
+/// A [`Deserializer`] that checks that the input is assignable to a type while it is deserialized,
+/// without buffering it in a [`serde_json::Value`]
+///
+/// Primitives are checked before they reach the visitor, arrays and objects when they end,
+/// a type with more than [`crate::MAX_ALTERNATIVES`] alternatives is checked on a buffered [`serde_json::Value`]
+///
+/// The errors have the JSON pointer of the value in their message, Eg: `the value at "/items/0" failed: ...`
+pub struct Validator<'t, D> {
+  inner: D,
+  ty: &'t Type,
+}
+
+impl<'t, D> Validator<'t, D> {
+  pub fn new(inner: D, ty: &'t Type) -> Self {
+    Self { inner, ty }
+  }
+}
+
+/// A [`DeserializeSeed`] that deserializes `seed` through a [`Validator`]
+/// Eg: `Validate::new(&ty, PhantomData::<User>)` or `PhantomData::<IgnoredAny>` to only validate
+pub struct Validate<'t, S> {
+  ty: &'t Type,
+  seed: S,
+}
+
+impl<'t, S> Validate<'t, S> {
+  pub fn new(ty: &'t Type, seed: S) -> Self {
+    Self { ty, seed }
+  }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Validate<'_, S> {
+  type Value = S::Value;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    self.seed.deserialize(Validator::new(deserializer, self.ty))
+  }
+}
+
+/// Deserialize JSON from a reader, checking that it is assignable to `ty`
+///
+/// The reader is read a byte at a time, wrap it in a [`std::io::BufReader`] if it is not buffered
+pub fn validate_reader<R: Read, T: DeserializeOwned>(reader: R, ty: &Type) -> Result<T, ValidateError> {
+  let mut counter = Counter { inner: reader, offset: 0, lines: 0, starts: [0, 0] };
+  let read = serde_json::de::IoRead::new(&mut counter);
+  let result = if is_wide(ty) {
+    parse_wide(read, ty).and_then(|value| T::deserialize(value).map_err(Failure::json))
+  } else {
+    deserialize(read, ty)
+  };
+  result.map_err(|failure| failure.into_error(|line, column| counter.position(line, column)))
+}
+
+/// Like [`validate_reader`], the value can borrow from `bytes`
+pub fn validate_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8], ty: &Type) -> Result<T, ValidateError> {
+  deserialize_slice(bytes, ty).map_err(|failure| failure.into_error(|line, column| position(bytes, line, column)))
+}
+
+/// Deserialize newline delimited JSON, checking that each line is assignable to `ty`
+///
+/// An invalid line doesn't stop the iterator, blank lines are skipped
+pub fn validate_ndjson<R: BufRead, T: DeserializeOwned>(reader: R, ty: &Type) -> Ndjson<'_, R, T> {
+  Ndjson { reader, ty, offset: 0, line: vec![], done: false, marker: PhantomData }
+}
+
+pub struct Ndjson<'t, R, T> {
+  reader: R,
+  ty: &'t Type,
+  offset: usize,
+  line: Vec<u8>,
+  done: bool,
+  marker: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for Ndjson<'_, R, T> {
+  type Item = Result<T, ValidateError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while !self.done {
+      self.line.clear();
+      let start = self.offset;
+      match self.reader.read_until(b'\n', &mut self.line) {
+        Ok(0) => self.done = true,
+        Ok(len) => {
+          self.offset += len;
+          if self.line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+          }
+          let line = &self.line;
+          let result = deserialize_slice(line, self.ty);
+          return Some(result.map_err(|failure| failure.into_error(|l, column| start + position(line, l, column))));
+        },
+        Err(error) => {
+          self.done = true;
+          return Some(Err(ValidateError { pointer: String::new(), offset: start, kind: ValidateErrorKind::Json(serde_json::Error::io(error)) }));
+        },
+      }
+    }
+    None
+  }
+}
+
+#[derive(Debug)]
+pub struct ValidateError {
+  /// the JSON pointer of the value that failed, Eg: `/items/2/name`
+  pub pointer: String,
+  /// the offset of the last byte read when the error was found, readers peek the byte after a number
+  pub offset: usize,
+  pub kind: ValidateErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ValidateErrorKind {
+  /// the value is not assignable to the type
+  Mismatch,
+  /// the input is not valid JSON or the value failed to deserialize
+  Json(serde_json::Error),
+}
+
+impl fmt::Display for ValidateError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "\"{}\" at byte {}: {}", self.pointer, self.offset, self.kind)
+  }
+}
+
+impl fmt::Display for ValidateErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Mismatch => write!(f, "the value is not assignable to the type"),
+      Self::Json(error) => write!(f, "{error}"),
+    }
+  }
+}
+
+impl std::error::Error for ValidateError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match &self.kind {
+      ValidateErrorKind::Mismatch => None,
+      ValidateErrorKind::Json(error) => Some(error),
+    }
+  }
+}
+
+/// A failed document before the offset of the error is known
+struct Failure {
+  error: serde_json::Error,
+  pointer: String,
+  mismatch: bool,
+}
+
+impl Failure {
+  fn json(error: serde_json::Error) -> Self {
+    Self { error, pointer: String::new(), mismatch: false }
+  }
+
+  fn into_error(self, position: impl FnOnce(usize, usize) -> usize) -> ValidateError {
+    let offset = position(self.error.line(), self.error.column());
+    let kind = if self.mismatch { ValidateErrorKind::Mismatch } else { ValidateErrorKind::Json(self.error) };
+    ValidateError { pointer: self.pointer, offset, kind }
+  }
+}
+
+fn deserialize<'de, R: serde_json::de::Read<'de>, T: Deserialize<'de>>(read: R, ty: &Type) -> Result<T, Failure> {
+  let ctx = Context::default();
+  let mut deserializer = serde_json::Deserializer::new(read);
+  let result = T::deserialize(Wrap { inner: &mut deserializer, alts: conjunction(&[ty]), ctx: &ctx });
+  result.and_then(|value| deserializer.end().map(|()| value)).map_err(|error| Failure {
+    error,
+    pointer: ctx.pointer(),
+    mismatch: ctx.mismatch.get(),
+  })
+}
+
+fn deserialize_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8], ty: &Type) -> Result<T, Failure> {
+  if !is_wide(ty) {
+    return deserialize(serde_json::de::SliceRead::new(bytes), ty);
+  }
+  // the document is deserialized again so the value can borrow from it
+  parse_wide(serde_json::de::SliceRead::new(bytes), ty)?;
+  deserialize(serde_json::de::SliceRead::new(bytes), &Type::Unknown)
+}
+
+/// The document of a type with too many alternatives to check it while it is deserialized,
+/// the mismatch has no pointer
+fn parse_wide<'de, R: serde_json::de::Read<'de>>(read: R, ty: &Type) -> Result<Value, Failure> {
+  let value = deserialize(read, &Type::Unknown)?;
+  if ty.is_assignable(&value) {
+    Ok(value)
+  } else {
+    Err(Failure { error: Context::default().mismatch(), pointer: String::new(), mismatch: true })
+  }
+}
+
+/// The offset of a line and column of serde_json in `bytes`
+fn position(bytes: &[u8], line: usize, column: usize) -> usize {
+  let start = match line {
+    0 => return bytes.len(),
+    1 => 0,
+    line => bytes.iter().enumerate().filter(|(_, byte)| **byte == b'\n').nth(line - 2).map_or(bytes.len(), |(i, _)| i + 1),
+  };
+  (start + column).saturating_sub(1)
+}
+
+/// Counts the bytes of a reader and remembers where the last two lines start,
+/// serde_json peeks at most one byte past its position
+struct Counter<R> {
+  inner: R,
+  offset: usize,
+  lines: usize,
+  starts: [usize; 2],
+}
+
+impl<R: Read> Read for Counter<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let len = self.inner.read(buf)?;
+    for (i, byte) in buf[..len].iter().enumerate() {
+      if *byte == b'\n' {
+        self.lines += 1;
+        self.starts = [self.starts[1], self.offset + i + 1];
+      }
+    }
+    self.offset += len;
+    Ok(len)
+  }
+}
+
+impl<R> Counter<R> {
+  fn position(&self, line: usize, column: usize) -> usize {
+    let start = if line == self.lines + 1 {
+      self.starts[1]
+    } else if line == self.lines {
+      self.starts[0]
+    } else {
+      return self.offset;
+    };
+    (start + column).saturating_sub(1)
+  }
+}
+
+/// The state shared by the wrappers of a document
+#[derive(Default)]
+struct Context {
+  /// the path of the value being deserialized, it is not popped on errors
+  path: RefCell<Vec<String>>,
+  /// whether each alternative accepts the last value
+  results: RefCell<Option<Vec<bool>>>,
+  /// the last map key or variant
+  key: RefCell<Option<String>>,
+  mismatch: Cell<bool>,
+}
+
+impl Context {
+  fn pointer(&self) -> String {
+    self.path.borrow().iter().map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1"))).collect()
+  }
+
+  fn mismatch<E: de::Error>(&self) -> E {
+    self.mismatch.set(true);
+    E::custom(format_args!("the value at \"{}\" is not assignable to the type", self.pointer()))
+  }
+
+  /// An error of the inner deserializer or the visitor with the pointer of the value, a mismatch already has it
+  fn locate<E: de::Error>(&self, error: E) -> E {
+    if self.mismatch.get() || self.path.borrow().is_empty() {
+      return error;
+    }
+    E::custom(format_args!("the value at \"{}\" failed: {error}", self.pointer()))
+  }
+
+  fn accept<E: de::Error>(&self, results: Vec<bool>) -> Result<(), E> {
+    if !results.iter().any(|ok| *ok) {
+      return Err(self.mismatch());
+    }
+    *self.results.borrow_mut() = Some(results);
+    Ok(())
+  }
+
+  /// The results of the last value, a value that wasn't visited is rejected
+  fn take_results(&self) -> Vec<bool> {
+    self.results.take().unwrap_or_default()
+  }
+
+  fn push(&self, segment: String) {
+    self.path.borrow_mut().push(segment);
+  }
+
+  fn pop(&self) {
+    self.path.borrow_mut().pop();
+  }
+}
+
+/// Forwards the `deserialize_*` methods of a [`Deserializer`] to `$target` with the visitor `$wrapped`
+macro_rules! forward {
+  ($this:ident, $visitor:ident => $target:expr, $wrapped:expr; $($method:ident($($arg:ident: $ty:ty),*))*) => {
+    $(
+      fn $method<V: Visitor<'de>>($this, $($arg: $ty,)* $visitor: V) -> Result<V::Value, Self::Error> {
+        $target.$method($($arg,)* $wrapped)
+      }
+    )*
+  };
+}
+
+/// Like [`forward!`], a type with too many alternatives is checked on a buffered [`Value`]
+macro_rules! forward_validator {
+  ($($method:ident($($arg:ident: $ty:ty),*))*) => {
+    $(
+      fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+        if is_wide(self.ty) {
+          let value = Value::deserialize(self.inner)?;
+          if !self.ty.is_assignable(&value) {
+            return Err(Context::default().mismatch());
+          }
+          return value.$method($($arg,)* visitor).map_err(de::Error::custom);
+        }
+        let ctx = Context::default();
+        let result = Wrap { inner: self.inner, alts: conjunction(&[self.ty]), ctx: &ctx }.$method($($arg,)* visitor);
+        result.map_err(|error| ctx.locate(error))
+      }
+    )*
+  };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Validator<'_, D> {
+  type Error = D::Error;
+
+  forward_validator! {
+    deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64()
+    deserialize_i128() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+    deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string() deserialize_bytes()
+    deserialize_byte_buf() deserialize_option() deserialize_unit() deserialize_unit_struct(name: &'static str)
+    deserialize_newtype_struct(name: &'static str) deserialize_seq() deserialize_tuple(len: usize)
+    deserialize_tuple_struct(name: &'static str, len: usize) deserialize_map()
+    deserialize_struct(name: &'static str, fields: &'static [&'static str])
+    deserialize_enum(name: &'static str, variants: &'static [&'static str]) deserialize_identifier()
+    deserialize_ignored_any()
+  }
+
+  fn is_human_readable(&self) -> bool {
+    self.inner.is_human_readable()
+  }
+}
+
+/// A value that must be accepted by one of `alts`
+struct Wrap<'c, 't, D> {
+  inner: D,
+  alts: Vec<Alt<'t>>,
+  ctx: &'c Context,
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Wrap<'_, '_, D> {
+  type Error = D::Error;
+
+  forward! {
+    self, visitor => self.inner, WrapVisitor { visitor, alts: self.alts, ctx: self.ctx };
+    deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64()
+    deserialize_i128() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+    deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string() deserialize_bytes()
+    deserialize_byte_buf() deserialize_option() deserialize_unit() deserialize_unit_struct(name: &'static str)
+    deserialize_newtype_struct(name: &'static str) deserialize_seq() deserialize_tuple(len: usize)
+    deserialize_tuple_struct(name: &'static str, len: usize) deserialize_map()
+    deserialize_struct(name: &'static str, fields: &'static [&'static str])
+    deserialize_enum(name: &'static str, variants: &'static [&'static str]) deserialize_identifier()
+  }
+
+  // serde_json skips ignored values without visiting them, Eg: unknown fields
+  fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.inner.deserialize_any(WrapVisitor { visitor, alts: self.alts, ctx: self.ctx })
+  }
+
+  fn is_human_readable(&self) -> bool {
+    self.inner.is_human_readable()
+  }
+}
+
+struct WrapSeed<'c, 't, S> {
+  seed: S,
+  alts: Vec<Alt<'t>>,
+  ctx: &'c Context,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for WrapSeed<'_, '_, S> {
+  type Value = S::Value;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    self.seed.deserialize(Wrap { inner: deserializer, alts: self.alts, ctx: self.ctx })
+  }
+}
+
+struct WrapVisitor<'c, 't, V> {
+  visitor: V,
+  alts: Vec<Alt<'t>>,
+  ctx: &'c Context,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for WrapVisitor<'_, '_, V> {
+  type Value = V::Value;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.visitor.expecting(f)
+  }
+
+  fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+    self.ctx.accept(check::boolean(&self.alts, v))?;
+    self.visitor.visit_bool(v)
+  }
+
+  fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+    self.ctx.accept(check::number(&self.alts, v as f64))?;
+    self.visitor.visit_i64(v)
+  }
+
+  fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+    self.ctx.accept(check::number(&self.alts, v as f64))?;
+    self.visitor.visit_i128(v)
+  }
+
+  fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+    self.ctx.accept(check::number(&self.alts, v as f64))?;
+    self.visitor.visit_u64(v)
+  }
+
+  fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+    self.ctx.accept(check::number(&self.alts, v as f64))?;
+    self.visitor.visit_u128(v)
+  }
+
+  fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+    self.ctx.accept(check::number(&self.alts, v))?;
+    self.visitor.visit_f64(v)
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+    self.ctx.accept(check::string(&self.alts, v))?;
+    self.visitor.visit_str(v)
+  }
+
+  fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+    self.ctx.accept(check::string(&self.alts, v))?;
+    self.visitor.visit_borrowed_str(v)
+  }
+
+  fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+    self.ctx.accept(check::string(&self.alts, &v))?;
+    self.visitor.visit_string(v)
+  }
+
+  // serde_json visits strings as bytes when the visitor asks for bytes
+  fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    self.ctx.accept(check::string(&self.alts, &String::from_utf8_lossy(v)))?;
+    self.visitor.visit_bytes(v)
+  }
+
+  fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+    self.ctx.accept(check::string(&self.alts, &String::from_utf8_lossy(v)))?;
+    self.visitor.visit_borrowed_bytes(v)
+  }
+
+  fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+    self.ctx.accept(check::string(&self.alts, &String::from_utf8_lossy(&v)))?;
+    self.visitor.visit_byte_buf(v)
+  }
+
+  fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+    self.ctx.accept(check::null(&self.alts))?;
+    self.visitor.visit_none()
+  }
+
+  fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+    self.ctx.accept(check::null(&self.alts))?;
+    self.visitor.visit_unit()
+  }
+
+  fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    self.visitor.visit_some(Wrap { inner: deserializer, alts: self.alts, ctx: self.ctx })
+  }
+
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    self.visitor.visit_newtype_struct(Wrap { inner: deserializer, alts: self.alts, ctx: self.ctx })
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+    let mut state = Seq::new(self.alts);
+    if !state.is_alive() {
+      return Err(self.ctx.mismatch());
+    }
+    let value = self.visitor.visit_seq(WrapSeq { inner: seq, state: &mut state, index: 0, ctx: self.ctx })?;
+    self.ctx.accept(state.finish())?;
+    Ok(value)
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+    let mut state = Map::new(self.alts);
+    if !state.is_alive() {
+      return Err(self.ctx.mismatch());
+    }
+    let value = self.visitor.visit_map(WrapMap { inner: map, state: &mut state, alts: vec![], ctx: self.ctx })?;
+    self.ctx.accept(state.finish())?;
+    Ok(value)
+  }
+
+  fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+    self.visitor.visit_enum(WrapEnum { inner: data, alts: self.alts, ctx: self.ctx })
+  }
+}
+
+struct WrapSeq<'s, 'c, 't, A> {
+  inner: A,
+  state: &'s mut Seq<'t>,
+  index: usize,
+  ctx: &'c Context,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for WrapSeq<'_, '_, '_, A> {
+  type Error = A::Error;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+    let alts = self.state.begin_element();
+    self.ctx.push(self.index.to_string());
+    let Some(value) = self.inner.next_element_seed(WrapSeed { seed, alts, ctx: self.ctx })? else {
+      self.ctx.pop();
+      return Ok(None);
+    };
+
+    self.state.end_element(self.ctx.take_results());
+    if !self.state.is_alive() {
+      return Err(self.ctx.mismatch());
+    }
+    self.ctx.pop();
+    self.index += 1;
+    Ok(Some(value))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    self.inner.size_hint()
+  }
+}
+
+struct WrapMap<'s, 'c, 't, A> {
+  inner: A,
+  state: &'s mut Map<'t>,
+  /// the alternatives of the value of the current entry
+  alts: Vec<Alt<'t>>,
+  ctx: &'c Context,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for WrapMap<'_, '_, '_, A> {
+  type Error = A::Error;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+    let Some(key) = self.inner.next_key_seed(KeySeed { seed, ctx: self.ctx })? else {
+      return Ok(None);
+    };
+
+    let name = self.ctx.key.take().unwrap_or_default();
+    self.alts = self.state.begin_entry(&name);
+    self.ctx.push(name);
+    // the key is not accepted by a record
+    if !self.state.is_alive() {
+      return Err(self.ctx.mismatch());
+    }
+    Ok(Some(key))
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    let alts = mem::take(&mut self.alts);
+    let value = self.inner.next_value_seed(WrapSeed { seed, alts, ctx: self.ctx })?;
+    self.state.end_entry(self.ctx.take_results());
+    if !self.state.is_alive() {
+      return Err(self.ctx.mismatch());
+    }
+    self.ctx.pop();
+    Ok(value)
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    self.inner.size_hint()
+  }
+}
+
+/// Remembers the string of a map key or a variant
+struct KeySeed<'c, S> {
+  seed: S,
+  ctx: &'c Context,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for KeySeed<'_, S> {
+  type Value = S::Value;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    self.seed.deserialize(Key { inner: deserializer, ctx: self.ctx })
+  }
+}
+
+struct Key<'c, D> {
+  inner: D,
+  ctx: &'c Context,
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Key<'_, D> {
+  type Error = D::Error;
+
+  forward! {
+    self, visitor => self.inner, KeyVisitor { visitor, ctx: self.ctx };
+    deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32() deserialize_i64()
+    deserialize_i128() deserialize_u8() deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+    deserialize_f32() deserialize_f64() deserialize_char() deserialize_str() deserialize_string() deserialize_bytes()
+    deserialize_byte_buf() deserialize_option() deserialize_unit() deserialize_unit_struct(name: &'static str)
+    deserialize_newtype_struct(name: &'static str) deserialize_seq() deserialize_tuple(len: usize)
+    deserialize_tuple_struct(name: &'static str, len: usize) deserialize_map()
+    deserialize_struct(name: &'static str, fields: &'static [&'static str])
+    deserialize_enum(name: &'static str, variants: &'static [&'static str]) deserialize_identifier()
+  }
+
+  fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.inner.deserialize_any(KeyVisitor { visitor, ctx: self.ctx })
+  }
+
+  fn is_human_readable(&self) -> bool {
+    self.inner.is_human_readable()
+  }
+}
+
+/// serde_json parses the keys of maps with number keys, they are checked by their display
+struct KeyVisitor<'c, V> {
+  visitor: V,
+  ctx: &'c Context,
+}
+
+impl<V> KeyVisitor<'_, V> {
+  fn key(&self, key: impl ToString) {
+    *self.ctx.key.borrow_mut() = Some(key.to_string());
+  }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for KeyVisitor<'_, V> {
+  type Value = V::Value;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.visitor.expecting(f)
+  }
+
+  fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_bool(v)
+  }
+
+  fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_i64(v)
+  }
+
+  fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_i128(v)
+  }
+
+  fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_u64(v)
+  }
+
+  fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_u128(v)
+  }
+
+  fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_f64(v)
+  }
+
+  fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_str(v)
+  }
+
+  fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+    self.key(v);
+    self.visitor.visit_borrowed_str(v)
+  }
+
+  fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+    self.key(&v);
+    self.visitor.visit_string(v)
+  }
+
+  fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    self.key(String::from_utf8_lossy(v));
+    self.visitor.visit_bytes(v)
+  }
+
+  fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+    self.key(String::from_utf8_lossy(v));
+    self.visitor.visit_borrowed_bytes(v)
+  }
+
+  fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+    self.key(String::from_utf8_lossy(&v));
+    self.visitor.visit_byte_buf(v)
+  }
+
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    self.visitor.visit_newtype_struct(Key { inner: deserializer, ctx: self.ctx })
+  }
+}
+
+/// An externally tagged enum, `"Variant"` or `{ "Variant": value }`
+struct WrapEnum<'c, 't, A> {
+  inner: A,
+  alts: Vec<Alt<'t>>,
+  ctx: &'c Context,
+}
+
+impl<'c, 't, 'de, A: EnumAccess<'de>> EnumAccess<'de> for WrapEnum<'c, 't, A> {
+  type Error = A::Error;
+  type Variant = WrapVariant<'c, 't, A::Variant>;
+
+  fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self::Variant), Self::Error> {
+    let (value, variant) = self.inner.variant_seed(KeySeed { seed, ctx: self.ctx })?;
+    let name = self.ctx.key.take().unwrap_or_default();
+    Ok((value, WrapVariant { inner: variant, alts: self.alts, name, ctx: self.ctx }))
+  }
+}
+
+struct WrapVariant<'c, 't, A> {
+  inner: A,
+  alts: Vec<Alt<'t>>,
+  name: String,
+  ctx: &'c Context,
+}
+
+impl<'de, 't, A: VariantAccess<'de>> WrapVariant<'_, 't, A> {
+  /// Checks `{ "Variant": value }` with the value deserialized by `f`
+  fn entry<T>(self, f: impl FnOnce(A, Vec<Alt<'t>>) -> Result<T, A::Error>) -> Result<T, A::Error> {
+    let mut state = Map::new(self.alts);
+    if !state.is_alive() {
+      return Err(self.ctx.mismatch());
+    }
+    let alts = state.begin_entry(&self.name);
+    self.ctx.push(self.name);
+    let value = f(self.inner, alts)?;
+    state.end_entry(self.ctx.take_results());
+    self.ctx.pop();
+    self.ctx.accept(state.finish())?;
+    Ok(value)
+  }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for WrapVariant<'_, '_, A> {
+  type Error = A::Error;
+
+  // serde_json accepts `"Variant"` and `{ "Variant": null }`
+  fn unit_variant(self) -> Result<(), Self::Error> {
+    self.inner.unit_variant()?;
+    let mut state = Map::new(self.alts.clone());
+    let alts = state.begin_entry(&self.name);
+    state.end_entry(check::null(&alts));
+    let results = check::string(&self.alts, &self.name).into_iter().zip(state.finish()).map(|(string, map)| string || map).collect();
+    self.ctx.accept(results)
+  }
+
+  fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, Self::Error> {
+    let ctx = self.ctx;
+    self.entry(|inner, alts| inner.newtype_variant_seed(WrapSeed { seed, alts, ctx }))
+  }
+
+  fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+    let ctx = self.ctx;
+    self.entry(|inner, alts| inner.tuple_variant(len, WrapVisitor { visitor, alts, ctx }))
+  }
+
+  fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+    let ctx = self.ctx;
+    self.entry(|inner, alts| inner.struct_variant(fields, WrapVisitor { visitor, alts, ctx }))
+  }
+}
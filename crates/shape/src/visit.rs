@@ -0,0 +1,413 @@
+//! Traversal of the nodes of a [`Type`]
+//!
+//! [`Visit`], [`VisitMut`] and [`Fold`] have a method for every node, the default methods walk the
+//! children of the node with the `walk_*` functions, so an implementation only overrides the nodes it cares about
+//!
+//! The [`Pointer`] is the JSON pointer of the values described by the node, the members of unions and
+//! intersections, the arguments of an [`Apply`] and the key of a [`Record`] have the pointer of their parent
+
+use std::{borrow::Cow, fmt, sync::Arc};
+
+use indexmap::IndexMap;
+
+use crate::{Apply, Array, Custom, Literal, Object, Property, Record, RecordKey, Tuple, Type};
+
+/// The JSON pointer of the node being visited, `*` is any element of an array or any value of a record
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pointer {
+  segments: Vec<String>,
+}
+
+impl Pointer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn segments(&self) -> &[String] {
+    &self.segments
+  }
+
+  pub fn push(&mut self, segment: impl Into<String>) {
+    self.segments.push(segment.into());
+  }
+
+  pub fn pop(&mut self) -> Option<String> {
+    self.segments.pop()
+  }
+}
+
+/// Eg: `/items/*/display~1name`
+impl fmt::Display for Pointer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for segment in &self.segments {
+      write!(f, "/{}", segment.replace('~', "~0").replace('/', "~1"))?;
+    }
+    Ok(())
+  }
+}
+
+/// Visit the nodes of a type by reference
+pub trait Visit {
+  fn visit_type(&mut self, ty: &Type, pointer: &mut Pointer) {
+    walk_type(self, ty, pointer)
+  }
+
+  fn visit_literal(&mut self, _literal: &Literal, _pointer: &mut Pointer) {}
+
+  fn visit_tuple(&mut self, tuple: &Tuple, pointer: &mut Pointer) {
+    walk_tuple(self, tuple, pointer)
+  }
+
+  fn visit_array(&mut self, array: &Array, pointer: &mut Pointer) {
+    walk_array(self, array, pointer)
+  }
+
+  fn visit_object(&mut self, object: &Object, pointer: &mut Pointer) {
+    walk_object(self, object, pointer)
+  }
+
+  /// The pointer already ends with the key
+  fn visit_property(&mut self, _key: &str, property: &Property, pointer: &mut Pointer) {
+    self.visit_type(&property.ty, pointer)
+  }
+
+  fn visit_record(&mut self, record: &Record, pointer: &mut Pointer) {
+    walk_record(self, record, pointer)
+  }
+
+  fn visit_record_key(&mut self, key: &RecordKey, pointer: &mut Pointer) {
+    walk_record_key(self, key, pointer)
+  }
+
+  fn visit_custom(&mut self, _custom: &Custom, _pointer: &mut Pointer) {}
+
+  fn visit_param(&mut self, _name: &str, _pointer: &mut Pointer) {}
+
+  fn visit_apply(&mut self, apply: &Apply, pointer: &mut Pointer) {
+    walk_apply(self, apply, pointer)
+  }
+}
+
+pub fn walk_type<V: Visit + ?Sized>(v: &mut V, ty: &Type, pointer: &mut Pointer) {
+  match ty {
+    Type::Literal(literal) => v.visit_literal(literal, pointer),
+    Type::Tuple(tuple) => v.visit_tuple(tuple, pointer),
+    Type::Array(array) => v.visit_array(array, pointer),
+    Type::Object(object) => v.visit_object(object, pointer),
+    Type::Record(record) => v.visit_record(record, pointer),
+    Type::And(items) | Type::Or(items) => items.iter().for_each(|item| v.visit_type(item, pointer)),
+    Type::Custom(custom) => v.visit_custom(custom, pointer),
+    Type::Param(name) => v.visit_param(name, pointer),
+    Type::Apply(apply) => v.visit_apply(apply, pointer),
+    Type::Null | Type::Undefined | Type::Never | Type::Unknown | Type::Boolean | Type::Number | Type::String | Type::Bytes => {},
+  }
+}
+
+pub fn walk_tuple<V: Visit + ?Sized>(v: &mut V, tuple: &Tuple, pointer: &mut Pointer) {
+  for (i, item) in tuple.items.iter().enumerate() {
+    pointer.push(i.to_string());
+    v.visit_type(item, pointer);
+    pointer.pop();
+  }
+  if let Some(rest) = &tuple.rest {
+    pointer.push("*");
+    v.visit_type(rest, pointer);
+    pointer.pop();
+  }
+}
+
+pub fn walk_array<V: Visit + ?Sized>(v: &mut V, array: &Array, pointer: &mut Pointer) {
+  pointer.push("*");
+  v.visit_type(&array.item, pointer);
+  pointer.pop();
+}
+
+pub fn walk_object<V: Visit + ?Sized>(v: &mut V, object: &Object, pointer: &mut Pointer) {
+  for (key, property) in &object.properties {
+    pointer.push(key.as_ref());
+    v.visit_property(key, property, pointer);
+    pointer.pop();
+  }
+}
+
+pub fn walk_record<V: Visit + ?Sized>(v: &mut V, record: &Record, pointer: &mut Pointer) {
+  v.visit_record_key(&record.key, pointer);
+  pointer.push("*");
+  v.visit_type(&record.value, pointer);
+  pointer.pop();
+}
+
+pub fn walk_record_key<V: Visit + ?Sized>(v: &mut V, key: &RecordKey, pointer: &mut Pointer) {
+  if let RecordKey::Type(key) | RecordKey::Unsupported(key) = key {
+    v.visit_type(key, pointer);
+  }
+}
+
+pub fn walk_apply<V: Visit + ?Sized>(v: &mut V, apply: &Apply, pointer: &mut Pointer) {
+  apply.args.iter().for_each(|arg| v.visit_type(arg, pointer));
+}
+
+/// Visit the nodes of a type by mutable reference
+pub trait VisitMut {
+  fn visit_type_mut(&mut self, ty: &mut Type, pointer: &mut Pointer) {
+    walk_type_mut(self, ty, pointer)
+  }
+
+  fn visit_literal_mut(&mut self, _literal: &mut Literal, _pointer: &mut Pointer) {}
+
+  fn visit_tuple_mut(&mut self, tuple: &mut Tuple, pointer: &mut Pointer) {
+    walk_tuple_mut(self, tuple, pointer)
+  }
+
+  fn visit_array_mut(&mut self, array: &mut Array, pointer: &mut Pointer) {
+    walk_array_mut(self, array, pointer)
+  }
+
+  fn visit_object_mut(&mut self, object: &mut Object, pointer: &mut Pointer) {
+    walk_object_mut(self, object, pointer)
+  }
+
+  /// The pointer already ends with the key
+  fn visit_property_mut(&mut self, _key: &str, property: &mut Property, pointer: &mut Pointer) {
+    self.visit_type_mut(Arc::make_mut(&mut property.ty), pointer)
+  }
+
+  fn visit_record_mut(&mut self, record: &mut Record, pointer: &mut Pointer) {
+    walk_record_mut(self, record, pointer)
+  }
+
+  fn visit_record_key_mut(&mut self, key: &mut RecordKey, pointer: &mut Pointer) {
+    walk_record_key_mut(self, key, pointer)
+  }
+
+  fn visit_custom_mut(&mut self, _custom: &mut Custom, _pointer: &mut Pointer) {}
+
+  fn visit_param_mut(&mut self, _name: &mut Cow<'static, str>, _pointer: &mut Pointer) {}
+
+  fn visit_apply_mut(&mut self, apply: &mut Apply, pointer: &mut Pointer) {
+    walk_apply_mut(self, apply, pointer)
+  }
+}
+
+pub fn walk_type_mut<V: VisitMut + ?Sized>(v: &mut V, ty: &mut Type, pointer: &mut Pointer) {
+  match ty {
+    Type::Literal(literal) => v.visit_literal_mut(literal, pointer),
+    Type::Tuple(tuple) => v.visit_tuple_mut(tuple, pointer),
+    Type::Array(array) => v.visit_array_mut(array, pointer),
+    Type::Object(object) => v.visit_object_mut(object, pointer),
+    Type::Record(record) => v.visit_record_mut(record, pointer),
+    Type::And(items) | Type::Or(items) => items.iter_mut().for_each(|item| v.visit_type_mut(Arc::make_mut(item), pointer)),
+    Type::Custom(custom) => v.visit_custom_mut(custom, pointer),
+    Type::Param(name) => v.visit_param_mut(name, pointer),
+    Type::Apply(apply) => v.visit_apply_mut(apply, pointer),
+    Type::Null | Type::Undefined | Type::Never | Type::Unknown | Type::Boolean | Type::Number | Type::String | Type::Bytes => {},
+  }
+}
+
+pub fn walk_tuple_mut<V: VisitMut + ?Sized>(v: &mut V, tuple: &mut Tuple, pointer: &mut Pointer) {
+  for (i, item) in tuple.items.iter_mut().enumerate() {
+    pointer.push(i.to_string());
+    v.visit_type_mut(Arc::make_mut(item), pointer);
+    pointer.pop();
+  }
+  if let Some(rest) = &mut tuple.rest {
+    pointer.push("*");
+    v.visit_type_mut(Arc::make_mut(rest), pointer);
+    pointer.pop();
+  }
+}
+
+pub fn walk_array_mut<V: VisitMut + ?Sized>(v: &mut V, array: &mut Array, pointer: &mut Pointer) {
+  pointer.push("*");
+  v.visit_type_mut(Arc::make_mut(&mut array.item), pointer);
+  pointer.pop();
+}
+
+pub fn walk_object_mut<V: VisitMut + ?Sized>(v: &mut V, object: &mut Object, pointer: &mut Pointer) {
+  for (key, property) in &mut object.properties {
+    pointer.push(key.as_ref());
+    v.visit_property_mut(key, property, pointer);
+    pointer.pop();
+  }
+}
+
+pub fn walk_record_mut<V: VisitMut + ?Sized>(v: &mut V, record: &mut Record, pointer: &mut Pointer) {
+  v.visit_record_key_mut(&mut record.key, pointer);
+  pointer.push("*");
+  v.visit_type_mut(Arc::make_mut(&mut record.value), pointer);
+  pointer.pop();
+}
+
+pub fn walk_record_key_mut<V: VisitMut + ?Sized>(v: &mut V, key: &mut RecordKey, pointer: &mut Pointer) {
+  if let RecordKey::Type(key) | RecordKey::Unsupported(key) = key {
+    v.visit_type_mut(Arc::make_mut(key), pointer);
+  }
+}
+
+pub fn walk_apply_mut<V: VisitMut + ?Sized>(v: &mut V, apply: &mut Apply, pointer: &mut Pointer) {
+  apply.args.iter_mut().for_each(|arg| v.visit_type_mut(Arc::make_mut(arg), pointer));
+}
+
+/// Rebuild a type from its nodes
+pub trait Fold {
+  fn fold_type(&mut self, ty: Type, pointer: &mut Pointer) -> Type {
+    walk_type_fold(self, ty, pointer)
+  }
+
+  fn fold_literal(&mut self, literal: Literal, _pointer: &mut Pointer) -> Literal {
+    literal
+  }
+
+  fn fold_tuple(&mut self, tuple: Tuple, pointer: &mut Pointer) -> Tuple {
+    walk_tuple_fold(self, tuple, pointer)
+  }
+
+  fn fold_array(&mut self, array: Array, pointer: &mut Pointer) -> Array {
+    walk_array_fold(self, array, pointer)
+  }
+
+  fn fold_object(&mut self, object: Object, pointer: &mut Pointer) -> Object {
+    walk_object_fold(self, object, pointer)
+  }
+
+  /// The pointer already ends with the key, return `None` to remove the property or another key to rename it
+  fn fold_property(&mut self, key: Cow<'static, str>, property: Property, pointer: &mut Pointer) -> Option<(Cow<'static, str>, Property)> {
+    let ty = self.fold_type(Arc::unwrap_or_clone(property.ty), pointer);
+    Some((key, Property { ty: Arc::new(ty), ..property }))
+  }
+
+  fn fold_record(&mut self, record: Record, pointer: &mut Pointer) -> Record {
+    walk_record_fold(self, record, pointer)
+  }
+
+  fn fold_record_key(&mut self, key: RecordKey, pointer: &mut Pointer) -> RecordKey {
+    walk_record_key_fold(self, key, pointer)
+  }
+
+  fn fold_custom(&mut self, custom: Custom, _pointer: &mut Pointer) -> Custom {
+    custom
+  }
+
+  fn fold_param(&mut self, name: Cow<'static, str>, _pointer: &mut Pointer) -> Cow<'static, str> {
+    name
+  }
+
+  fn fold_apply(&mut self, apply: Apply, pointer: &mut Pointer) -> Apply {
+    walk_apply_fold(self, apply, pointer)
+  }
+}
+
+pub fn walk_type_fold<F: Fold + ?Sized>(f: &mut F, ty: Type, pointer: &mut Pointer) -> Type {
+  match ty {
+    Type::Literal(literal) => Type::Literal(f.fold_literal(literal, pointer)),
+    Type::Tuple(tuple) => Type::Tuple(f.fold_tuple(tuple, pointer)),
+    Type::Array(array) => Type::Array(f.fold_array(array, pointer)),
+    Type::Object(object) => Type::Object(f.fold_object(object, pointer)),
+    Type::Record(record) => Type::Record(f.fold_record(record, pointer)),
+    Type::And(items) => Type::And(items.into_iter().map(|item| Arc::new(f.fold_type(Arc::unwrap_or_clone(item), pointer))).collect()),
+    Type::Or(items) => Type::Or(items.into_iter().map(|item| Arc::new(f.fold_type(Arc::unwrap_or_clone(item), pointer))).collect()),
+    Type::Custom(custom) => Type::Custom(f.fold_custom(custom, pointer)),
+    Type::Param(name) => Type::Param(f.fold_param(name, pointer)),
+    Type::Apply(apply) => Type::Apply(f.fold_apply(apply, pointer)),
+    ty @ (Type::Null | Type::Undefined | Type::Never | Type::Unknown | Type::Boolean | Type::Number | Type::String | Type::Bytes) => ty,
+  }
+}
+
+pub fn walk_tuple_fold<F: Fold + ?Sized>(f: &mut F, tuple: Tuple, pointer: &mut Pointer) -> Tuple {
+  let items = tuple.items.into_iter().enumerate().map(|(i, item)| {
+    pointer.push(i.to_string());
+    let item = f.fold_type(Arc::unwrap_or_clone(item), pointer);
+    pointer.pop();
+    Arc::new(item)
+  }).collect();
+
+  let rest = tuple.rest.map(|rest| {
+    pointer.push("*");
+    let rest = f.fold_type(Arc::unwrap_or_clone(rest), pointer);
+    pointer.pop();
+    Arc::new(rest)
+  });
+
+  Tuple { items, rest }
+}
+
+pub fn walk_array_fold<F: Fold + ?Sized>(f: &mut F, array: Array, pointer: &mut Pointer) -> Array {
+  pointer.push("*");
+  let item = f.fold_type(Arc::unwrap_or_clone(array.item), pointer);
+  pointer.pop();
+  Array { item: Arc::new(item) }
+}
+
+pub fn walk_object_fold<F: Fold + ?Sized>(f: &mut F, object: Object, pointer: &mut Pointer) -> Object {
+  let mut properties = IndexMap::with_capacity(object.properties.len());
+  for (key, property) in object.properties {
+    pointer.push(key.as_ref());
+    if let Some((key, property)) = f.fold_property(key, property, pointer) {
+      properties.insert(key, property);
+    }
+    pointer.pop();
+  }
+  Object { properties }
+}
+
+pub fn walk_record_fold<F: Fold + ?Sized>(f: &mut F, record: Record, pointer: &mut Pointer) -> Record {
+  let key = f.fold_record_key(record.key, pointer);
+  pointer.push("*");
+  let value = f.fold_type(Arc::unwrap_or_clone(record.value), pointer);
+  pointer.pop();
+  Record { key, value: Arc::new(value), ..record }
+}
+
+pub fn walk_record_key_fold<F: Fold + ?Sized>(f: &mut F, key: RecordKey, pointer: &mut Pointer) -> RecordKey {
+  match key {
+    RecordKey::Type(key) => RecordKey::Type(Arc::new(f.fold_type(Arc::unwrap_or_clone(key), pointer))),
+    RecordKey::Unsupported(key) => RecordKey::Unsupported(Arc::new(f.fold_type(Arc::unwrap_or_clone(key), pointer))),
+    key => key,
+  }
+}
+
+pub fn walk_apply_fold<F: Fold + ?Sized>(f: &mut F, apply: Apply, pointer: &mut Pointer) -> Apply {
+  let args = apply.args.into_iter().map(|arg| Arc::new(f.fold_type(Arc::unwrap_or_clone(arg), pointer))).collect();
+  Apply { name: apply.name, args }
+}
+
+struct Walk<F>(F);
+
+impl<F: FnMut(&Type, &Pointer)> Visit for Walk<F> {
+  fn visit_type(&mut self, ty: &Type, pointer: &mut Pointer) {
+    (self.0)(ty, pointer);
+    walk_type(self, ty, pointer)
+  }
+}
+
+impl<F: FnMut(&mut Type, &Pointer)> VisitMut for Walk<F> {
+  fn visit_type_mut(&mut self, ty: &mut Type, pointer: &mut Pointer) {
+    (self.0)(ty, pointer);
+    walk_type_mut(self, ty, pointer)
+  }
+}
+
+impl<F: FnMut(Type, &Pointer) -> Type> Fold for Walk<F> {
+  fn fold_type(&mut self, ty: Type, pointer: &mut Pointer) -> Type {
+    let ty = walk_type_fold(self, ty, pointer);
+    (self.0)(ty, pointer)
+  }
+}
+
+impl Type {
+  /// Call `f` with this type and all the types inside it, parents before their children
+  pub fn walk(&self, f: impl FnMut(&Type, &Pointer)) {
+    Walk(f).visit_type(self, &mut Pointer::new())
+  }
+
+  /// Call `f` with this type and all the types inside it, parents before their children,
+  /// the children of a type replaced by `f` are the ones walked
+  pub fn walk_mut(&mut self, f: impl FnMut(&mut Type, &Pointer)) {
+    Walk(f).visit_type_mut(self, &mut Pointer::new())
+  }
+
+  /// Replace this type and all the types inside it with `f`, children before their parents
+  pub fn fold(self, f: impl FnMut(Type, &Pointer) -> Type) -> Type {
+    Walk(f).fold_type(self, &mut Pointer::new())
+  }
+}
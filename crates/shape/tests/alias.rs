@@ -0,0 +1,51 @@
+mod common;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shape::{shape, IsAsignable, Shape, ShapeOptions};
+
+#[derive(Serialize, Deserialize, Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  #[serde(alias = "username", alias = "login")]
+  name: String,
+}
+
+#[derive(Serialize, Deserialize, Shape)]
+#[serde(tag = "type")]
+#[allow(unused)]
+enum Event {
+  #[serde(alias = "new")]
+  Created { id: u32 },
+  Deleted,
+}
+
+#[test]
+fn field_alias() {
+  eq!(User::shape(&ShapeOptions::for_serialize()), shape!{{ id: number, name: string }});
+
+  let ty = User::shape(&ShapeOptions::for_deserialize());
+  eq!(ty, shape!{ { id: number } & ({ name: string } | { username: string } | { login: string }) });
+
+  for value in [json!({ "id": 1, "name": "a" }), json!({ "id": 1, "username": "a" }), json!({ "id": 1, "login": "a" })] {
+    assert!(serde_json::from_value::<User>(value.clone()).is_ok());
+    assert!(ty.is_assignable(&value));
+  }
+
+  let value = json!({ "id": 1, "nick": "a" });
+  assert!(serde_json::from_value::<User>(value.clone()).is_err());
+  assert!(!ty.is_assignable(&value));
+}
+
+#[test]
+fn variant_alias() {
+  eq!(Event::shape(&ShapeOptions::for_serialize()), shape!{ { type: "Created" } & { id: number } | { type: "Deleted" } });
+
+  let ty = Event::shape(&ShapeOptions::for_deserialize());
+  eq!(ty, shape!{ { type: "Created" } & { id: number } | { type: "new" } & { id: number } | { type: "Deleted" } });
+
+  let value = json!({ "type": "new", "id": 1 });
+  assert!(serde_json::from_value::<Event>(value.clone()).is_ok());
+  assert!(ty.is_assignable(&value));
+}
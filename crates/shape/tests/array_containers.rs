@@ -1,6 +1,7 @@
 mod common;
 
 use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
 
 use indexmap::IndexSet;
 use shape::{Array, Shape, ShapeOptions, Tuple, Type};
@@ -9,7 +10,7 @@ use shape::{Array, Shape, ShapeOptions, Tuple, Type};
 fn array_containers() {
   
   let expected = Type::Array(Array {
-    item: Box::new(Type::String),
+    item: Arc::new(Type::String),
   });
   
   eq!(Vec::<String>::shape(&ShapeOptions::for_serialize()), expected);
@@ -32,7 +33,7 @@ fn array_containers() {
 fn static_array() {
   let expected = Type::Tuple(
     Tuple {
-      items: vec![Type::Number, Type::Number, Type::Number],
+      items: vec![Arc::new(Type::Number), Arc::new(Type::Number), Arc::new(Type::Number)],
       rest: None,
     }
   );
@@ -0,0 +1,156 @@
+mod common;
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use shape::{Array, Declarations, Object, Property, Shape, ShapeOptions, ToTypescript, Type};
+
+/// A type that does not implement Shape
+#[allow(unused)]
+struct NotShape;
+
+fn property(ty: Type) -> Property {
+  Property {
+    optional: false,
+    readonly: false,
+    ty: Arc::new(ty),
+  }
+}
+
+#[test]
+fn phantom_data() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Id<T> {
+    id: u64,
+    _marker: PhantomData<T>,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([
+      ("id".into(), property(Type::Number)),
+      ("_marker".into(), property(Type::Null)),
+    ]),
+  });
+
+  eq!(Id::<NotShape>::shape(&ShapeOptions::for_serialize()), expected);
+
+  // phantom parameters are not parameters of the declaration
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Id<NotShape>>();
+  assert_eq!(declarations.get("Id").unwrap().params, Vec::<String>::new());
+  assert_eq!(declarations.get("Id").unwrap().ty.to_typescript(), "{ id: number; _marker: null; }");
+}
+
+#[test]
+fn skipped_field() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Skipped<T> {
+    value: u8,
+    #[serde(skip)]
+    cache: Option<T>,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([("value".into(), property(Type::Number))]),
+  });
+
+  eq!(Skipped::<NotShape>::shape(&ShapeOptions::for_serialize()), expected);
+}
+
+#[test]
+fn associated_type() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Items<I: Iterator> {
+    items: Vec<I::Item>,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([(
+      "items".into(),
+      property(Type::Array(Array { item: Arc::new(Type::String) })),
+    )]),
+  });
+
+  eq!(Items::<std::vec::IntoIter<String>>::shape(&ShapeOptions::for_serialize()), expected);
+}
+
+#[test]
+fn container_bound() {
+  trait Named {
+    #[allow(unused)]
+    fn name() -> &'static str;
+  }
+
+  impl Named for String {
+    fn name() -> &'static str {
+      "string"
+    }
+  }
+
+  #[derive(Shape)]
+  #[serde(bound = "T: Shape + Named")]
+  #[allow(unused)]
+  struct Wrapper<T> {
+    inner: Box<T>,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([("inner".into(), property(Type::String))]),
+  });
+
+  eq!(Wrapper::<String>::shape(&ShapeOptions::for_serialize()), expected);
+
+  // custom bounds can't be described generically, so the type is inlined
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  eq!(declarations.add::<Wrapper<String>>(), expected);
+  assert!(declarations.is_empty());
+}
+
+#[test]
+fn field_bound() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Pair<A, B> {
+    #[shape(bound = "A: Shape")]
+    first: A,
+    #[serde(bound(serialize = "B: Shape", deserialize = "B: Shape + Default"))]
+    second: B,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([
+      ("first".into(), property(Type::String)),
+      ("second".into(), property(Type::Number)),
+    ]),
+  });
+
+  eq!(Pair::<String, u32>::shape(&ShapeOptions::for_serialize()), expected);
+}
+
+#[test]
+fn enum_variant_bound() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Either<L, R> {
+    #[serde(bound = "L: Shape")]
+    Left(L),
+    Right(R),
+    #[serde(skip)]
+    Never(PhantomData<(L, R)>, NotShape),
+  }
+
+  let expected = Type::or([
+    Type::Object(Object {
+      properties: IndexMap::from([("Left".into(), property(Type::String))]),
+    }),
+    Type::Object(Object {
+      properties: IndexMap::from([("Right".into(), property(Type::Boolean))]),
+    }),
+  ]);
+
+  eq!(Either::<String, bool>::shape(&ShapeOptions::for_serialize()), expected);
+}
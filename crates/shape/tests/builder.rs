@@ -0,0 +1,74 @@
+mod common;
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use shape::{shape, Apply, Literal, Object, Property, Record, RecordKey, ToTypescript, Tuple, Type};
+
+#[test]
+fn builder() {
+  let user = Type::object()
+    .field("id", Type::Number)
+    .optional_field("tags", Type::array(Type::String))
+    .readonly_field("kind", Type::literal("user"));
+
+  eq!(Type::from(user.clone()), Type::Object(Object {
+    properties: IndexMap::from([
+      ("id".into(), Property { optional: false, readonly: false, ty: Arc::new(Type::Number) }),
+      ("tags".into(), Property { optional: true, readonly: false, ty: Arc::new(Type::Array(shape::Array { item: Arc::new(Type::String) })) }),
+      ("kind".into(), Property { optional: false, readonly: true, ty: Arc::new(Type::Literal(Literal::String("user".into()))) })
+    ])
+  }));
+
+  eq!(Type::from(user).or_null().to_typescript(), "({ id: number; tags?: Array<string>; readonly kind: \"user\"; } | null)");
+  eq!(Type::record(Type::String, Type::tuple([Type::Number, Type::literal(true)])).to_typescript(), "{ [key: string]: [number, true] }");
+  eq!(Type::and([Type::object().field("a", Type::Null).into(), Type::or([Type::literal(1.0), Type::Undefined])]).to_typescript(), "({ a: null; } & (1 | undefined))");
+}
+
+#[test]
+fn dsl() {
+  eq!(shape!{ { id: number, tags?: string[] } | null }, Type::or([
+    Type::object().field("id", Type::Number).optional_field("tags", Type::array(Type::String)).into(),
+    Type::Null
+  ]));
+
+  eq!(shape!{ boolean & unknown & never & undefined & Uint8Array }, Type::and([Type::Boolean, Type::Unknown, Type::Never, Type::Undefined, Type::Bytes]));
+  eq!(shape!{ "a" | -1.5 | 2 | false }, Type::or([Type::literal("a"), Type::literal(-1.5), Type::literal(2.0), Type::literal(false)]));
+  eq!(shape!{ (string | number)[][] }, Type::array(Type::array(Type::or([Type::String, Type::Number]))));
+  eq!(shape!{ Array<Record<string, number>> }, Type::array(Type::record(RecordKey::String, Type::Number)));
+  eq!(shape!{ Page<User, string> }, Type::Apply(Apply {
+    name: "Page".into(),
+    args: vec![Arc::new(Type::Apply(Apply { name: "User".into(), args: vec![] })), Arc::new(Type::String)]
+  }));
+
+  // tuples with a rest
+  eq!(shape!{ [] }, Type::tuple([]));
+  eq!(shape!{ [string, number,] }, Type::tuple([Type::String, Type::Number]));
+  eq!(shape!{ [string, ...number[][]] }, Type::Tuple(Tuple { items: vec![Arc::new(Type::String)], rest: Some(Arc::new(Type::array(Type::Number))) }));
+
+  // readonly, quoted and keyword keys and index signatures
+  eq!(shape!{
+    | { readonly type: "a"; "display-name": string; readonly: boolean; }
+    | { [key: number]: string }
+    | { id: number, [key: string]: unknown }
+  }, Type::or([
+    Type::object()
+      .readonly_field("type", Type::literal("a"))
+      .field("display-name", Type::String)
+      .field("readonly", Type::Boolean)
+      .into(),
+    Type::record(RecordKey::Number, Type::String),
+    Type::and([Type::object().field("id", Type::Number).into(), Type::Record(Record {
+      optional: false,
+      readonly: false,
+      key: RecordKey::String,
+      value: Arc::new(Type::Unknown)
+    })])
+  ]));
+
+  // interpolation of rust expressions
+  let id = Type::literal("id");
+  eq!(shape!{ { id: #id, items: #(Type::array(Type::Number)) }[] }, Type::array(Type::object()
+    .field("id", Type::literal("id"))
+    .field("items", Type::array(Type::Number))));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::sync::Arc;
+
+use shape::{shape, shape_of, Shape, ShapeOptions};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  name: Option<String>,
+  tags: [String; 3],
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+#[serde(rename_all = "snake_case")]
+enum Role {
+  Admin,
+  Guest { since: u64 },
+}
+
+#[test]
+fn shape_of_is_cached() {
+  let serialize = ShapeOptions::for_serialize();
+  let deserialize = ShapeOptions::for_deserialize();
+
+  let a = shape_of::<User>(&serialize);
+  let b = shape_of::<User>(&serialize);
+  assert!(Arc::ptr_eq(&a, &b));
+  eq!(*a, User::shape(&serialize));
+  eq!(*a, shape!{{ id: number, name: string | null, tags: [string, string, string] }});
+
+  // each type and options has its own shape
+  let de = shape_of::<User>(&deserialize);
+  assert!(!Arc::ptr_eq(&a, &de));
+  eq!(*de, User::shape(&deserialize));
+  eq!(*shape_of::<Role>(&serialize), shape!{ "admin" | { guest: { since: number } } });
+}
+
+#[test]
+fn names_are_borrowed() {
+  let ty = User::shape(&ShapeOptions::for_serialize());
+  let shape::Type::Object(object) = ty else { panic!("not an object") };
+  assert!(object.properties.keys().all(|key| matches!(key, std::borrow::Cow::Borrowed(_))));
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Big {
+  user: User,
+  role: Option<Role>,
+  pairs: (u8, String, [bool; 2]),
+}
+
+#[test]
+fn children_are_shared() {
+  let options = ShapeOptions::for_serialize();
+  let ty = <[Big; 64]>::shape(&options);
+  let shape::Type::Tuple(tuple) = &ty else { panic!("not a tuple") };
+  assert_eq!(tuple.items.len(), 64);
+  assert!(tuple.items.iter().all(|item| Arc::ptr_eq(item, &tuple.items[0])));
+  eq!(*tuple.items[0], Big::shape(&options));
+
+  // a clone shares the properties and the members of unions
+  let shape::Type::Object(object) = &*tuple.items[0] else { panic!("not an object") };
+  let clone = object.clone();
+  for (key, property) in &object.properties {
+    assert!(Arc::ptr_eq(&property.ty, &clone.properties[key].ty));
+  }
+  let shape::Type::Or(items) = &*object.properties["role"].ty else { panic!("not a union") };
+  let shape::Type::Or(cloned) = (*object.properties["role"].ty).clone() else { panic!("not a union") };
+  assert!(items.iter().zip(&cloned).all(|(a, b)| Arc::ptr_eq(a, b)));
+
+  // and the arguments of a declaration
+  let shape::Type::Apply(apply) = shape!(Page<#(ty.clone())>) else { panic!("not an apply") };
+  let clone = apply.clone();
+  assert!(Arc::ptr_eq(&apply.args[0], &clone.args[0]));
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use indexmap::indexmap;
+use serde::Serialize;
+use shape::{check, check_type, CheckError, IsAsignable, Literal, Object, Property, Record, RecordKey, Shape, ShapeOptions, Tuple, Type};
+
+#[derive(Serialize, Shape)]
+struct User {
+  id: u64,
+  name: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  email: Option<String>,
+  tags: Vec<String>,
+  scores: HashMap<u8, f64>,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event {
+  Created { user: User },
+  Renamed(Rename),
+  Deleted,
+}
+
+#[derive(Serialize, Shape)]
+struct Rename {
+  from: String,
+  to: String,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(tag = "t", content = "c")]
+enum Message {
+  Text(String),
+  Point(f64, f64),
+  Ping,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(untagged)]
+enum Id {
+  Number(u64),
+  Text(String),
+  Pair(u8, u8),
+}
+
+#[derive(Serialize, Shape)]
+enum Status {
+  Active,
+  Banned { until: u64 },
+  Moved(String, u32),
+  Renamed(String),
+}
+
+#[derive(Serialize, Shape)]
+struct Feed {
+  #[serde(flatten)]
+  rename: Rename,
+  events: Vec<Event>,
+  messages: (Message, Message, Message),
+  ids: [Id; 3],
+  statuses: Vec<Status>,
+  nan: f64,
+  unit: (),
+}
+
+fn user() -> User {
+  User {
+    id: 1,
+    name: String::from("a"),
+    email: None,
+    tags: vec![String::from("b")],
+    scores: HashMap::from([(1, 2.5)]),
+  }
+}
+
+/// The result of `check` is the result of `is_assignable` with the value converted to JSON
+fn same<T: Serialize + ?Sized>(value: &T, ty: &Type) -> bool {
+  let assignable = ty.is_assignable(&serde_json::to_value(value).unwrap());
+  let checked = check_type(value, ty);
+  assert_eq!(checked.is_ok(), assignable, "{}", serde_json::to_string(value).unwrap());
+  assignable
+}
+
+#[test]
+fn derived() {
+  let feed = Feed {
+    rename: Rename { from: String::from("a"), to: String::from("b") },
+    events: vec![
+      Event::Created { user: user() },
+      Event::Renamed(Rename { from: String::from("a"), to: String::from("b") }),
+      Event::Deleted,
+    ],
+    messages: (Message::Text(String::from("hi")), Message::Point(1.0, 2.0), Message::Ping),
+    ids: [Id::Number(1), Id::Text(String::from("a")), Id::Pair(1, 2)],
+    statuses: vec![Status::Active, Status::Banned { until: 1 }, Status::Moved(String::from("a"), 1), Status::Renamed(String::from("b"))],
+    nan: 1.0,
+    unit: (),
+  };
+
+  let options = ShapeOptions::for_serialize();
+  eq!(check(&feed, &options), Ok(()));
+  eq!(check(&user(), &options), Ok(()));
+  assert!(same(&feed, &Feed::shape(&options)));
+
+  // a NaN is serialized as null
+  assert!(!same(&Feed { nan: f64::NAN, ..feed }, &Feed::shape(&options)));
+
+  assert!(!same(&User { email: Some(String::from("a")), ..user() }, &Event::shape(&options)));
+  assert!(!same(&Event::Deleted, &Message::shape(&options)));
+  assert!(same(&Event::Deleted, &Event::shape(&options)));
+}
+
+#[test]
+fn types() {
+  let tuple = Type::Tuple(Tuple { items: vec![Arc::new(Type::String), Arc::new(Type::Number)], rest: Some(Arc::new(Type::Boolean)) });
+  assert!(same(&("a", 1, true, false), &tuple));
+  assert!(!same(&("a", 1, true, 1), &tuple));
+  assert!(!same(&("a",), &tuple));
+  assert!(same(&("a", 1), &Type::Tuple(Tuple { items: vec![Arc::new(Type::String)], rest: None })));
+
+  let object = |properties| Type::Object(Object { properties });
+  let property = |optional, ty| Property { optional, readonly: false, ty: Arc::new(ty) };
+  let a = object(indexmap! { "a".into() => property(false, Type::Number) });
+  let b = object(indexmap! { "b".into() => property(true, Type::String) });
+  let and = Type::and([a.clone(), b.clone()]);
+  assert!(same(&HashMap::from([("a", 1)]), &and));
+  assert!(!same(&HashMap::from([("b", "c")]), &and));
+  assert!(!same(&BTreeMap::from([("a", "1"), ("b", "c")]), &and));
+  assert!(same(&BTreeMap::from([("b", "c")]), &Type::or([a, b, Type::Null])));
+  assert!(same(&None::<u8>, &Type::or([Type::String, Type::Null])));
+
+  let record = |key| Type::Record(Record { optional: false, readonly: false, key: RecordKey::from(key), value: Arc::new(Type::Boolean) });
+  let literal = |value: &'static str| Type::Literal(Literal::String(value.into()));
+  assert!(same(&HashMap::from([("1.5", true), ("2", false)]), &record(Type::Number)));
+  assert!(!same(&HashMap::from([("x", true)]), &record(Type::Number)));
+  assert!(same(&HashMap::from([("a", true), ("b", true)]), &record(Type::or([literal("a"), literal("b")]))));
+  assert!(!same(&HashMap::from([("a", true)]), &record(Type::or([literal("a"), literal("b")]))));
+  assert!(same(&HashMap::from([("a", true), ("b", false)]), &record(Type::and([Type::String, literal("a")]))));
+  assert!(!same(&HashMap::from([("a", 1)]), &record(Type::and([Type::String, literal("a")]))));
+  assert!(same(&HashMap::from([("true", 1)]), &Type::record(Type::Boolean, Type::Number)));
+  assert!(!same(&HashMap::from([("yes", 1)]), &Type::record(Type::Boolean, Type::Number)));
+
+  assert!(!same(&1, &Type::Never));
+  assert!(!same(&1, &Type::or([])));
+  assert!(same(&1, &Type::and([])));
+}
+
+#[test]
+fn serialize_errors() {
+  let value = HashMap::from([((1, 2), true)]);
+  eq!(check_type(&value, &Type::String), Err(CheckError::Serialize(String::from("key must be a string"))));
+
+  let value = HashMap::from([(NanKey, true)]);
+  let error = serde_json::to_value(&value).unwrap_err().to_string();
+  eq!(error, "float key must be finite (got NaN or +/-inf)");
+  eq!(check_type(&value, &Type::record(Type::Number, Type::Boolean)), Err(CheckError::Serialize(error)));
+
+  // serde_json writes the keys of `Some` as the inner key, but `to_value` rejects them
+  let value = BTreeMap::from([(Some(1), 2)]);
+  eq!(serde_json::to_string(&value).unwrap(), r#"{"1":2}"#);
+  eq!(check_type(&value, &Type::record(Type::Number, Type::Number)), Ok(()));
+  eq!(check_type(&value, &Type::record(Type::Boolean, Type::Number)), Err(CheckError::Mismatch));
+  let value = BTreeMap::from([(None::<u8>, 2)]);
+  eq!(check_type(&value, &Type::record(Type::Number, Type::Number)), Err(CheckError::Serialize(String::from("key must be a string"))));
+}
+
+/// A map key that serializes as NaN
+#[derive(PartialEq, Eq, Hash)]
+struct NanKey;
+
+impl Serialize for NanKey {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(f64::NAN)
+  }
+}
+
+#[test]
+fn wide_intersections() {
+  // 2^12 alternatives, the value is checked on a `serde_json::Value`
+  let literal = |value: &'static str| Type::Literal(Literal::String(value.into()));
+  let members = (0..12).map(|i| {
+    let key = format!("k{i}");
+    let variant = |value| Type::Object(Object { properties: indexmap! { key.clone().into() => Property { optional: false, readonly: false, ty: Arc::new(literal(value)) } } });
+    Type::or([variant("a"), variant("b")])
+  });
+  let wide = Type::and(members);
+
+  let value = (0..12).map(|i| (format!("k{i}"), if i % 2 == 0 { "a" } else { "b" })).collect::<BTreeMap<_, _>>();
+  assert!(same(&value, &wide));
+  assert!(same(&vec![&value], &Type::array(wide.clone())));
+
+  let mut invalid = value.clone();
+  invalid.insert(String::from("k3"), "c");
+  assert!(!same(&invalid, &wide));
+  invalid.remove("k3");
+  assert!(!same(&invalid, &wide));
+}
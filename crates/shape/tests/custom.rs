@@ -0,0 +1,116 @@
+mod common;
+
+use regex_static::static_regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use shape::{
+  check, validate_slice, Custom, CustomType, Declaration, Declarations, IsAsignable, PythonStyle, Shape, ShapeOptions, ToJtd, ToPython, ToTypeGuards, ToTypescript, Type,
+};
+
+/// A date time serialized as a RFC 3339 string
+struct Rfc3339;
+
+impl CustomType for Rfc3339 {
+  fn name(&self) -> &str {
+    "Rfc3339"
+  }
+
+  fn typescript(&self) -> String {
+    String::from("string")
+  }
+
+  fn json_schema(&self) -> Option<Value> {
+    Some(json!({ "type": "string", "format": "date-time" }))
+  }
+
+  fn fallback(&self) -> Option<Type> {
+    Some(Type::String)
+  }
+
+  fn is_assignable(&self, value: &Value) -> bool {
+    let regex = static_regex!(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$");
+    value.as_str().is_some_and(|value| regex.is_match(value))
+  }
+}
+
+#[derive(Serialize, Shape)]
+struct Post {
+  title: String,
+  #[shape(type = Rfc3339)]
+  created_at: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[shape(type = "Date")]
+  updated_at: Option<String>,
+}
+
+#[test]
+fn derived() {
+  let ty = Post::shape(&ShapeOptions::for_serialize());
+  eq!(ty.to_typescript(), "{ title: string; created_at: string; updated_at?: Date; }");
+
+  let Type::Object(object) = &ty else { panic!("{ty:?}") };
+  eq!(*object.properties["created_at"].ty, Type::Custom(Custom::new(Rfc3339)));
+  eq!(*object.properties["updated_at"].ty, Type::Custom("Date".into()));
+  eq!(format!("{:?}", object.properties["updated_at"].ty), "Custom(Custom(\"Date\"))");
+}
+
+#[test]
+fn assignable() {
+  let ty = Type::Custom(Custom::new(Rfc3339));
+  assert!(ty.is_assignable(&json!("2024-01-02T03:04:05Z")));
+  assert!(ty.is_assignable(&json!("2024-01-02T03:04:05.123+02:00")));
+  assert!(!ty.is_assignable(&json!("yesterday")));
+  assert!(!ty.is_assignable(&json!(1)));
+
+  // name only custom types can't be checked
+  assert!(!Type::Custom("Date".into()).is_assignable(&json!("2024-01-02T03:04:05Z")));
+
+  let post = |created_at: &str| Post { title: String::from("a"), created_at: created_at.to_string(), updated_at: None };
+  check(&post("2024-01-02T03:04:05Z"), &ShapeOptions::for_serialize()).unwrap();
+  assert!(check(&post("yesterday"), &ShapeOptions::for_serialize()).is_err());
+
+  let ty = Post::shape(&ShapeOptions::for_serialize());
+  validate_slice::<Value>(br#"{"title": "a", "created_at": "2024-01-02T03:04:05Z"}"#, &ty).unwrap();
+  let error = validate_slice::<Value>(br#"{"title": "a", "created_at": "yesterday"}"#, &ty).unwrap_err();
+  eq!(error.pointer, "/created_at");
+}
+
+#[test]
+fn fallback() {
+  let ty = Type::Custom(Custom::new(Rfc3339));
+  eq!(ty.to_jtd().unwrap(), json!({ "type": "string", "metadata": { "type": "Rfc3339" } }));
+  eq!(Type::Custom("Date".into()).to_jtd().unwrap(), json!({ "metadata": { "type": "Date" } }));
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Post>();
+  assert!(declarations.to_type_guards().contains("hasProperty(v, \"created_at\", (v1) => typeof v1 === \"string\")"));
+  assert!(declarations.to_python(PythonStyle::TypedDict).contains("created_at: str"));
+
+  let Type::Custom(custom) = &ty else { unreachable!() };
+  eq!(custom.json_schema(), Some(json!({ "type": "string", "format": "date-time" })));
+  assert!(!Type::and([ty.clone(), Type::Number]).is_satisfiable());
+}
+
+/// A date that is checked with its own guard
+struct JsDate;
+
+impl CustomType for JsDate {
+  fn name(&self) -> &str {
+    "Date"
+  }
+
+  fn type_guard(&self, v: &str) -> Option<String> {
+    Some(format!("{v} instanceof Date"))
+  }
+}
+
+#[test]
+fn type_guards() {
+  // a custom type without a fallback calls a guard named after it
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Post>();
+  assert!(declarations.to_type_guards().contains("optionalProperty(v, \"updated_at\", (v1) => isDate(v1))"));
+
+  let dates = Declaration { name: "Dates".into(), params: vec![], ty: Type::array(Type::Custom(Custom::new(JsDate))) };
+  assert!(dates.to_type_guards().contains("return (Array.isArray(v) && v.every((v1) => (v1 instanceof Date)));"));
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use shape::{Apply, Array, Collision, Declaration, Declarations, Object, Property, Shape, ShapeOptions, ToTypescript, Type};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  name: String,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+  next: Option<String>,
+}
+
+fn simplify(ty: &str) -> String {
+  let re = regex_static::static_regex!(r"\s+");
+  re.replace_all(ty, "").trim().to_string()
+}
+
+#[test]
+fn generic_declaration() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  let ty = declarations.add::<Page<User>>();
+
+  eq!(ty, Type::Apply(Apply {
+    name: "Page".into(),
+    args: vec![Arc::new(Type::Apply(Apply { name: "User".into(), args: vec![] }))],
+  }));
+
+  eq!(declarations.get("Page").unwrap(), &Declaration {
+    name: "Page".into(),
+    params: vec!["T".into()],
+    ty: Type::Object(Object {
+      properties: IndexMap::from([
+        (
+          "items".into(),
+          Property {
+            optional: false,
+            readonly: false,
+            ty: Arc::new(Type::Array(Array { item: Arc::new(Type::Param("T".into())) })),
+          },
+        ),
+        (
+          "next".into(),
+          Property {
+            optional: false,
+            readonly: false,
+            ty: Arc::new(Type::or([Type::String, Type::Null])),
+          },
+        ),
+      ]),
+    }),
+  });
+
+  assert_eq!(declarations.len(), 2);
+  assert!(declarations.contains("User"));
+}
+
+#[test]
+fn declared_once() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Pages {
+    users: Page<User>,
+    ids: Page<u32>,
+    owner: User,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Pages>();
+
+  assert_eq!(
+    declarations.iter().map(|d| &*d.name).collect::<Vec<_>>(),
+    vec!["Pages", "Page", "User"],
+  );
+
+  assert_eq!(
+    simplify(&declarations.get("Pages").unwrap().to_typescript()),
+    simplify("export type Pages = { users: Page<User>; ids: Page<number>; owner: User; };"),
+  );
+}
+
+#[test]
+fn to_typescript() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Page<User>>();
+
+  assert_eq!(
+    simplify(&declarations.to_typescript()),
+    simplify("
+      export type Page<T> = { items: Array<T>; next: (string | null); };
+      export type User = { id: number; name: string; };
+    "),
+  );
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+enum Role {
+  Admin,
+  Guest,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Index<K, V> {
+  entries: HashMap<K, V>,
+  roles: HashMap<Role, u8>,
+}
+
+#[test]
+fn map_keys() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  let ty = declarations.add::<Index<Role, User>>();
+
+  assert_eq!(
+    simplify(&declarations.to_typescript()),
+    simplify(r#"
+      export type Index<K, V> = { entries: { [key in K]?: V }; roles: { [key in Role]?: number }; };
+      export type Role = ("Admin" | "Guest");
+      export type User = { id: number; name: string; };
+    "#),
+  );
+
+  eq!(declarations.expand(&ty), Index::<Role, User>::shape(&ShapeOptions::for_serialize()));
+}
+
+#[test]
+fn recursive() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Tree {
+    children: Vec<Tree>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Tree>();
+
+  assert_eq!(
+    simplify(&declarations.to_typescript()),
+    simplify("export type Tree = { children: Array<Tree>; };"),
+  );
+
+  // the recursive references are kept when expanded
+  eq!(declarations.expand(&Type::Apply(Apply { name: "Tree".into(), args: vec![] })).to_typescript(), "{ children: Array<Tree>; }");
+}
+
+#[test]
+fn expand() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  let ty = declarations.add::<Page<User>>();
+
+  eq!(declarations.expand(&ty), Page::<User>::shape(&ShapeOptions::for_serialize()));
+}
+
+#[test]
+fn bounded_params_are_inlined() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Bounded<T: Default> {
+    item: T,
+    owner: User,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  let ty = declarations.add::<Bounded<String>>();
+
+  assert_eq!(
+    simplify(&ty.to_typescript()),
+    simplify("{ item: string; owner: User; }"),
+  );
+
+  assert_eq!(
+    declarations.iter().map(|d| &*d.name).collect::<Vec<_>>(),
+    vec!["User"],
+  );
+}
+
+#[test]
+fn inlined_without_declarations() {
+  let ty = Page::<User>::shape(&ShapeOptions::for_serialize());
+  assert_eq!(
+    simplify(&ty.to_typescript()),
+    simplify("{ items: Array<{ id: number; name: string; }>; next: (string | null); }"),
+  );
+}
+
+#[test]
+fn serde_rename() {
+  #[derive(Shape)]
+  #[serde(rename = "Account")]
+  #[allow(unused)]
+  struct UserRow {
+    id: u32,
+  }
+
+  #[derive(Shape)]
+  #[serde(rename(serialize = "Output", deserialize = "Input"))]
+  #[allow(unused)]
+  struct Payload {
+    user: UserRow,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  eq!(declarations.add::<Payload>(), Type::Apply(Apply { name: "Output".into(), args: vec![] }));
+  eq!(declarations.iter().map(|d| d.to_typescript()).collect::<Vec<_>>(), vec![
+    "export type Output = { user: Account; };",
+    "export type Account = { id: number; };",
+  ]);
+
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Payload>();
+  eq!(declarations.iter().map(|d| &*d.name).collect::<Vec<_>>(), vec!["Input", "Account"]);
+}
+
+mod other {
+  #[derive(shape::Shape)]
+  #[allow(unused)]
+  pub struct User {
+    pub email: String,
+  }
+}
+
+#[test]
+fn collisions() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Users {
+    active: Vec<User>,
+    invited: Vec<other::User>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Users>();
+  declarations.add::<other::User>();
+
+  eq!(declarations.iter().map(|d| &*d.name).collect::<Vec<_>>(), vec!["Users", "User"]);
+  eq!(declarations.collisions(), &[Collision {
+    name: String::from("User"),
+    first: "declarations::User",
+    second: "declarations::other::User",
+  }]);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde_json::json;
+use shape::{IsAsignable, Literal, Object, Property, Shape, ShapeOptions, Type};
+
+fn tag(name: &'static str, ty: Type) -> Type {
+  Type::Object(Object {
+    properties: IndexMap::from([(
+      name.into(),
+      Property {
+        optional: false,
+        readonly: false,
+        ty: Arc::new(ty),
+      },
+    )]),
+  })
+}
+
+fn string(value: &'static str) -> Type {
+  Type::Literal(Literal::String(value.into()))
+}
+
+#[test]
+fn other_internally_tagged() {
+  #[derive(Shape)]
+  #[serde(tag = "type")]
+  #[allow(unused)]
+  enum Event {
+    Created,
+    Deleted,
+    #[serde(other)]
+    Unknown,
+  }
+
+  eq!(
+    Event::shape(&ShapeOptions::for_serialize()),
+    Type::or([
+      tag("type", string("Created")),
+      tag("type", string("Deleted")),
+      tag("type", string("Unknown")),
+    ])
+  );
+
+  let de = Event::shape(&ShapeOptions::for_deserialize());
+
+  eq!(
+    de,
+    Type::or([
+      tag("type", string("Created")),
+      tag("type", string("Deleted")),
+      tag("type", Type::String),
+    ])
+  );
+
+  assert!(de.is_assignable(&json!({ "type": "Created" })));
+  assert!(de.is_assignable(&json!({ "type": "Renamed" })));
+  assert!(!de.is_assignable(&json!({ "type": 1 })));
+}
+
+#[test]
+fn other_adjacently_tagged() {
+  #[derive(Shape)]
+  #[serde(tag = "t", content = "c")]
+  #[allow(unused)]
+  enum Event {
+    Created,
+    #[serde(other)]
+    Unknown,
+  }
+
+  eq!(
+    Event::shape(&ShapeOptions::for_deserialize()),
+    Type::or([
+      tag("t", string("Created")),
+      tag("t", Type::String),
+    ])
+  );
+}
+
+#[test]
+fn other_externally_tagged() {
+  #[derive(Shape)]
+  #[serde(rename_all = "lowercase")]
+  #[allow(unused)]
+  enum Color {
+    Red,
+    Green,
+    #[serde(other)]
+    Other,
+  }
+
+  eq!(
+    Color::shape(&ShapeOptions::for_serialize()),
+    Type::or([string("red"), string("green"), string("other")])
+  );
+
+  eq!(
+    Color::shape(&ShapeOptions::for_deserialize()),
+    Type::or([string("red"), string("green"), Type::String])
+  );
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::net::{IpAddr, SocketAddrV4};
+use std::time::Duration;
+use std::sync::Arc;
+
+use indexmap::indexmap;
+use shape::{Array, Format, Object, Property, Record, RecordKey, Shape, ShapeOptions, ToTypescript, Tuple, Type};
+
+fn serialize(format: Format) -> ShapeOptions {
+  ShapeOptions { format, ..ShapeOptions::for_serialize() }
+}
+
+fn deserialize(format: Format) -> ShapeOptions {
+  ShapeOptions { format, ..ShapeOptions::for_deserialize() }
+}
+
+fn bytes(len: usize) -> Type {
+  Type::tuple(vec![Type::Number; len])
+}
+
+fn object(properties: Vec<(&str, bool, Type)>) -> Type {
+  Type::Object(Object {
+    properties: properties.into_iter().map(|(key, optional, ty)| (key.to_string().into(), Property { optional, readonly: false, ty: Arc::new(ty) })).collect(),
+  })
+}
+
+#[test]
+fn bytes_strings() {
+  let array = Type::Array(Array { item: Arc::new(Type::Number) });
+  eq!(CString::shape(&serialize(Format::Json)), array.clone());
+  eq!(CString::shape(&deserialize(Format::Json)), Type::or([array.clone(), Type::String]));
+  eq!(CString::shape(&deserialize(Format::Toml)), array);
+  eq!(CString::shape(&serialize(Format::MessagePack)), Type::Bytes);
+  eq!(CString::shape(&deserialize(Format::Cbor)).to_typescript(), "Uint8Array");
+
+  assert!(!Type::and([Type::Bytes, Type::String]).is_satisfiable());
+  assert!(Type::or([Type::Bytes, Type::Null]).is_satisfiable());
+}
+
+#[test]
+fn human_readable() {
+  eq!(IpAddr::shape(&serialize(Format::Yaml)), Type::String);
+  eq!(IpAddr::shape(&serialize(Format::Cbor)), Type::or([
+    object(vec![("V4", false, bytes(4))]),
+    object(vec![("V6", false, bytes(16))])
+  ]));
+  eq!(SocketAddrV4::shape(&serialize(Format::MessagePack)), Type::Tuple(Tuple { items: vec![Arc::new(bytes(4)), Arc::new(Type::Number)], rest: None }));
+
+  let duration = object(vec![("secs", false, Type::Number), ("nanos", false, Type::Number)]);
+  eq!(Duration::shape(&serialize(Format::Cbor)), duration.clone());
+  eq!(Duration::shape(&deserialize(Format::Json)), Type::or([duration, Type::Tuple(Tuple { items: vec![Arc::new(Type::Number), Arc::new(Type::Number)], rest: None })]));
+}
+
+#[test]
+fn map_keys() {
+  let record = |key| Type::Record(Record { optional: false, readonly: false, key, value: Arc::new(Type::Boolean) });
+  eq!(HashMap::<u32, bool>::shape(&serialize(Format::Json)), record(RecordKey::Integer));
+  eq!(HashMap::<u32, bool>::shape(&serialize(Format::Yaml)), record(RecordKey::Integer));
+  eq!(HashMap::<(u8, u8), bool>::shape(&serialize(Format::Json)), record(RecordKey::Unsupported(Arc::new(bytes(2)))));
+  eq!(HashMap::<(u8, u8), bool>::shape(&serialize(Format::MessagePack)), record(RecordKey::Type(Arc::new(bytes(2)))));
+  // TOML only has string keys
+  eq!(HashMap::<u32, bool>::shape(&serialize(Format::Toml)), record(RecordKey::Unsupported(Arc::new(Type::Number))));
+  eq!(HashMap::<String, bool>::shape(&serialize(Format::Toml)), record(RecordKey::String));
+}
+
+#[test]
+fn toml_nulls() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Config {
+    name: Option<String>,
+    unit: (),
+  }
+
+  #[derive(Shape)]
+  struct Marker;
+
+  eq!(Config::shape(&serialize(Format::Toml)), Type::Object(Object {
+    properties: indexmap! {
+      "name".into() => Property { optional: true, readonly: false, ty: Arc::new(Type::or([Type::String, Type::Undefined])) },
+      "unit".into() => Property { optional: false, readonly: false, ty: Arc::new(Type::Never) }
+    }
+  }));
+  eq!(Marker::shape(&serialize(Format::Toml)), Type::Never);
+  eq!(Marker::shape(&serialize(Format::MessagePack)), Type::Null);
+  eq!(Option::<u8>::shape(&serialize(Format::Cbor)), Type::or([Type::Number, Type::Null]));
+}
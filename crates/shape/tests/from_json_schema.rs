@@ -0,0 +1,125 @@
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::json;
+use shape::{parse_json_schema, parse_json_schema_declarations, shape, Declaration, JsonSchemaError, JsonSchemaErrorKind, RecordKey, Shape, ShapeOptions, Type};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  name: String,
+  email: Option<String>,
+  tags: Vec<String>,
+}
+
+#[test]
+fn parse() {
+  let schema = json!({
+    "$schema": "https://json-schema.org/draft/2020-12/schema",
+    "title": "User",
+    "type": "object",
+    "required": ["id", "name", "tags"],
+    "properties": {
+      "id": { "type": "integer", "minimum": 0, "readOnly": true },
+      "name": { "type": "string", "minLength": 1 },
+      "email": { "type": ["string", "null"], "format": "email" },
+      "tags": { "type": "array", "items": { "type": "string" } },
+      "role": { "enum": ["admin", "guest"] },
+      "point": { "type": "array", "prefixItems": [{ "type": "number" }, { "type": "number" }], "items": false }
+    }
+  });
+
+  eq!(parse_json_schema(&schema).unwrap(), shape!{{
+    readonly id: number,
+    name: string,
+    email?: string | null,
+    tags: string[],
+    role?: "admin" | "guest",
+    point?: [number, number]
+  }});
+}
+
+#[test]
+fn keywords() {
+  let parse = |schema| parse_json_schema(&schema).unwrap();
+
+  eq!(parse(json!(true)), Type::Unknown);
+  eq!(parse(json!({})), Type::Unknown);
+  eq!(parse(json!(false)), Type::Never);
+  eq!(parse(json!({ "const": null })), Type::Null);
+  eq!(parse(json!({ "const": 1 })), Type::literal(1.0));
+  eq!(parse(json!({ "type": "string", "nullable": true })), Type::String.or_null());
+  eq!(parse(json!({ "anyOf": [{ "type": "boolean" }, { "$ref": "#/$defs/User" }] })), shape!{ boolean | User });
+  eq!(parse(json!({ "allOf": [{ "$ref": "#/definitions/a~1b" }, { "properties": { "id": {} } }] })), shape!{ #(Type::Apply(shape::Apply { name: "a/b".into(), args: vec![] })) & { id?: unknown } });
+
+  // maps and objects with extra properties
+  eq!(parse(json!({ "type": "object" })), Type::record(RecordKey::String, Type::Unknown));
+  eq!(parse(json!({ "additionalProperties": { "type": "number" } })), shape!{ Record<string, number> });
+  eq!(parse(json!({ "properties": { "a": true }, "additionalProperties": false })), shape!{ { a?: unknown } });
+  eq!(parse(json!({ "properties": { "a": true }, "additionalProperties": { "type": "null" } })), shape!{ { a?: unknown, [key: string]: null } });
+  eq!(parse(json!({ "propertyNames": { "enum": ["a", "b"] }, "additionalProperties": true })), Type::Record(shape::Record {
+    optional: true,
+    readonly: false,
+    key: RecordKey::Type(Arc::new(Type::or([Type::literal("a"), Type::literal("b")]))),
+    value: Arc::new(Type::Unknown)
+  }));
+
+  // tuples of the older drafts
+  eq!(parse(json!({ "type": "array", "items": [{ "type": "string" }], "additionalItems": { "type": "number" } })), shape!{ [string, ...number[]] });
+  eq!(parse(json!({ "type": "array", "items": [{ "type": "string" }], "maxItems": 1 })), shape!{ [string] });
+  eq!(parse(json!({ "type": "array", "items": [] })), shape!{ [...unknown[]] });
+}
+
+#[test]
+fn declarations() {
+  let schema = json!({
+    "$ref": "#/$defs/User",
+    "$defs": {
+      "User": { "properties": { "id": { "type": "number" } }, "required": ["id"] }
+    },
+    "definitions": {
+      "Users": { "type": "array", "items": { "$ref": "#/$defs/User" } }
+    }
+  });
+
+  eq!(parse_json_schema(&schema).unwrap(), shape!{ User });
+  eq!(parse_json_schema_declarations(&schema).unwrap(), vec![
+    Declaration { name: "User".into(), params: vec![], ty: shape!{ { id: number } } },
+    Declaration { name: "Users".into(), params: vec![], ty: shape!{ User[] } }
+  ]);
+}
+
+#[test]
+fn errors() {
+  let error = |schema| parse_json_schema(&schema).unwrap_err();
+
+  eq!(error(json!({ "properties": { "a": { "not": {} } } })), JsonSchemaError {
+    path: String::from("/properties/a/not"),
+    kind: JsonSchemaErrorKind::Unsupported(String::from("not"))
+  });
+  eq!(error(json!({ "items": { "$ref": "other.json" } })).to_string(), "/items/$ref: only references to `#/$defs` and `#/definitions` are supported, found `other.json`");
+  eq!(error(json!({ "required": "id" })).to_string(), "/required: invalid value for `required`");
+  eq!(error(json!({ "anyOf": [1] })).to_string(), "/anyOf/0: invalid value for `schema`");
+  eq!(error(json!({ "const": [1] })).to_string(), "/const: `const` can't be represented as a shape");
+}
+
+#[test]
+fn contract() {
+  // the serialized users satisfy the hand written schema, but the schema accepts more
+  let contract = parse_json_schema(&json!({
+    "type": "object",
+    "required": ["id", "name"],
+    "properties": {
+      "id": { "type": "integer" },
+      "name": { "type": "string" },
+      "email": { "type": ["string", "null"] },
+      "tags": { "type": "array", "items": { "type": "string" } }
+    }
+  })).unwrap();
+
+  let ty = User::shape(&ShapeOptions::for_serialize());
+  assert!(ty.is_subtype_of(&contract));
+  assert!(!contract.is_subtype_of(&ty));
+}
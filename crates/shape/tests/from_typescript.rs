@@ -0,0 +1,120 @@
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use shape::{parse_typescript, parse_typescript_declarations, shape, Declaration, Declarations, Record, RecordKey, Shape, ShapeOptions, ToTypescript, Type, TypescriptError};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  #[serde(rename = "display-name")]
+  name: String,
+  tags: Vec<(String, Option<u8>)>,
+  bytes: Box<[u8; 2]>,
+  flags: BTreeMap<bool, ()>,
+  roles: BTreeMap<Role, bool>,
+  kind: Kind,
+}
+
+#[derive(Shape, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(unused)]
+enum Role {
+  Admin,
+  Guest,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+#[serde(tag = "type")]
+enum Kind {
+  Person { age: u8 },
+  Bot,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+  next: Option<String>,
+}
+
+#[test]
+fn round_trip() {
+  for options in [ShapeOptions::for_serialize(), ShapeOptions::for_deserialize()] {
+    let ty = User::shape(&options);
+    eq!(parse_typescript(&ty.to_typescript()).unwrap(), ty);
+  }
+
+  // integer keys are written as number keys
+  let ty = HashMap::<i32, f64>::shape(&ShapeOptions::for_serialize());
+  let parsed = parse_typescript(&ty.to_typescript()).unwrap();
+  eq!(parsed, Type::record(RecordKey::Number, Type::Number));
+  assert!(ty.is_subtype_of(&parsed) && !parsed.is_subtype_of(&ty));
+
+  let ty = shape!{ [string, ...number[]] | Record<"a" | "b", unknown> | { [key: string]: never } };
+  eq!(parse_typescript(&ty.to_typescript()).unwrap(), Type::or([
+    // the rest of a tuple is not written
+    Type::tuple([Type::String]),
+    Type::Record(Record {
+      optional: false,
+      readonly: false,
+      key: RecordKey::Type(Arc::new(Type::or([Type::literal("a"), Type::literal("b")]))),
+      value: Arc::new(Type::Unknown)
+    }),
+    Type::record(RecordKey::String, Type::Never)
+  ]));
+}
+
+#[test]
+fn declarations() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Page<User>>();
+
+  let parsed = parse_typescript_declarations(&declarations.to_typescript()).unwrap();
+  eq!(parsed, declarations.iter().cloned().collect::<Vec<_>>());
+
+  // hand written declarations
+  let parsed = parse_typescript_declarations(r#"
+    // a page of items
+    export interface Page<T extends object = {}> extends Base {
+      items: T[];
+      readonly next?: string | null,
+    }
+
+    /* users */
+    type Users = Page<{ 'id': number; 0: boolean }>
+  "#).unwrap();
+
+  eq!(parsed, vec![
+    Declaration {
+      name: "Page".into(),
+      params: vec![String::from("T")],
+      ty: shape!{ Base & { items: T[]; readonly next?: string | null } }.fold(|ty, _| match ty {
+        Type::Apply(apply) if apply.name == "T" => Type::Param(apply.name),
+        ty => ty,
+      })
+    },
+    Declaration {
+      name: "Users".into(),
+      params: vec![],
+      ty: shape!{ Page<{ id: number, "0": boolean }> }
+    }
+  ]);
+}
+
+#[test]
+fn syntax() {
+  eq!(parse_typescript("readonly (-1.5 | 'a\\'b' | true)[]").unwrap(), shape!{ (-1.5 | "a'b" | true)[] });
+  eq!(parse_typescript("any & void & Uint8Array & ReadonlyArray<Date>").unwrap(), shape!{ unknown & undefined & Uint8Array & Date[] });
+  eq!(parse_typescript("{ readonly: string, [key in never]: number }").unwrap(), Type::and([
+    Type::object().field("readonly", Type::String).into(),
+    Type::record(RecordKey::Unsupported(Arc::new(Type::Never)), Type::Number)
+  ]));
+
+  eq!(parse_typescript("{ id: number").unwrap_err(), TypescriptError { offset: 12, message: String::from("expected `;`, `,` or `}`") });
+  eq!(parse_typescript("keyof User").unwrap_err().to_string(), "`keyof` types are not supported at byte 0");
+  eq!(parse_typescript("string number").unwrap_err().to_string(), "expected the end of the type at byte 7");
+  eq!(parse_typescript("Record<string>").unwrap_err().to_string(), "wrong number of type arguments for `Record` at byte 0");
+}
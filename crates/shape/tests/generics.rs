@@ -1,5 +1,7 @@
 mod common;
 
+use std::sync::Arc;
+
 use indexmap::IndexMap;
 use shape::{Object, Property, Shape, ShapeOptions, Type};
 
@@ -16,7 +18,7 @@ fn generics() {
     properties: IndexMap::from([(
       "field".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -38,7 +40,7 @@ fn generics_with_where_clause() {
     properties: IndexMap::from([(
       "field".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -0,0 +1,78 @@
+mod common;
+
+use serde_json::json;
+use shape::{infer, shape, InferOptions, ToTypescript, Type};
+
+#[test]
+fn merge() {
+  let samples = [
+    json!({ "id": 1, "name": "Alice", "status": "active", "tags": ["a", "b"], "manager": null }),
+    json!({ "id": 2, "name": "Bob", "status": "active", "tags": [], "manager": { "id": 1 } }),
+    json!({ "id": 3, "name": "Carol", "status": "disabled", "email": "carol@example.com", "manager": { "id": 1, "name": "Alice" } }),
+    json!({ "id": 4, "name": "Dave", "status": "active", "manager": null })
+  ];
+
+  // the objects with a `disabled` status have different properties
+  let options = InferOptions { discriminators: false, ..InferOptions::default() };
+  eq!(infer(&samples, &options), shape!{{
+    id: number,
+    name: string,
+    status: "active" | "disabled",
+    tags?: string[],
+    manager: { id: number, name?: string } | null,
+    email?: string
+  }});
+}
+
+#[test]
+fn values() {
+  let options = InferOptions::default();
+  let infer = |samples: &[serde_json::Value]| infer(samples, &options);
+
+  eq!(infer(&[]), Type::Never);
+  eq!(infer(&[json!(null)]), Type::Null);
+  eq!(infer(&[json!(null), json!(true), json!(1.5)]), shape!{ boolean | number | null });
+  eq!(infer(&[json!([])]), shape!{ unknown[] });
+  eq!(infer(&[json!([1, 2, 3]), json!(["a"])]), shape!{ (number | string)[] });
+
+  // tuples have the same length and different types in some position
+  eq!(infer(&[json!([1, "a"]), json!([2, "b"])]), shape!{ [number, string] });
+  eq!(infer(&[json!([1, 2])]), shape!{ number[] });
+  eq!(infer(&[json!([1, "a"]), json!([2, "b", 3])]), shape!{ (number | string)[] });
+
+  // strings repeated a few times are literals
+  eq!(infer(&[json!("a"), json!("b")]), Type::String);
+  eq!(infer(&[json!("a"), json!("a")]), Type::literal("a"));
+  eq!(infer(&[json!("a"), json!("b"), json!("a")]), shape!{ "a" | "b" });
+}
+
+#[test]
+fn options() {
+  let samples = [json!({ "point": [1, "x"], "kind": "a" }), json!({ "point": [2, "y"], "kind": "a" })];
+  let options = InferOptions { max_literals: 0, tuples: false, discriminators: true };
+  eq!(infer(&samples, &options), shape!{ { point: (number | string)[], kind: string } });
+}
+
+#[test]
+fn tagged() {
+  let samples = [
+    json!({ "type": "circle", "id": 1, "radius": 1.0 }),
+    json!({ "type": "square", "id": 2, "side": 2.0 }),
+    json!({ "type": "circle", "id": 3, "radius": 3.0, "label": "c" })
+  ];
+
+  let ty = infer(&samples, &InferOptions::default());
+  eq!(ty, shape!{
+    | { type: "circle", id: number, radius: number, label?: string }
+    | { type: "square", id: number, side: number }
+  });
+  eq!(
+    ty.to_typescript(),
+    "({ type: \"circle\"; id: number; radius: number; label?: string; } | { type: \"square\"; id: number; side: number; })"
+  );
+
+  // a string property without different properties for each value is not a tag
+  let samples = [json!({ "type": "a", "id": 1 }), json!({ "type": "b", "id": 2 })];
+  eq!(infer(&samples, &InferOptions::default()), shape!{ { type: string, id: number } });
+  eq!(infer(&samples, &InferOptions { discriminators: false, ..InferOptions::default() }), shape!{ { type: string, id: number } });
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use indexmap::IndexMap;
 use shape::{Literal, Object, Property, ShapeOptions, Type};
 use shape::Shape;
@@ -16,7 +18,7 @@ fn snake_upper() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -24,18 +26,18 @@ fn snake_upper() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ]),
@@ -58,7 +60,7 @@ fn screaming_snake_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -66,18 +68,18 @@ fn screaming_snake_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -100,7 +102,7 @@ fn lowercase() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -108,18 +110,18 @@ fn lowercase() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ]),
@@ -142,7 +144,7 @@ fn camel_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -150,18 +152,18 @@ fn camel_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ]),
@@ -184,7 +186,7 @@ fn pascal_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -192,18 +194,18 @@ fn pascal_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),       
+            })),       
           },
         ),
       ]),
@@ -226,7 +228,7 @@ fn screaming_kebab_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -234,18 +236,18 @@ fn screaming_kebab_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ]),
@@ -268,7 +270,7 @@ fn kebab_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -276,18 +278,18 @@ fn kebab_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -310,7 +312,7 @@ fn uppercase() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -318,18 +320,18 @@ fn uppercase() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },      
         ),
       ])
@@ -354,7 +356,7 @@ fn rename_all_fields_snake_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -362,18 +364,18 @@ fn rename_all_fields_snake_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some_field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            })
+            }))
           }
         ),
       ])
@@ -397,7 +399,7 @@ fn rename_all_fields_screaming_snake_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -405,18 +407,18 @@ fn rename_all_fields_screaming_snake_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SOME_FIELD".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            })
+            }))
           }  
         ),
       ])
@@ -440,7 +442,7 @@ fn rename_all_fields_lowercase() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -448,18 +450,18 @@ fn rename_all_fields_lowercase() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "somefield".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
             
           },
         ),
@@ -484,7 +486,7 @@ fn rename_all_fields_uppercase() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -492,18 +494,18 @@ fn rename_all_fields_uppercase() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SOMEFIELD".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -526,7 +528,7 @@ fn rename_all_fields_camel_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -534,18 +536,18 @@ fn rename_all_fields_camel_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "someField".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
             
           },
         ),
@@ -570,7 +572,7 @@ fn rename_all_fields_screaming_kebab_case() {
     },
   }
 
-  let ser = Type::Or(vec![
+  let ser = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -578,25 +580,25 @@ fn rename_all_fields_screaming_kebab_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SOME-FIELD".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
     })
   ]);
 
-  let de = Type::Or(vec![
+  let de = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -604,18 +606,18 @@ fn rename_all_fields_screaming_kebab_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "someField".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -639,7 +641,7 @@ fn rename_all_fields_kebab_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -647,18 +649,18 @@ fn rename_all_fields_kebab_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "some-field".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -682,7 +684,7 @@ fn rename_all_fields_pascal_case() {
     },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -690,18 +692,18 @@ fn rename_all_fields_pascal_case() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SomeField".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -724,7 +726,7 @@ fn variant_rename_all() {
     },
   }
 
-  let ser = Type::Or(vec![
+  let ser = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -732,18 +734,18 @@ fn variant_rename_all() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SomeField".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
 
           },      
         ),
@@ -751,7 +753,7 @@ fn variant_rename_all() {
     })
   ]);
 
-  let de = Type::Or(vec![
+  let de = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -759,18 +761,18 @@ fn variant_rename_all() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SOME_FIELD".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ])
@@ -792,12 +794,12 @@ fn variant_rename() {
     VariantTwo,
   }
 
-  let ser = Type::Or(vec![
+  let ser = Type::or([
     Type::Literal(Literal::String("variant_one".into())),
     Type::Literal(Literal::String("VARI_TWO".into())),
   ]);
   
-  let de = Type::Or(vec![
+  let de = Type::or([
     Type::Literal(Literal::String("VARIANT-ONE".into())),
     Type::Literal(Literal::String("VARI_TWO".into())),
   ]);
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use indexmap::IndexMap;
 use shape::{Object, Property, ShapeOptions, Type};
 use shape::Shape;
@@ -21,7 +23,7 @@ fn snake_case() {
       (
         "some_field".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -48,7 +50,7 @@ fn kebab_case() {
       (
         "some-field".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -76,7 +78,7 @@ fn screaming_snake_case() {
       (
         "SOME_FIELD".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -104,7 +106,7 @@ fn pascal_case() {
       (
         "SomeField".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -132,7 +134,7 @@ fn camel_case() {
       (
         "someField".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -160,7 +162,7 @@ fn screaming_kebab_case() {
       (
         "SOME-FIELD".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -188,7 +190,7 @@ fn uppercase() {
       (
         "SOMEFIELD".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -216,7 +218,7 @@ fn lowercase() {
       (
         "some_field".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -1,8 +1,10 @@
 mod common;
 
+use std::sync::Arc;
+
 use indexmap::indexmap;
 use serde_json::json;
-use shape::{Array, IsAsignable, Literal, Object, Property, Record, Tuple, Type};
+use shape::{Array, IsAsignable, Literal, Object, Property, Record, RecordKey, Tuple, Type};
 
 #[test]
 fn primitives() {
@@ -41,37 +43,37 @@ fn never() {
 #[test]
 fn tuple() {
   assert!(Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
     rest: None,
   })
   .is_assignable(&json!(["a", 1])));
 
   assert!(Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
-    rest: Some(Box::new(Type::Boolean)),
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
+    rest: Some(Arc::new(Type::Boolean)),
   })
   .is_assignable(&json!(["a", 1, true, false])));
 
   assert!(Type::Tuple(Tuple {
-    items: vec![Type::Literal(Literal::String("a".into())), Type::Number],
-    rest: Some(Box::new(Type::Boolean)),
+    items: vec![Arc::new(Type::Literal(Literal::String("a".into()))), Arc::new(Type::Number)],
+    rest: Some(Arc::new(Type::Boolean)),
   })
   .is_assignable(&json!(["a", 1, true, false])));
 
   assert!(!Type::Tuple(Tuple {
-    items: vec![Type::Literal(Literal::String("a".into())), Type::Number],
-    rest: Some(Box::new(Type::Boolean)),
+    items: vec![Arc::new(Type::Literal(Literal::String("a".into()))), Arc::new(Type::Number)],
+    rest: Some(Arc::new(Type::Boolean)),
   })
   .is_assignable(&json!(["b", 1, true, false])));
 
   assert!(!Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
-    rest: Some(Box::new(Type::Boolean)),
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
+    rest: Some(Arc::new(Type::Boolean)),
   })
   .is_assignable(&json!(["a", 1, 2, false])));
 
   assert!(!Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
     rest: None,
   })
   .is_assignable(&json!([1])));
@@ -87,8 +89,8 @@ fn objects() {
   // Caso 1: Objeto con propiedades obligatorias.
   let obj_type = Type::Object(Object {
     properties: indexmap! {
-        "name".to_string() => Property { ty: Type::String, optional: false, readonly: false },
-        "age".to_string() => Property { ty: Type::Number, optional: false, readonly: false },
+        "name".into() => Property { ty: Arc::new(Type::String), optional: false, readonly: false },
+        "age".into() => Property { ty: Arc::new(Type::Number), optional: false, readonly: false },
     },
   });
   assert!(obj_type.is_assignable(&json!({ "name": "Alice", "age": 30 })));
@@ -97,8 +99,8 @@ fn objects() {
   // Caso 2: Propiedad opcional.
   let obj_type = Type::Object(Object {
     properties: indexmap! {
-        "name".to_string() => Property { ty: Type::String, optional: false, readonly: false },
-        "age".to_string() => Property { ty: Type::Number, optional: true, readonly: false },
+        "name".into() => Property { ty: Arc::new(Type::String), optional: false, readonly: false },
+        "age".into() => Property { ty: Arc::new(Type::Number), optional: true, readonly: false },
     },
   });
   assert!(obj_type.is_assignable(&json!({ "name": "Alice" }))); // "age" opcional.
@@ -107,7 +109,7 @@ fn objects() {
   // Caso 3: Propiedad de solo lectura.
   let obj_type = Type::Object(Object {
     properties: indexmap! {
-        "id".to_string() => Property { ty: Type::Number, optional: false, readonly: true },
+        "id".into() => Property { ty: Arc::new(Type::Number), optional: false, readonly: true },
     },
   });
   assert!(obj_type.is_assignable(&json!({ "id": 42 }))); // "readonly" no afecta asignabilidad.
@@ -115,7 +117,7 @@ fn objects() {
   // Caso 4: Propiedad con un tipo compuesto.
   let obj_type = Type::Object(Object {
     properties: indexmap! {
-        "data".to_string() => Property { ty: Type::Array(Array { item: Box::new(Type::String) }), optional: false, readonly: false },
+        "data".into() => Property { ty: Arc::new(Type::Array(Array { item: Arc::new(Type::String) })), optional: false, readonly: false },
     },
   });
   assert!(obj_type.is_assignable(&json!({ "data": ["a", "b", "c"] })));
@@ -130,26 +132,26 @@ fn objects() {
 fn arrays() {
   // Caso 5: Array de un tipo simple.
   let array_type = Type::Array(Array {
-    item: Box::new(Type::Number),
+    item: Arc::new(Type::Number),
   });
   assert!(array_type.is_assignable(&json!([1, 2, 3])));
   assert!(!array_type.is_assignable(&json!([1, "a", 3]))); // Tipo incorrecto.
 
   // Caso 6: Array vacío.
   let array_type = Type::Array(Array {
-    item: Box::new(Type::String),
+    item: Arc::new(Type::String),
   });
   assert!(array_type.is_assignable(&json!([]))); // Un array vacío debería ser asignable.
 
   // Caso 7: Array con tipos literales.
   let array_type = Type::Array(Array {
-    item: Box::new(Type::Literal(Literal::String("a".into()))),
+    item: Arc::new(Type::Literal(Literal::String("a".into()))),
   });
   assert!(array_type.is_assignable(&json!(["a", "a", "a"])));
   assert!(!array_type.is_assignable(&json!(["a", "b"]))); // Elemento incorrecto.
 
   assert!(!Type::Array(Array {
-    item: Box::new(Type::String),
+    item: Arc::new(Type::String),
   }).is_assignable(&json!({ "a": 1 })))
 }
 
@@ -159,8 +161,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::String),
-    value: Box::new(Type::Number),
+    key: RecordKey::String,
+    value: Arc::new(Type::Number),
   });
   assert!(record_type.is_assignable(&json!({ "a": 1, "b": 2 })));
   assert!(!record_type.is_assignable(&json!({ "a": "1", "b": 2 }))); // Valor incorrecto.
@@ -169,8 +171,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: true,
     readonly: false,
-    key: Box::new(Type::String),
-    value: Box::new(Type::Boolean),
+    key: RecordKey::String,
+    value: Arc::new(Type::Boolean),
   });
   assert!(record_type.is_assignable(&json!({}))); // Record vacío permitido.
   assert!(record_type.is_assignable(&json!({ "a": true, "b": false })));
@@ -179,8 +181,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Literal(Literal::String("key".into()))),
-    value: Box::new(Type::Or(vec![Type::Number, Type::String])),
+    key: RecordKey::Type(Arc::new(Type::Literal(Literal::String("key".into())))),
+    value: Arc::new(Type::or([Type::Number, Type::String])),
   });
   assert!(record_type.is_assignable(&json!({ "key": 42 })));
   assert!(record_type.is_assignable(&json!({ "key": "value" })));
@@ -190,8 +192,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Number),
-    value: Box::new(Type::Number),
+    key: RecordKey::Number,
+    value: Arc::new(Type::Number),
   });
   assert!(record_type.is_assignable(&json!({ "1": 42 })));
   assert!(record_type.is_assignable(&json!({ "3.0": 42.0 })));
@@ -201,8 +203,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Or( vec![ Type::Literal(Literal::Number(5.0)), Type::Literal(Literal::String("30".into())) ])),
-    value: Box::new(Type::Number),
+    key: RecordKey::Type(Arc::new(Type::or([ Type::Literal(Literal::Number(5.0)), Type::Literal(Literal::String("30".into())) ]))),
+    value: Arc::new(Type::Number),
   });
   assert!(record_type.is_assignable(&json!({ "30": 42, "5": 1 })));
   assert!(!record_type.is_assignable(&json!({ "5": 42.0 })));
@@ -211,8 +213,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::And( vec![ Type::Literal(Literal::String("a".into())), Type::Literal(Literal::String("b".into())) ])),
-    value: Box::new(Type::Number),
+    key: RecordKey::Type(Arc::new(Type::and([ Type::Literal(Literal::String("a".into())), Type::Literal(Literal::String("b".into())) ]))),
+    value: Arc::new(Type::Number),
   });
   assert!(record_type.is_assignable(&json!({})));
   assert!(record_type.is_assignable(&json!({ "a": 42 })));
@@ -220,8 +222,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::And( vec![ Type::Literal(Literal::String("a".into())), Type::Literal(Literal::String("a".into())) ])),
-    value: Box::new(Type::Number),
+    key: RecordKey::Type(Arc::new(Type::and([ Type::Literal(Literal::String("a".into())), Type::Literal(Literal::String("a".into())) ]))),
+    value: Arc::new(Type::Number),
   });
   assert!(record_type.is_assignable(&json!({ "a": 42 })));
   assert!(!record_type.is_assignable(&json!({ "a": "b" })));
@@ -229,8 +231,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::String),
-    value: Box::new(Type::Never),
+    key: RecordKey::String,
+    value: Arc::new(Type::Never),
   });
   assert!(record_type.is_assignable(&json!({})));
   assert!(!record_type.is_assignable(&json!({ "a": "b" })));
@@ -239,8 +241,8 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Custom("a".into())),
-    value: Box::new(Type::Never),
+    key: RecordKey::Type(Arc::new(Type::Custom("a".into()))),
+    value: Arc::new(Type::Never),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
@@ -248,88 +250,88 @@ fn records() {
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Null),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Null)),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Undefined),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Undefined)),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Array(Array { item: Box::new(Type::String) })),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Array(Array { item: Arc::new(Type::String) }))),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Object(Object { properties: indexmap! {} })),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Object(Object { properties: indexmap! {} }))),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Never),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Never)),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Boolean),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Boolean)),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Record(Record{ optional: false, readonly: false, key: Box::new(Type::String), value: Box::new(Type::String) })),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Record(Record{ optional: false, readonly: false, key: RecordKey::String, value: Arc::new(Type::String) }))),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Tuple(Tuple{ items: vec![Type::String, Type::Number], rest: None })),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Tuple(Tuple{ items: vec![Arc::new(Type::String), Arc::new(Type::Number)], rest: None }))),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Literal(Literal::Boolean(true))),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Literal(Literal::Boolean(true)))),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::Literal(Literal::Boolean(true))),
-    value: Box::new(Type::String),
+    key: RecordKey::Type(Arc::new(Type::Literal(Literal::Boolean(true)))),
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!({})));
 
   let record_type = Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::String),
-    value: Box::new(Type::String),
+    key: RecordKey::String,
+    value: Arc::new(Type::String),
   });
   assert!(!record_type.is_assignable(&json!([])));
 }
@@ -338,21 +340,21 @@ fn records() {
 #[test]
 fn and_type() {
   // Caso 1: Un valor que es tanto un número como un literal específico.
-  let and_type = Type::And(vec![Type::Number, Type::Literal(Literal::Number(42.0))]);
+  let and_type = Type::and([Type::Number, Type::Literal(Literal::Number(42.0))]);
   assert!(and_type.is_assignable(&json!(42)));
   assert!(!and_type.is_assignable(&json!(43))); // No es el literal correcto.
   assert!(!and_type.is_assignable(&json!("42"))); // No es un número.
 
   // Caso 2: Objeto con una propiedad específica y un tipo compuesto.
-  let and_type = Type::And(vec![
+  let and_type = Type::and([
     Type::Object(Object {
       properties: indexmap! {
-          "key".to_string() => Property { ty: Type::String, optional: false, readonly: false },
+          "key".into() => Property { ty: Arc::new(Type::String), optional: false, readonly: false },
       },
     }),
     Type::Object(Object {
       properties: indexmap! {
-          "value".to_string() => Property { ty: Type::Number, optional: false, readonly: false },
+          "value".into() => Property { ty: Arc::new(Type::Number), optional: false, readonly: false },
       },
     }),
   ]);
@@ -361,12 +363,12 @@ fn and_type() {
   assert!(!and_type.is_assignable(&json!({ "value": 123 }))); // Falta "key".
 
   // Caso 3: Un array que debe cumplir dos condiciones.
-  let and_type = Type::And(vec![
+  let and_type = Type::and([
     Type::Array(Array {
-      item: Box::new(Type::String),
+      item: Arc::new(Type::String),
     }),
     Type::Array(Array {
-      item: Box::new(Type::Literal(Literal::String("test".into()))),
+      item: Arc::new(Type::Literal(Literal::String("test".into()))),
     }),
   ]);
   assert!(and_type.is_assignable(&json!(["test", "test"])));
@@ -376,13 +378,13 @@ fn and_type() {
 #[test]
 fn or_type() {
   // Caso 4: Un valor que puede ser un número o una cadena.
-  let or_type = Type::Or(vec![Type::Number, Type::String]);
+  let or_type = Type::or([Type::Number, Type::String]);
   assert!(or_type.is_assignable(&json!(42)));
   assert!(or_type.is_assignable(&json!("hello")));
   assert!(!or_type.is_assignable(&json!(true))); // No es número ni cadena.
 
   // Caso 5: Un valor que puede ser un literal o un tipo más general.
-  let or_type = Type::Or(vec![
+  let or_type = Type::or([
     Type::Literal(Literal::String("specific".into())),
     Type::String,
   ]);
@@ -391,15 +393,15 @@ fn or_type() {
   assert!(!or_type.is_assignable(&json!(123))); // No es una cadena.
 
   // Caso 6: Objeto con una propiedad opcional o clave específica.
-  let or_type = Type::Or(vec![
+  let or_type = Type::or([
     Type::Object(Object {
       properties: indexmap! {
-          "optionalKey".to_string() => Property { ty: Type::String, optional: false, readonly: false },
+          "optionalKey".into() => Property { ty: Arc::new(Type::String), optional: false, readonly: false },
       },
     }),
     Type::Object(Object {
       properties: indexmap! {
-          "requiredKey".to_string() => Property { ty: Type::Number, optional: false, readonly: false },
+          "requiredKey".into() => Property { ty: Arc::new(Type::Number), optional: false, readonly: false },
       },
     }),
   ]);
@@ -408,12 +410,12 @@ fn or_type() {
   assert!(!or_type.is_assignable(&json!({ "anotherKey": true }))); // No cumple con ninguno.
 
   // Caso 7: Array que puede contener números o cadenas.
-  let or_type = Type::Or(vec![
+  let or_type = Type::or([
     Type::Array(Array {
-      item: Box::new(Type::Number),
+      item: Arc::new(Type::Number),
     }),
     Type::Array(Array {
-      item: Box::new(Type::String),
+      item: Arc::new(Type::String),
     }),
   ]);
   assert!(or_type.is_assignable(&json!([1, 2, 3])));
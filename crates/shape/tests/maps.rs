@@ -1,31 +1,167 @@
 mod common;
 
 use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 use indexmap::IndexMap;
-use shape::{Record, Shape, ShapeOptions, Type};
+use serde_json::json;
+use shape::{check_type, Declarations, IsAsignable, JtdErrorKind, Literal, Record, RecordKey, Shape, ShapeOptions, ToJtd, ToTypescript, Type};
 
 #[test]
 fn maps() {
   eq!(HashMap::<String, i32>::shape(&ShapeOptions::for_serialize()), Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::String),
-    value: Box::new(Type::Number),
+    key: RecordKey::String,
+    value: Arc::new(Type::Number),
   }));
 
   eq!(IndexMap::<String, i32>::shape(&ShapeOptions::for_serialize()), Type::Record(Record {
     optional: false,
     readonly: false,
-    key: Box::new(Type::String),
-    value: Box::new(Type::Number),
+    key: RecordKey::String,
+    value: Arc::new(Type::Number),
   }));
 
   eq!(BTreeMap::<String, i32>::shape(&ShapeOptions::for_serialize()), Type::Record(
     Record {
       optional: false,
       readonly: false,
-      key: Box::new(Type::String),
-      value: Box::new(Type::Number),
+      key: RecordKey::String,
+      value: Arc::new(Type::Number),
   }));
-}
\ No newline at end of file
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+enum Key {
+  A,
+  B,
+}
+
+#[test]
+fn integer_keys() {
+  let ty = HashMap::<i64, bool>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::Record(Record { optional: false, readonly: false, key: RecordKey::Integer, value: Arc::new(Type::Boolean) }));
+  eq!(ty.to_typescript(), "{ [key: number]: boolean }");
+
+  assert!(ty.is_assignable(&json!({ "0": true, "-12": false })));
+  assert!(!ty.is_assignable(&json!({ "1.5": true })));
+  assert!(!ty.is_assignable(&json!({ "01": true })));
+  assert!(!ty.is_assignable(&json!({ "+1": true })));
+  assert!(!ty.is_assignable(&json!({ "-0": true, "a": true })));
+
+  let ty = HashMap::<f64, bool>::shape(&ShapeOptions::for_serialize());
+  assert!(ty.is_assignable(&json!({ "1.5": true, "-2": false, "1e3": true })));
+  assert!(!ty.is_assignable(&json!({ "a": true })));
+
+  // serde_json only reads finite JSON numbers as keys
+  for key in ["NaN", "inf", "-infinity", "+1", " 1", ".5", "1e400"] {
+    assert!(!ty.is_assignable(&json!({ key: true })), "{key}");
+    assert!(check_type(&HashMap::from([(key, true)]), &ty).is_err(), "{key}");
+  }
+}
+
+#[test]
+fn bool_keys() {
+  let ty = BTreeMap::<bool, u8>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::Record(Record { optional: false, readonly: false, key: RecordKey::Boolean, value: Arc::new(Type::Number) }));
+  eq!(ty.to_typescript(), "{ [key in \"true\" | \"false\"]?: number }");
+
+  assert!(ty.is_assignable(&json!({})));
+  assert!(ty.is_assignable(&json!({ "true": 1, "false": 2 })));
+  assert!(!ty.is_assignable(&json!({ "True": 1 })));
+  assert!(!ty.is_assignable(&json!({ "1": 1 })));
+}
+
+#[test]
+fn option_keys() {
+  // serde_json writes and reads the keys of `Some` as the inner key
+  let ty = BTreeMap::<Option<u8>, bool>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::Record(Record { optional: false, readonly: false, key: RecordKey::Integer, value: Arc::new(Type::Boolean) }));
+  eq!(ty.to_typescript(), "{ [key: number]: boolean }");
+
+  let value = BTreeMap::from([(Some(1), true)]);
+  assert!(ty.is_assignable(&json!({ "1": true })));
+  assert!(!ty.is_assignable(&json!({ "null": true })));
+  assert!(check_type(&value, &ty).is_ok());
+  eq!(serde_json::from_str::<BTreeMap<Option<u8>, bool>>(r#"{"1":true}"#).unwrap(), value);
+
+  let ty = BTreeMap::<Option<bool>, u8>::shape(&ShapeOptions::for_deserialize());
+  eq!(ty.to_typescript(), "{ [key in \"true\" | \"false\"]?: number }");
+}
+
+#[test]
+fn enum_keys() {
+  let literal = |value: &'static str| Type::Literal(Literal::String(value.into()));
+  let ty = HashMap::<Key, bool>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::Record(Record {
+    optional: true,
+    readonly: false,
+    key: RecordKey::Type(Arc::new(Type::or([literal("A"), literal("B")]))),
+    value: Arc::new(Type::Boolean)
+  }));
+  eq!(ty.to_typescript(), "{ [key in (\"A\" | \"B\")]?: boolean }");
+
+  assert!(ty.is_assignable(&json!({})));
+  assert!(ty.is_assignable(&json!({ "B": true })));
+  assert!(!ty.is_assignable(&json!({ "A": true, "C": true })));
+  assert!(!ty.is_assignable(&json!({ "A": 1 })));
+}
+
+#[test]
+fn declared_keys() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Index<K> {
+    entries: HashMap<K, u32>,
+    keys: HashMap<Key, u8>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  let ty = declarations.add::<Index<Key>>();
+
+  // the declared enum is resolved, a map doesn't need all its variants
+  let Type::Object(index) = &declarations.get("Index").unwrap().ty else { panic!("not an object") };
+  assert!(matches!(&*index.properties["keys"].ty, Type::Record(Record { optional: true, .. })));
+
+  // index signatures only accept string and number keys
+  eq!(
+    declarations.get("Index").unwrap().to_typescript(),
+    "export type Index<K> = { entries: { [key in K]?: number }; keys: { [key in Key]?: number }; };"
+  );
+
+  // the enum argument of the generic keys doesn't need all its variants either
+  let expanded = declarations.expand(&ty);
+  assert!(expanded.is_assignable(&json!({ "entries": { "A": 1 }, "keys": {} })));
+  assert!(!expanded.is_assignable(&json!({ "entries": { "C": 1 }, "keys": {} })));
+}
+
+#[test]
+fn unsupported_keys() {
+  let ty = HashMap::<(u8, u8), bool>::shape(&ShapeOptions::for_serialize());
+  assert!(matches!(&ty, Type::Record(Record { key: RecordKey::Unsupported(_), .. })));
+
+  // serde_json only serializes the empty map
+  assert!(ty.is_assignable(&json!({})));
+  assert!(!ty.is_assignable(&json!({ "[1,2]": true })));
+
+  let errors = ty.to_jtd().unwrap_err();
+  eq!(errors.len(), 1);
+  eq!(errors[0].kind, JtdErrorKind::Key);
+  eq!(errors[0].to_string(), "/: serde_json can't serialize the keys of this map");
+
+  // sequences are not keys, even of integers
+  let ty = HashMap::<Vec<u32>, bool>::shape(&ShapeOptions::for_serialize());
+  assert!(matches!(&ty, Type::Record(Record { key: RecordKey::Unsupported(_), .. })));
+  assert!(serde_json::to_value(HashMap::from([(vec![1u32], true)])).is_err());
+  assert!(!ty.is_assignable(&json!({ "1": true })));
+}
+
+#[test]
+fn char_keys() {
+  eq!(char::shape(&ShapeOptions::for_serialize()), Type::String);
+  let ty = HashMap::<char, bool>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::Record(Record { optional: false, readonly: false, key: RecordKey::String, value: Arc::new(Type::Boolean) }));
+  assert!(ty.is_assignable(&serde_json::to_value(HashMap::from([('a', true)])).unwrap()));
+}
@@ -1,7 +1,9 @@
 mod common;
 
+use std::sync::Arc;
+
 use indexmap::IndexMap;
-use shape::{Array, Literal, Object, Property, Shape, ShapeOptions, Tuple, Type};
+use shape::{Array, Literal, Object, Property, Record, RecordKey, Shape, ShapeOptions, Tuple, Type};
 
 #[test]
 fn simple_struct() {
@@ -18,7 +20,7 @@ fn simple_struct() {
       (
         "bool".into(),
         Property {
-          ty: Type::Boolean,
+          ty: Arc::new(Type::Boolean),
           optional: false,
           readonly: false,
         },
@@ -26,7 +28,7 @@ fn simple_struct() {
       (
         "u8".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -34,7 +36,7 @@ fn simple_struct() {
       (
         "str".into(),
         Property {
-          ty: Type::String,
+          ty: Arc::new(Type::String),
           optional: false,
           readonly: false,
         },
@@ -78,7 +80,7 @@ fn struct_with_serde_attrs() {
       (
         "firstField".into(),
         Property {
-          ty: Type::String,
+          ty: Arc::new(Type::String),
           optional: false,
           readonly: false,
         },
@@ -86,7 +88,7 @@ fn struct_with_serde_attrs() {
       (
         "opt".into(),
         Property {
-          ty: Type::Boolean,
+          ty: Arc::new(Type::Boolean),
           optional: true,
           readonly: false,
         },
@@ -94,12 +96,12 @@ fn struct_with_serde_attrs() {
       (
         "nestedStruct".into(),
         Property {
-          ty: Type::Object(Object {
+          ty: Arc::new(Type::Object(Object {
             properties: IndexMap::from([
               (
                 "field-one".into(),
                 Property {
-                  ty: Type::String,
+                  ty: Arc::new(Type::String),
                   optional: false,
                   readonly: false,
                 },
@@ -107,13 +109,13 @@ fn struct_with_serde_attrs() {
               (
                 "field-two".into(),
                 Property {
-                  ty: Type::Number,
+                  ty: Arc::new(Type::Number),
                   optional: false,
                   readonly: false,
                 },
               ),
             ]),
-          }),
+          })),
           optional: false,
           readonly: false,
         },
@@ -142,12 +144,12 @@ fn struct_with_flatten() {
     another_field: bool,
   }
 
-  let expected = Type::And(vec![
+  let expected = Type::and([
     Type::Object(Object {
       properties: IndexMap::from([(
         "top_level".into(),
         Property {
-          ty: Type::String,
+          ty: Arc::new(Type::String),
           optional: false,
           readonly: false,
         },
@@ -158,7 +160,7 @@ fn struct_with_flatten() {
         (
           "nested_field".into(),
           Property {
-            ty: Type::Number,
+            ty: Arc::new(Type::Number),
             optional: false,
             readonly: false,
           },
@@ -166,7 +168,7 @@ fn struct_with_flatten() {
         (
           "another_field".into(),
           Property {
-            ty: Type::Boolean,
+            ty: Arc::new(Type::Boolean),
             optional: false,
             readonly: false,
           },
@@ -210,11 +212,11 @@ fn enum_untagged() {
     D { a: i32, b: String },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Null,
     Type::Number,
     Type::Tuple(Tuple {
-      items: vec![Type::Number, Type::String],
+      items: vec![Arc::new(Type::Number), Arc::new(Type::String)],
       rest: None,
     }),
     Type::Object(Object {
@@ -222,7 +224,7 @@ fn enum_untagged() {
         (
           "a".into(),
           Property {
-            ty: Type::Number,
+            ty: Arc::new(Type::Number),
             optional: false,
             readonly: false,
           },
@@ -230,7 +232,7 @@ fn enum_untagged() {
         (
           "b".into(),
           Property {
-            ty: Type::String,
+            ty: Arc::new(Type::String),
             optional: false,
             readonly: false,
           },
@@ -250,61 +252,30 @@ fn enum_internally_tagged() {
   #[serde(tag = "type")]
   enum Enum {
     A,
-    B(i32),
-    C(u8, bool),
+    // a newtype variant `B(i32)` and a tuple variant `C(u8, bool)` are rejected at compile time,
+    // serde fails to serialize them, see the lint tests of shape-macros
     D { a: i32, b: String },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     // unit
     Type::Object(Object {
       properties: IndexMap::from([(
         "type".into(),
         Property {
-          ty: Type::Literal(Literal::String("A".into())),
+          ty: Arc::new(Type::Literal(Literal::String("A".into()))),
           optional: false,
           readonly: false,
         },
       )]),
     }),
-    // newtype
-    Type::And(vec![
-      Type::Object(Object {
-        properties: IndexMap::from([(
-          "type".into(),
-          Property {
-            ty: Type::Literal(Literal::String("B".into())),
-            optional: false,
-            readonly: false,
-          },
-        )]),
-      }),
-      Type::Number,
-    ]),
-    // tuple
-    Type::And(vec![
-      Type::Object(Object {
-        properties: IndexMap::from([(
-          "type".into(),
-          Property {
-            ty: Type::Literal(Literal::String("C".into())),
-            optional: false,
-            readonly: false,
-          },
-        )]),
-      }),
-      Type::Tuple(Tuple {
-        items: vec![Type::Number, Type::Boolean],
-        rest: None,
-      }),
-    ]),
     // named
-    Type::And(vec![
+    Type::and([
       Type::Object(Object {
         properties: IndexMap::from([(
           "type".into(),
           Property {
-            ty: Type::Literal(Literal::String("D".into())),
+            ty: Arc::new(Type::Literal(Literal::String("D".into()))),
             optional: false,
             readonly: false,
           },
@@ -315,7 +286,7 @@ fn enum_internally_tagged() {
           (
             "a".into(),
             Property {
-              ty: Type::Number,
+              ty: Arc::new(Type::Number),
               optional: false,
               readonly: false,
             },
@@ -323,7 +294,7 @@ fn enum_internally_tagged() {
           (
             "b".into(),
             Property {
-              ty: Type::String,
+              ty: Arc::new(Type::String),
               optional: false,
               readonly: false,
             },
@@ -349,13 +320,13 @@ fn enum_adjacently_tagged() {
     D { a: i32, b: String },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     // unit
     Type::Object(Object {
       properties: IndexMap::from([(
         "type".into(),
         Property {
-          ty: Type::Literal(Literal::String("A".into())),
+          ty: Arc::new(Type::Literal(Literal::String("A".into()))),
           optional: false,
           readonly: false,
         },
@@ -367,7 +338,7 @@ fn enum_adjacently_tagged() {
         (
           "type".into(),
           Property {
-            ty: Type::Literal(Literal::String("B".into())),
+            ty: Arc::new(Type::Literal(Literal::String("B".into()))),
             optional: false,
             readonly: false,
           },
@@ -375,7 +346,7 @@ fn enum_adjacently_tagged() {
         (
           "content".into(),
           Property {
-            ty: Type::Number,
+            ty: Arc::new(Type::Number),
             optional: false,
             readonly: false,
           },
@@ -388,7 +359,7 @@ fn enum_adjacently_tagged() {
         (
           "type".into(),
           Property {
-            ty: Type::Literal(Literal::String("C".into())),
+            ty: Arc::new(Type::Literal(Literal::String("C".into()))),
             optional: false,
             readonly: false,
           },
@@ -398,10 +369,10 @@ fn enum_adjacently_tagged() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Tuple(Tuple {
-              items: vec![Type::Number, Type::Boolean],
+            ty: Arc::new(Type::Tuple(Tuple {
+              items: vec![Arc::new(Type::Number), Arc::new(Type::Boolean)],
               rest: None,
-            }),
+            })),
           },
         ),
       ]),
@@ -412,7 +383,7 @@ fn enum_adjacently_tagged() {
         (
           "type".into(),
           Property {
-            ty: Type::Literal(Literal::String("D".into())),
+            ty: Arc::new(Type::Literal(Literal::String("D".into()))),
             optional: false,
             readonly: false,
           },
@@ -422,12 +393,12 @@ fn enum_adjacently_tagged() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "a".into(),
                   Property {
-                    ty: Type::Number,
+                    ty: Arc::new(Type::Number),
                     optional: false,
                     readonly: false,
                   },
@@ -435,13 +406,13 @@ fn enum_adjacently_tagged() {
                 (
                   "b".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           },
         ),
       ]),
@@ -468,7 +439,7 @@ fn struct_with_rename() {
       (
         "original_field".into(),
         Property {
-          ty: Type::String,
+          ty: Arc::new(Type::String),
           optional: false,
           readonly: false,
         },
@@ -476,7 +447,7 @@ fn struct_with_rename() {
       (
         "another_field".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -502,7 +473,7 @@ fn struct_with_skip_serializing() {
     properties: IndexMap::from([(
       "included".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -526,7 +497,7 @@ fn struct_with_skip_deserializing() {
     properties: IndexMap::from([(
       "included".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -552,7 +523,7 @@ fn struct_with_default() {
     properties: IndexMap::from([(
       "field_with_default".into(),
       Property {
-        ty: Type::Number,
+        ty: Arc::new(Type::Number),
         optional: false,
         readonly: false,
       },
@@ -563,7 +534,7 @@ fn struct_with_default() {
     properties: IndexMap::from([(
       "field_with_default".into(),
       Property {
-        ty: Type::Number,
+        ty: Arc::new(Type::Number),
         optional: true,
         readonly: false,
       },
@@ -593,7 +564,7 @@ fn struct_with_skip_serializing_if() {
     properties: IndexMap::from([(
       "optional_field".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: true,
         readonly: false,
       },
@@ -621,6 +592,51 @@ fn struct_with_transparent() {
   );
 }
 
+#[test]
+fn struct_with_transparent_skipped_fields() {
+  #[derive(Shape)]
+  #[serde(transparent)]
+  #[allow(unused)]
+  struct Named {
+    #[serde(skip)]
+    cache: Vec<u8>,
+    value: String,
+  }
+
+  #[derive(Shape)]
+  #[serde(transparent)]
+  #[allow(unused)]
+  struct Unnamed(u32, #[serde(skip)] Vec<u8>);
+
+  eq!(Named::shape(&ShapeOptions::for_serialize()), Type::String);
+  eq!(Unnamed::shape(&ShapeOptions::for_deserialize()), Type::Number);
+}
+
+#[test]
+fn allow_unsupported() {
+  #[derive(Shape)]
+  #[serde(deny_unknown_fields, expecting = "a user")]
+  #[shape(allow_unsupported)]
+  #[allow(unused)]
+  struct User {
+    #[serde(deserialize_with = "trimmed")]
+    name: String,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([(
+      "name".into(),
+      Property {
+        ty: Arc::new(Type::String),
+        optional: false,
+        readonly: false,
+      },
+    )]),
+  });
+
+  eq!(User::shape(&ShapeOptions::for_deserialize()), expected);
+}
+
 #[test]
 fn enum_with_rename_all() {
   #[derive(Shape)]
@@ -631,7 +647,7 @@ fn enum_with_rename_all() {
     VariantTwo,
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Literal(Literal::String("VariantOne".into())),
     Type::Literal(Literal::String("VariantTwo".into())),
   ]);
@@ -656,7 +672,7 @@ fn struct_with_skip_and_rename() {
     properties: IndexMap::from([(
       "included".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -677,7 +693,7 @@ fn enum_with_rename() {
     SecondVariant,
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Literal(Literal::String("FIRSTVARIANT".into())),
     Type::Literal(Literal::String("SECONDVARIANT".into())),
   ]);
@@ -697,11 +713,11 @@ fn enum_with_skip_variant() {
     Skipped,
   }
 
-  let expected = Type::Or(vec![Type::Object(Object {
+  let expected = Type::or([Type::Object(Object {
     properties: IndexMap::from([(
       "type".into(),
       Property {
-        ty: Type::Literal(Literal::String("Included".into())),
+        ty: Arc::new(Type::Literal(Literal::String("Included".into()))),
         optional: false,
         readonly: false,
       },
@@ -723,11 +739,11 @@ fn enum_with_skip_serializing_variant() {
     NeverSerialized,
   }
 
-  let expected = Type::Or(vec![Type::Object(Object {
+  let expected = Type::or([Type::Object(Object {
     properties: IndexMap::from([(
       "type".into(),
       Property {
-        ty: Type::Literal(Literal::String("AlwaysSerialized".into())),
+        ty: Arc::new(Type::Literal(Literal::String("AlwaysSerialized".into()))),
         optional: false,
         readonly: false,
       },
@@ -751,11 +767,11 @@ fn enum_with_skip_deserializing_variant() {
     NeverDeserialized,
   }
 
-  let expected = Type::Or(vec![Type::Object(Object {
+  let expected = Type::or([Type::Object(Object {
     properties: IndexMap::from([(
       "type".into(),
       Property {
-        ty: Type::Literal(Literal::String("AlwaysDeserialized".into())),
+        ty: Arc::new(Type::Literal(Literal::String("AlwaysDeserialized".into()))),
         optional: false,
         readonly: false,
       },
@@ -775,43 +791,29 @@ fn enum_with_internal_tagging() {
   #[serde(tag = "tag")]
   enum InternallyTaggedEnum {
     Unit,
-    NewType(i32),
+    // a newtype variant `NewType(i32)` is rejected at compile time, see the lint tests of shape-macros
     Struct { field: String },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     // Unit variant
     Type::Object(Object {
       properties: IndexMap::from([(
         "tag".into(),
         Property {
-          ty: Type::Literal(Literal::String("Unit".into())),
+          ty: Arc::new(Type::Literal(Literal::String("Unit".into()))),
           optional: false,
           readonly: false,
         },
       )]),
     }),
-    // Newtype variant
-    Type::And(vec![
-      Type::Object(Object {
-        properties: IndexMap::from([(
-          "tag".into(),
-          Property {
-            ty: Type::Literal(Literal::String("NewType".into())),
-            optional: false,
-            readonly: false,
-          },
-        )]),
-      }),
-      Type::Number,
-    ]),
     // Struct variant
-    Type::And(vec![
+    Type::and([
       Type::Object(Object {
         properties: IndexMap::from([(
           "tag".into(),
           Property {
-            ty: Type::Literal(Literal::String("Struct".into())),
+            ty: Arc::new(Type::Literal(Literal::String("Struct".into()))),
             optional: false,
             readonly: false,
           },
@@ -821,7 +823,7 @@ fn enum_with_internal_tagging() {
         properties: IndexMap::from([(
           "field".into(),
           Property {
-            ty: Type::String,
+            ty: Arc::new(Type::String),
             optional: false,
             readonly: false,
           },
@@ -840,6 +842,63 @@ fn enum_with_internal_tagging() {
   );
 }
 
+#[test]
+fn enum_internally_tagged_newtypes() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Inner {
+    c: bool,
+  }
+
+  // the content of a newtype variant is flattened next to the tag, serde only serializes maps and structs
+  #[derive(Shape)]
+  #[allow(unused)]
+  #[serde(tag = "type")]
+  enum Enum {
+    B(Inner),
+    NewType(std::collections::BTreeMap<String, i32>),
+  }
+
+  let tag = |name: &'static str| Type::Object(Object {
+    properties: IndexMap::from([(
+      "type".into(),
+      Property {
+        ty: Arc::new(Type::Literal(Literal::String(name.into()))),
+        optional: false,
+        readonly: false,
+      },
+    )]),
+  });
+
+  let expected = Type::or([
+    Type::and([
+      tag("B"),
+      Type::Object(Object {
+        properties: IndexMap::from([(
+          "c".into(),
+          Property {
+            ty: Arc::new(Type::Boolean),
+            optional: false,
+            readonly: false,
+          },
+        )]),
+      }),
+    ]),
+    Type::and([
+      tag("NewType"),
+      Type::Record(Record {
+        optional: false,
+        readonly: false,
+        key: RecordKey::String,
+        value: Arc::new(Type::Number),
+      }),
+    ]),
+  ]);
+
+  eq!(Enum::shape(&ShapeOptions::for_serialize()), expected);
+  eq!(Enum::shape(&ShapeOptions::for_deserialize()), expected);
+}
+
 #[test]
 fn enum_with_adjacently_tagged() {
   #[derive(Shape)]
@@ -850,13 +909,13 @@ fn enum_with_adjacently_tagged() {
     VariantTwo { field: String },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
           "type".into(),
           Property {
-            ty: Type::Literal(Literal::String("VariantOne".into())),
+            ty: Arc::new(Type::Literal(Literal::String("VariantOne".into()))),
             optional: false,
             readonly: false,
           },
@@ -864,7 +923,7 @@ fn enum_with_adjacently_tagged() {
         (
           "data".into(),
           Property {
-            ty: Type::Number,
+            ty: Arc::new(Type::Number),
             optional: false,
             readonly: false,
           },
@@ -876,7 +935,7 @@ fn enum_with_adjacently_tagged() {
         (
           "type".into(),
           Property {
-            ty: Type::Literal(Literal::String("VariantTwo".into())),
+            ty: Arc::new(Type::Literal(Literal::String("VariantTwo".into()))),
             optional: false,
             readonly: false,
           },
@@ -884,16 +943,16 @@ fn enum_with_adjacently_tagged() {
         (
           "data".into(),
           Property {
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([(
                 "field".into(),
                 Property {
-                  ty: Type::String,
+                  ty: Arc::new(Type::String),
                   optional: false,
                   readonly: false,
                 },
               )]),
-            }),
+            })),
             optional: false,
             readonly: false,
           },
@@ -922,7 +981,7 @@ fn enum_with_untagged() {
     VariantTwo(String),
   }
 
-  let expected = Type::Or(vec![Type::Number, Type::String]);
+  let expected = Type::or([Type::Number, Type::String]);
 
   eq!(UntaggedEnum::shape(&ShapeOptions::for_serialize()), expected);
   eq!(UntaggedEnum::shape(&ShapeOptions::for_deserialize()), expected);
@@ -949,8 +1008,8 @@ fn enum_with_flatten() {
     },
   }
 
-  let expected = Type::Or(vec![
-    Type::And(vec![
+  let expected = Type::or([
+    Type::and([
       Type::Object(Object {
         properties: IndexMap::new(),
       }),
@@ -958,7 +1017,7 @@ fn enum_with_flatten() {
         properties: IndexMap::from([(
           "field".into(),
           Property {
-            ty: Type::String,
+            ty: Arc::new(Type::String),
             optional: false,
             readonly: false,
           },
@@ -969,7 +1028,7 @@ fn enum_with_flatten() {
       properties: IndexMap::from([(
         "another_field".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -991,16 +1050,16 @@ fn enum_with_complex_variants() {
     Struct { a: i32, b: bool },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Literal(Literal::String("Unit".into())),
     Type::Object(Object {
       properties: IndexMap::from([(
         "Tuple".into(),
         Property {
-          ty: Type::Tuple(Tuple {
-            items: vec![Type::Number, Type::String],
+          ty: Arc::new(Type::Tuple(Tuple {
+            items: vec![Arc::new(Type::Number), Arc::new(Type::String)],
             rest: None,
-          }),
+          })),
           optional: false,
           readonly: false,
         },
@@ -1012,14 +1071,14 @@ fn enum_with_complex_variants() {
         Property {
           optional: false,
           readonly: false,
-          ty: Type::Object(Object {
+          ty: Arc::new(Type::Object(Object {
             properties: IndexMap::from([
               (
                 "a".into(),
                 Property {
                   optional: false,
                   readonly: false,
-                  ty: Type::Number,
+                  ty: Arc::new(Type::Number),
                 },
               ),
               (
@@ -1027,11 +1086,11 @@ fn enum_with_complex_variants() {
                 Property {
                   optional: false,
                   readonly: false,
-                  ty: Type::Boolean,
+                  ty: Arc::new(Type::Boolean),
                 },
               ),
             ]),
-          }),
+          })),
         },
       )]),
     }),
@@ -1053,7 +1112,7 @@ fn option_type_serialization() {
     properties: IndexMap::from([(
       "optional_field".into(),
       Property {
-        ty: Type::Or(vec![Type::Number, Type::Null]),
+        ty: Arc::new(Type::or([Type::Number, Type::Null])),
         optional: false,
         readonly: false,
       },
@@ -1075,7 +1134,7 @@ fn option_type_deserialization() {
     properties: IndexMap::from([(
       "optional_field".into(),
       Property {
-        ty: Type::Or(vec![Type::Number, Type::Null, Type::Undefined]),
+        ty: Arc::new(Type::or([Type::Number, Type::Null, Type::Undefined])),
         optional: true,
         readonly: false,
       },
@@ -1097,7 +1156,7 @@ fn nested_option_serialization() {
     properties: IndexMap::from([(
       "nested_field".into(),
       Property {
-        ty: Type::Or(vec![Type::Or(vec![Type::String, Type::Null]), Type::Null]),
+        ty: Arc::new(Type::or([Type::or([Type::String, Type::Null]), Type::Null])),
         optional: false,
         readonly: false,
       },
@@ -1119,15 +1178,15 @@ fn nested_option_deserialization() {
     properties: IndexMap::from([(
       "nested_field".into(),
       Property {
-        ty: Type::Or(vec![
-              Type::Or(vec![
+        ty: Arc::new(Type::or([
+              Type::or([
                 Type::String,
                 Type::Null,
                 Type::Undefined
               ]),
               Type::Null,
               Type::Undefined,
-        ]),
+        ])),
         optional: true,
         readonly: false,
       },
@@ -1146,12 +1205,12 @@ fn option_in_enum_serialization() {
     Variant { field: Option<u32> },
   }
 
-  let expected = Type::Or(vec![
+  let expected = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([(
         "field".into(),
         Property {
-          ty: Type::Or(vec![Type::Number, Type::Null]),
+          ty: Arc::new(Type::or([Type::Number, Type::Null])),
           optional: false,
           readonly: false,
         },
@@ -1171,12 +1230,12 @@ fn option_in_enum_deserialization() {
     Variant { field: Option<u32> },
   }
 
-  let expected = Type::Or(vec![Type::Object(
+  let expected = Type::or([Type::Object(
     Object {
       properties: IndexMap::from([(
         "field".into(),
         Property {
-          ty: Type::Or(vec![Type::Number, Type::Null, Type::Undefined]),
+          ty: Arc::new(Type::or([Type::Number, Type::Null, Type::Undefined])),
           optional: true,
           readonly: false,
         },
@@ -1201,9 +1260,9 @@ fn option_in_vec_serialization() {
       Property {
         optional: false,
         readonly: false,
-        ty: Type::Array(Array {
-          item: Box::new(Type::Or(vec![Type::Number, Type::Null])),   
-        }),
+        ty: Arc::new(Type::Array(Array {
+          item: Arc::new(Type::or([Type::Number, Type::Null])),   
+        })),
       }
     )])
   });
@@ -1225,13 +1284,13 @@ fn option_in_vec_deserialization() {
       Property {
         optional: false,
         readonly: false,
-        ty: Type::Array(Array {
-          item: Box::new(Type::Or(vec![
+        ty: Arc::new(Type::Array(Array {
+          item: Arc::new(Type::or([
             Type::Number,
             Type::Null,
             Type::Undefined,
           ])),
-        })
+        }))
       },
     )]),
   });
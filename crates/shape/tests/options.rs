@@ -1,7 +1,7 @@
 mod common;
-use std::vec;
+use std::{sync::Arc, vec};
 
-use shape::{Shape, ShapeOptions, ShapeOptionsKind, Type};
+use shape::{Format, Shape, ShapeOptions, ShapeOptionsKind, Type};
 
 #[test]
 fn options_none() {
@@ -10,6 +10,8 @@ fn options_none() {
     option_is_optional: false,
     option_add_undefined: false,
     option_add_null: false,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let de_opts = ShapeOptions {
@@ -17,6 +19,8 @@ fn options_none() {
     option_is_optional: false,
     option_add_undefined: false,
     option_add_null: false,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   eq!(Option::<String>::shape(&ser_opts), Type::String);
@@ -37,6 +41,8 @@ fn options_is_optional() {
     option_is_optional: true,
     option_add_undefined: false,
     option_add_null: false,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let de_opts = ShapeOptions {
@@ -44,13 +50,15 @@ fn options_is_optional() {
     option_is_optional: true,
     option_add_undefined: false,
     option_add_null: false,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let expected = Type::Object(shape::Object {
     properties: shape::indexmap::IndexMap::from([(
       "field".into(),
       shape::Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: true,
         readonly: false,
       },
@@ -76,6 +84,8 @@ fn options_add_undefined() {
     option_is_optional: false,
     option_add_undefined: true,
     option_add_null: false,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let de_opts = ShapeOptions {
@@ -83,13 +93,15 @@ fn options_add_undefined() {
     option_is_optional: false,
     option_add_undefined: true,
     option_add_null: false,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let expected = Type::Object(shape::Object {
     properties: shape::indexmap::IndexMap::from([(
       "field".into(),
       shape::Property {
-        ty: Type::Or(vec![Type::String, Type::Undefined]),
+        ty: Arc::new(Type::or([Type::String, Type::Undefined])),
         optional: false,
         readonly: false,
       },
@@ -114,6 +126,8 @@ fn options_add_null() {
     option_is_optional: false,
     option_add_undefined: false,
     option_add_null: true,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let de_opts = ShapeOptions {
@@ -121,13 +135,15 @@ fn options_add_null() {
     option_is_optional: false,
     option_add_undefined: false,
     option_add_null: true,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let expected = Type::Object(shape::Object {
     properties: shape::indexmap::IndexMap::from([(
       "field".into(),
       shape::Property {
-        ty: Type::Or(vec![Type::String, Type::Null]),
+        ty: Arc::new(Type::or([Type::String, Type::Null])),
         optional: false,
         readonly: false,
       },
@@ -152,6 +168,8 @@ fn options_add_null_and_undefined() {
     option_is_optional: false,
     option_add_undefined: true,
     option_add_null: true,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let de_opts = ShapeOptions {
@@ -159,13 +177,15 @@ fn options_add_null_and_undefined() {
     option_is_optional: false,
     option_add_undefined: true,
     option_add_null: true,
+    use_declarations: false,
+    format: Format::Json,
   };
 
   let expected = Type::Object(shape::Object {
     properties: shape::indexmap::IndexMap::from([(
       "field".into(),
       shape::Property {
-        ty: Type::Or(vec![Type::String, Type::Null, Type::Undefined]),
+        ty: Arc::new(Type::or([Type::String, Type::Null, Type::Undefined])),
         optional: false,
         readonly: false,
       },
@@ -0,0 +1,106 @@
+mod common;
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use shape::{Declarations, IsAsignable, Object, Property, Shape, ShapeOptions, ToTypescript, Type};
+
+// `Range` is defined in another crate, it implements neither Shape nor (without a feature) Serialize
+#[derive(Serialize, Deserialize, Shape)]
+#[serde(remote = "Range")]
+#[allow(unused)]
+struct RangeDef<Idx> {
+  start: Idx,
+  end: Idx,
+}
+
+// a remote derive of a type with private fields
+#[derive(Serialize, Deserialize, Shape)]
+#[serde(remote = "Duration")]
+#[allow(unused)]
+struct DurationDef {
+  #[serde(getter = "Duration::as_secs")]
+  secs: u64,
+  #[serde(getter = "Duration::subsec_nanos")]
+  nanos: u32,
+}
+
+impl From<DurationDef> for Duration {
+  fn from(def: DurationDef) -> Duration {
+    Duration::new(def.secs, def.nanos)
+  }
+}
+
+#[derive(Serialize, Deserialize, Shape)]
+#[allow(unused)]
+struct Process {
+  name: String,
+  #[serde(with = "RangeDef")]
+  #[shape(with = "RangeDef<u32>")]
+  pages: Range<u32>,
+  #[serde(with = "DurationDef")]
+  #[shape(with = "DurationDef")]
+  wall_time: Duration,
+}
+
+fn property(ty: Type) -> Property {
+  Property {
+    optional: false,
+    readonly: false,
+    ty: Arc::new(ty),
+  }
+}
+
+fn object(properties: [(&'static str, Type); 2]) -> Type {
+  Type::Object(Object {
+    properties: properties.into_iter().map(|(key, ty)| (key.into(), property(ty))).collect::<IndexMap<_, _>>(),
+  })
+}
+
+#[test]
+fn mirror() {
+  eq!(RangeDef::<u32>::shape(&ShapeOptions::for_serialize()), object([("start", Type::Number), ("end", Type::Number)]));
+  eq!(DurationDef::shape(&ShapeOptions::for_serialize()), object([("secs", Type::Number), ("nanos", Type::Number)]));
+}
+
+#[test]
+fn with_field() {
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([
+      ("name".into(), property(Type::String)),
+      ("pages".into(), property(object([("start", Type::Number), ("end", Type::Number)]))),
+      ("wall_time".into(), property(object([("secs", Type::Number), ("nanos", Type::Number)]))),
+    ]),
+  });
+
+  eq!(Process::shape(&ShapeOptions::for_serialize()), expected);
+  eq!(Process::shape(&ShapeOptions::for_deserialize()), expected);
+
+  let process = Process {
+    name: "worker".into(),
+    pages: 1..10,
+    wall_time: Duration::new(1, 500),
+  };
+
+  let value = serde_json::to_value(&process).unwrap();
+  assert!(expected.is_assignable(&value));
+  assert!(serde_json::from_value::<Process>(value).is_ok());
+}
+
+#[test]
+fn remote_declaration_name() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Process>();
+
+  assert_eq!(
+    declarations.iter().map(|d| d.to_typescript()).collect::<Vec<_>>(),
+    vec![
+      "export type Process = { name: string; pages: Range<number>; wall_time: Duration; };",
+      "export type Range<Idx> = { start: Idx; end: Idx; };",
+      "export type Duration = { secs: number; nanos: number; };",
+    ],
+  );
+}
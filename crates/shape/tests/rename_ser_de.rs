@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use indexmap::IndexMap;
 use shape::{Literal, Object, Property, Shape, ShapeOptions, Type};
 
@@ -18,7 +20,7 @@ fn rename_all_ser_de() {
       (
         "originalField".into(),
         Property {
-          ty: Type::String,
+          ty: Arc::new(Type::String),
           optional: false,
           readonly: false,
         },
@@ -26,7 +28,7 @@ fn rename_all_ser_de() {
       (
         "anotherField".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -39,7 +41,7 @@ fn rename_all_ser_de() {
       (
         "OriginalField".into(),
         Property {
-          ty: Type::String,
+          ty: Arc::new(Type::String),
           optional: false,
           readonly: false,
         },
@@ -47,7 +49,7 @@ fn rename_all_ser_de() {
       (
         "AnotherField".into(),
         Property {
-          ty: Type::Number,
+          ty: Arc::new(Type::Number),
           optional: false,
           readonly: false,
         },
@@ -72,7 +74,7 @@ fn rename_ser_de() {
     properties: IndexMap::from([(
       "_field".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -83,7 +85,7 @@ fn rename_ser_de() {
     properties: IndexMap::from([(
       "Field".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
@@ -105,7 +107,7 @@ fn enum_rename_all_fields() {
     },
   }
 
-  let ser = Type::Or(vec![
+  let ser = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -113,25 +115,25 @@ fn enum_rename_all_fields() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SOME_FIELD".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           }
         )
       ]),
     })
   ]);
 
-  let de = Type::Or(vec![
+  let de = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -139,18 +141,18 @@ fn enum_rename_all_fields() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "someField".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           }
         )
       ]),
@@ -170,11 +172,11 @@ fn enum_variant_rename() {
     VariantOne,
   }
 
-  let ser = Type::Or(vec![
+  let ser = Type::or([
     Type::Literal(Literal::String("variant_one".into())),
   ]);
   
-  let de = Type::Or(vec![
+  let de = Type::or([
     Type::Literal(Literal::String("VARIANT-ONE".into())),
   ]);
 
@@ -193,7 +195,7 @@ fn enum_variant_rename_all() {
     },
   }
 
-  let ser = Type::Or(vec![
+  let ser = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -201,25 +203,25 @@ fn enum_variant_rename_all() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SomeField".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           }
         )
       ]),
     })
   ]);
 
-  let de = Type::Or(vec![
+  let de = Type::or([
     Type::Object(Object {
       properties: IndexMap::from([
         (
@@ -227,18 +229,18 @@ fn enum_variant_rename_all() {
           Property {
             optional: false,
             readonly: false,
-            ty: Type::Object(Object {
+            ty: Arc::new(Type::Object(Object {
               properties: IndexMap::from([
                 (
                   "SOME-FIELD".into(),
                   Property {
-                    ty: Type::String,
+                    ty: Arc::new(Type::String),
                     optional: false,
                     readonly: false,
                   },
                 ),
               ]),
-            }),
+            })),
           }  
         ),
       ])
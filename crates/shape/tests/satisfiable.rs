@@ -0,0 +1,106 @@
+mod common;
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shape::{IsAsignable, Literal, Object, Property, Shape, ShapeOptions, Type};
+
+fn object(properties: &[(&str, Type)]) -> Type {
+  Type::Object(Object {
+    properties: properties
+      .iter()
+      .map(|(name, ty)| {
+        (
+          name.to_string().into(),
+          Property {
+            optional: false,
+            readonly: false,
+            ty: Arc::new(ty.clone()),
+          },
+        )
+      })
+      .collect::<IndexMap<_, _>>(),
+  })
+}
+
+fn string(value: &'static str) -> Type {
+  Type::Literal(Literal::String(value.into()))
+}
+
+#[test]
+fn is_satisfiable() {
+  assert!(!Type::Never.is_satisfiable());
+  assert!(!Type::or([]).is_satisfiable());
+  assert!(Type::or([Type::Never, Type::Number]).is_satisfiable());
+  assert!(!Type::and([object(&[("type", string("A"))]), Type::Number]).is_satisfiable());
+  assert!(!Type::and([object(&[("type", string("A"))]), object(&[("type", string("B"))])]).is_satisfiable());
+  assert!(!object(&[("value", Type::Never)]).is_satisfiable());
+  assert!(Type::and([object(&[("type", string("A"))]), object(&[("value", Type::Number)])]).is_satisfiable());
+  assert!(Type::and([Type::String, string("A")]).is_satisfiable());
+  assert!(Type::and([object(&[]), Type::Param("T".into())]).is_satisfiable());
+}
+
+#[test]
+fn internally_tagged_generic_newtype() {
+  #[derive(Shape)]
+  #[serde(tag = "type")]
+  #[allow(unused)]
+  enum Message<T> {
+    Value(T),
+  }
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Point {
+    x: u8,
+  }
+
+  let tag = object(&[("type", string("Value"))]);
+
+  let ty = Message::<Point>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::or([Type::and([tag.clone(), object(&[("x", Type::Number)])])]));
+  assert!(ty.is_satisfiable());
+
+  // serde fails to serialize a number in an internally tagged newtype variant
+  let ty = Message::<u32>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::or([Type::Never]));
+  assert!(!ty.is_satisfiable());
+
+  // a unit is serialized as the tag alone
+  eq!(Message::<()>::shape(&ShapeOptions::for_serialize()), Type::or([tag.clone()]));
+}
+
+#[test]
+fn internally_tagged_option_newtype() {
+  #[derive(Debug, PartialEq, Serialize, Deserialize, Shape)]
+  #[serde(tag = "type")]
+  enum Message<T> {
+    Value(T),
+  }
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize, Shape)]
+  struct Point {
+    x: u8,
+  }
+
+  // serde fails to serialize both None and Some
+  let ty = Message::<Option<Point>>::shape(&ShapeOptions::for_serialize());
+  eq!(ty, Type::or([Type::Never]));
+  assert!(serde_json::to_value(Message::<Option<Point>>::Value(None)).is_err());
+  assert!(serde_json::to_value(Message::Value(Some(Point { x: 1 }))).is_err());
+
+  // the content is always deserialized as Some
+  let ty = Message::<Option<Point>>::shape(&ShapeOptions::for_deserialize());
+  let tag = object(&[("type", string("Value"))]);
+  eq!(ty, Type::or([Type::or([Type::and([tag, object(&[("x", Type::Number)])])])]));
+
+  let value = json!({ "type": "Value", "x": 1 });
+  assert!(ty.is_assignable(&value));
+  eq!(serde_json::from_value::<Message<Option<Point>>>(value).unwrap(), Message::Value(Some(Point { x: 1 })));
+
+  let value = json!({ "type": "Value" });
+  assert!(!ty.is_assignable(&value));
+  assert!(serde_json::from_value::<Message<Option<Point>>>(value).is_err());
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "schemars")]
+
+mod common;
+
+use schemars::{schema_for, JsonSchema};
+use serde_json::json;
+use shape::schemars::{parse_schemars, Schemars, Shaped};
+use shape::{shape, Declarations, Shape, ShapeOptions, Type};
+
+#[derive(JsonSchema)]
+#[allow(unused)]
+struct Address {
+  street: String,
+  zip: Option<u32>,
+}
+
+#[derive(JsonSchema)]
+#[allow(unused)]
+struct Tree {
+  children: Vec<Tree>,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  #[shape(with = "Schemars<Address>")]
+  address: Address,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Role {
+  name: String,
+  user: User,
+}
+
+#[derive(JsonSchema)]
+#[allow(unused)]
+struct Order {
+  #[schemars(with = "Shaped<User>")]
+  user: User,
+}
+
+#[test]
+fn schemars_in_shape() {
+  eq!(User::shape(&ShapeOptions::for_serialize()), shape!{{
+    id: number,
+    address: { street: string, zip: number | null }
+  }});
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<User>();
+  eq!(declarations.iter().map(|declaration| &*declaration.name).collect::<Vec<_>>(), vec!["User", "Address"]);
+  eq!(declarations.get("User").unwrap().ty, shape!{{ id: number, address: Address }});
+
+  // recursive references are kept
+  eq!(Schemars::<Tree>::shape(&ShapeOptions::for_serialize()), shape!{{ children: Tree[] }});
+  eq!(parse_schemars::<Vec<bool>>(&ShapeOptions::for_serialize()).unwrap(), Type::array(Type::Boolean));
+}
+
+#[test]
+fn shape_in_schemars() {
+  let schema = schema_for!(Order).to_value();
+  eq!(schema["properties"]["user"], json!({ "$ref": "#/$defs/User" }));
+  eq!(schema["$defs"]["User"], json!({
+    "type": "object",
+    "properties": {
+      "id": { "type": "number" },
+      "address": { "$ref": "#/$defs/Address" }
+    },
+    "required": ["id", "address"]
+  }));
+  eq!(schema["$defs"]["Address"]["required"], json!(["street"]));
+
+  // the shape of the schema is the shape of the type
+  let options = ShapeOptions::for_serialize();
+  eq!(parse_schemars::<Shaped<Role>>(&options).unwrap(), Role::shape(&options));
+}
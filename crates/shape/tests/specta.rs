@@ -0,0 +1,84 @@
+#![cfg(feature = "specta")]
+
+mod common;
+
+use shape::specta::{Shaped, Specta, ToSpecta};
+use shape::{shape, Declarations, Shape, ShapeOptions};
+use specta::{datatype::DataType, Generics, TypeCollection};
+
+#[derive(specta::Type)]
+#[allow(unused)]
+struct Address {
+  street: String,
+  zip: Option<u32>,
+  corners: [f32; 2],
+}
+
+#[derive(specta::Type)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+}
+
+#[derive(specta::Type)]
+#[allow(unused)]
+#[serde(tag = "type")]
+enum Kind {
+  Person { age: u8 },
+  Bot,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  #[shape(with = "Specta<Page<Address>>")]
+  addresses: Page<Address>,
+  #[shape(with = "Specta<Kind>")]
+  kind: Kind,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Role {
+  name: String,
+  tags: Option<Vec<String>>,
+}
+
+// specta resolves the generic arguments of the type, an alias hides them
+type RoleType = Shaped<Role>;
+
+#[derive(specta::Type)]
+#[allow(unused)]
+struct Order {
+  #[specta(type = RoleType)]
+  role: Role,
+}
+
+#[test]
+fn specta_in_shape() {
+  eq!(User::shape(&ShapeOptions::for_serialize()), shape!{{
+    id: number,
+    addresses: { items: { street: string, zip: number | null, corners: [number, number] }[] },
+    kind: { type: "Person" } & { age: number } | { type: "Bot" }
+  }});
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<User>();
+  eq!(declarations.get("User").unwrap().ty, shape!{{ id: number, addresses: Page<Address>, kind: Kind }});
+  eq!(declarations.get("Page").unwrap().params, vec![String::from("T")]);
+}
+
+#[test]
+fn shape_in_specta() {
+  // the shape of the specta type is the shape of the type
+  let options = ShapeOptions::for_serialize();
+  eq!(Specta::<Order>::shape(&options), shape!{{ role: #(Role::shape(&options)) }});
+
+  let data_type = <Shaped<Role> as specta::Type>::inline(&mut TypeCollection::default(), Generics::Definition);
+  eq!(data_type, Role::shape(&options).to_specta());
+
+  // specta tuples have a fixed length
+  assert!(matches!(shape!{ [string, ...number[]] }.to_specta(), DataType::List(_)));
+  assert!(matches!(shape!{ Record<string, boolean> }.to_specta(), DataType::Map(_)));
+}
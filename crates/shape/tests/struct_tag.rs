@@ -0,0 +1,85 @@
+mod common;
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shape::{IsAsignable, Literal, Object, Property, Shape, ShapeOptions, Type};
+
+fn property(ty: Type) -> Property {
+  Property {
+    optional: false,
+    readonly: false,
+    ty: Arc::new(ty),
+  }
+}
+
+fn tag(name: &'static str) -> Property {
+  property(Type::Literal(Literal::String(name.into())))
+}
+
+#[test]
+fn struct_tag() {
+  #[derive(Serialize, Deserialize, Shape)]
+  #[serde(tag = "type")]
+  struct Point {
+    x: i32,
+    y: i32,
+  }
+
+  let expected = Type::Object(Object {
+    properties: IndexMap::from([
+      ("type".into(), tag("Point")),
+      ("x".into(), property(Type::Number)),
+      ("y".into(), property(Type::Number)),
+    ]),
+  });
+
+  eq!(Point::shape(&ShapeOptions::for_serialize()), expected);
+  eq!(Point::shape(&ShapeOptions::for_deserialize()), expected);
+
+  let value = serde_json::to_value(Point { x: 1, y: 2 }).unwrap();
+  assert_eq!(value, json!({ "type": "Point", "x": 1, "y": 2 }));
+  assert!(expected.is_assignable(&value));
+  assert!(!expected.is_assignable(&json!({ "x": 1, "y": 2 })));
+}
+
+#[test]
+fn struct_tag_renamed() {
+  #[derive(Serialize, Shape)]
+  #[serde(tag = "kind", rename(serialize = "point", deserialize = "Point2D"))]
+  struct Point {
+    x: i32,
+  }
+
+  eq!(
+    Point::shape(&ShapeOptions::for_serialize()),
+    Type::Object(Object {
+      properties: IndexMap::from([("kind".into(), tag("point")), ("x".into(), property(Type::Number))]),
+    })
+  );
+
+  eq!(
+    Point::shape(&ShapeOptions::for_deserialize()),
+    Type::Object(Object {
+      properties: IndexMap::from([("kind".into(), tag("Point2D")), ("x".into(), property(Type::Number))]),
+    })
+  );
+
+  assert_eq!(serde_json::to_value(Point { x: 1 }).unwrap(), json!({ "kind": "point", "x": 1 }));
+}
+
+#[test]
+fn struct_tag_empty() {
+  #[derive(Shape)]
+  #[serde(tag = "type", rename = "nothing")]
+  struct Empty {}
+
+  eq!(
+    Empty::shape(&ShapeOptions::for_serialize()),
+    Type::Object(Object {
+      properties: IndexMap::from([("type".into(), tag("nothing"))]),
+    })
+  );
+}
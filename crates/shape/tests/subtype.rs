@@ -0,0 +1,56 @@
+mod common;
+
+use shape::{shape, Custom, CustomType, RecordKey, Type};
+
+#[test]
+fn primitives() {
+  assert!(shape!{ "a" | "b" }.is_subtype_of(&Type::String));
+  assert!(shape!{ never }.is_subtype_of(&Type::Null));
+  assert!(shape!{ number[] }.is_subtype_of(&Type::Unknown));
+  assert!(Type::Boolean.is_equivalent(&shape!{ true | false }));
+  assert!(!shape!{ string | null }.is_subtype_of(&Type::String));
+  assert!(!Type::Number.is_subtype_of(&shape!{ 1 }));
+}
+
+#[test]
+fn structures() {
+  // extra properties are allowed and optional properties can be required
+  assert!(shape!{ { id: number, name: string } }.is_subtype_of(&shape!{ { id: number, name?: string } }));
+  assert!(!shape!{ { id?: number } }.is_subtype_of(&shape!{ { id: number } }));
+  assert!(shape!{ { id: number } & { name: string } }.is_equivalent(&shape!{ { name: string, id: number } }));
+  assert!(shape!{ { a: 1, b: 2 } }.is_subtype_of(&shape!{ Record<string, number> }));
+
+  assert!(shape!{ [1, 2] }.is_subtype_of(&shape!{ number[] }));
+  assert!(shape!{ [1, 2] }.is_subtype_of(&shape!{ [number, ...number[]] }));
+  assert!(!shape!{ [number] }.is_subtype_of(&shape!{ [number, number] }));
+  assert!(shape!{ string[] }.is_equivalent(&shape!{ [...string[]] }));
+
+  // integer keys are numbers and numbers are strings
+  let integers = Type::record(RecordKey::Integer, Type::Null);
+  assert!(integers.is_subtype_of(&Type::record(RecordKey::Number, Type::Null)));
+  assert!(integers.is_subtype_of(&shape!{ Record<string, null | undefined> }));
+  assert!(!shape!{ Record<string, null> }.is_subtype_of(&integers));
+}
+
+#[test]
+fn references() {
+  assert!(shape!{ Page<User> }.is_equivalent(&shape!{ Page<User> }));
+  assert!(!shape!{ Page<User> }.is_subtype_of(&shape!{ Page<unknown> }));
+
+  struct Date;
+
+  impl CustomType for Date {
+    fn name(&self) -> &str {
+      "Date"
+    }
+
+    fn fallback(&self) -> Option<Type> {
+      Some(Type::String)
+    }
+  }
+
+  let date = Type::Custom(Custom::new(Date));
+  assert!(date.is_subtype_of(&Type::String));
+  assert!(date.is_subtype_of(&Type::Custom(Custom::from("Date"))));
+  assert!(!Type::String.is_subtype_of(&date));
+}
@@ -0,0 +1,282 @@
+mod common;
+
+use shape::{Declarations, GraphqlError, GraphqlErrorKind, Shape, ShapeOptions, ToGraphql};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+  next: Option<String>,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u64,
+  name: String,
+  role: Role,
+  address: Address,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Address {
+  city: String,
+  zip: Option<String>,
+}
+
+#[derive(Shape)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[allow(unused)]
+enum Role {
+  Admin,
+  Member,
+}
+
+#[derive(Shape)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(unused)]
+enum Event {
+  Created { user: User },
+  Deleted,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+enum Filter {
+  ByName(String),
+  ById { id: u64 },
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Query {
+  events: Page<Event>,
+  filter: Option<Filter>,
+}
+
+#[test]
+fn output_types() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Query>();
+
+  eq!(declarations.to_graphql().unwrap(), r#"
+type Query {
+  events: PageEvent!
+  filter: Filter
+}
+
+union Event = EventCreated | EventDeleted
+
+type EventCreated {
+  type: String!
+  user: User!
+}
+
+type User {
+  id: Float!
+  name: String!
+  role: Role!
+  address: Address!
+}
+
+enum Role {
+  ADMIN
+  MEMBER
+}
+
+type Address {
+  city: String!
+  zip: String
+}
+
+type EventDeleted {
+  type: String!
+}
+
+type PageEvent {
+  items: [Event!]!
+  next: String
+}
+
+union Filter = FilterByNameVariant | FilterByIdVariant
+
+type FilterByNameVariant {
+  ByName: String!
+}
+
+type FilterByIdVariant {
+  ById: FilterById!
+}
+
+type FilterById {
+  id: Float!
+}
+"#.trim_start());
+}
+
+#[test]
+fn input_types() {
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Filter>();
+  declarations.add::<User>();
+
+  eq!(declarations.to_graphql().unwrap(), r#"
+input Filter @oneOf {
+  ByName: String
+  ById: FilterById
+}
+
+input FilterById {
+  id: Float!
+}
+
+input User {
+  id: Float!
+  name: String!
+  role: Role!
+  address: Address!
+}
+
+enum Role {
+  ADMIN
+  MEMBER
+}
+
+input Address {
+  city: String!
+  zip: String
+}
+"#.trim_start());
+
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Event>();
+  eq!(declarations.to_graphql().unwrap_err(), vec![GraphqlError { path: String::from("Event"), kind: GraphqlErrorKind::InputUnion }]);
+}
+
+#[test]
+fn unrepresentable() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Point {
+    location: (f64, f64),
+  }
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Scores {
+    by_user: std::collections::HashMap<String, u64>,
+  }
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Header {
+    #[serde(rename = "content-type")]
+    content_type: String,
+  }
+
+  let error = |declarations: Declarations| declarations.to_graphql().unwrap_err().iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Point>();
+  eq!(error(declarations), "Point.location: tuples can't be represented in GraphQL");
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Scores>();
+  eq!(error(declarations), "Scores.by_user: records with arbitrary keys can't be represented in GraphQL");
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Header>();
+  eq!(error(declarations), "Header.content-type: `content-type` is not a valid GraphQL name");
+
+  // the errors of all the declarations are reported together
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Point>();
+  declarations.add::<Scores>();
+  declarations.add::<Header>();
+  eq!(declarations.to_graphql().unwrap_err().iter().map(|error| error.path.clone()).collect::<Vec<_>>(), vec![
+    "Point.location",
+    "Scores.by_user",
+    "Header.content-type",
+  ]);
+
+  #[derive(Shape)]
+  #[serde(tag = "type")]
+  #[allow(unused)]
+  enum Change {
+    Renamed { name: String },
+    Scored(std::collections::HashMap<String, u64>),
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Change>();
+  // the content of a variant that is not an object is not dropped
+  eq!(error(declarations), "Change: GraphQL unions must be unit only enums, tagged enums or unions of objects");
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Geometry {
+    Point((f64, f64)),
+    #[serde(rename = "multi-point")]
+    MultiPoint(Vec<Point>),
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Geometry>();
+  eq!(error(declarations), "Geometry.Point: tuples can't be represented in GraphQL\nPoint.location: tuples can't be represented in GraphQL");
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Shapes {
+    #[serde(rename = "multi-point")]
+    MultiPoint(Vec<String>),
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Shapes>();
+  eq!(error(declarations), "Shapes.multi-point: `multi-point` is not a valid GraphQL name");
+}
+
+#[test]
+fn input_and_output() {
+  #[derive(Shape)]
+  #[serde(tag = "t", content = "c")]
+  #[allow(unused)]
+  enum Message {
+    Text(String),
+    Image { url: String },
+    Ping,
+  }
+
+  let emit = |options: ShapeOptions| {
+    let mut declarations = Declarations::new(options);
+    declarations.add::<Message>();
+    declarations.to_graphql()
+  };
+
+  // the tag and the content are fields of the objects of an output union
+  eq!(emit(ShapeOptions::for_serialize()).unwrap(), r#"
+union Message = MessageText | MessageImage | MessagePing
+
+type MessageText {
+  t: String!
+  c: String!
+}
+
+type MessageImage {
+  t: String!
+  c: MessageImageC!
+}
+
+type MessageImageC {
+  url: String!
+}
+
+type MessagePing {
+  t: String!
+}
+"#.trim_start());
+
+  // inputs can't be unions
+  eq!(emit(ShapeOptions::for_deserialize()).unwrap_err(), vec![GraphqlError { path: String::from("Message"), kind: GraphqlErrorKind::InputUnion }]);
+}
@@ -0,0 +1,309 @@
+mod common;
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use shape::{validate_jtd, Declarations, JtdError, JtdErrorKind, JtdValidationError, Shape, ShapeOptions, ToJtd};
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+  next: Option<String>,
+}
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+struct User {
+  id: u64,
+  tags: HashMap<String, bool>,
+  role: Role,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(rename_all = "lowercase")]
+#[allow(unused)]
+enum Role {
+  Admin,
+  Member,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(unused)]
+enum Event {
+  Created { user: User },
+  Deleted,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(tag = "t", content = "c")]
+#[allow(unused)]
+enum Message {
+  Text(String),
+  Image { url: String },
+}
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+struct Feed {
+  events: Page<Event>,
+  message: Option<Message>,
+}
+
+#[test]
+fn declarations() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Feed>();
+
+  eq!(declarations.to_jtd().unwrap(), json!({
+    "ref": "Feed",
+    "definitions": {
+      "Feed": {
+        "properties": {
+          "events": { "ref": "Page<Event>" },
+          "message": { "ref": "Message", "nullable": true },
+        },
+      },
+      "Page<Event>": {
+        "properties": {
+          "items": { "elements": { "ref": "Event" } },
+          "next": { "type": "string", "nullable": true },
+        },
+      },
+      "Event": {
+        "discriminator": "type",
+        "mapping": {
+          "created": { "properties": { "user": { "ref": "User" } } },
+          "deleted": { "properties": {} },
+        },
+      },
+      "User": {
+        "properties": {
+          "id": { "type": "float64" },
+          "tags": { "values": { "type": "boolean" } },
+          "role": { "ref": "Role" },
+        },
+      },
+      "Role": { "enum": ["admin", "member"] },
+      "Message": {
+        "discriminator": "t",
+        "mapping": {
+          "Text": { "properties": { "c": { "type": "string" } } },
+          "Image": { "properties": { "c": { "properties": { "url": { "type": "string" } } } } },
+        },
+      },
+    },
+  }));
+}
+
+#[test]
+fn deserialize() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Query {
+    limit: Option<u32>,
+    role: Role,
+  }
+
+  // serde ignores unknown fields
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Query>();
+
+  eq!(declarations.to_jtd().unwrap(), json!({
+    "ref": "Query",
+    "definitions": {
+      "Query": {
+        "properties": {
+          "role": { "ref": "Role" },
+        },
+        "optionalProperties": {
+          "limit": { "type": "float64", "nullable": true },
+        },
+        "additionalProperties": true,
+      },
+      "Role": { "enum": ["admin", "member"] },
+    },
+  }));
+
+  eq!(Option::<String>::shape(&ShapeOptions::for_deserialize()).to_jtd().unwrap(), json!({ "type": "string", "nullable": true }));
+}
+
+#[test]
+fn unrepresentable() {
+  #[derive(Shape)]
+  #[serde(untagged)]
+  #[allow(unused)]
+  enum Id {
+    Number(u64),
+    Text(String),
+  }
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Shapes {
+    point: (f64, f64),
+    id: Id,
+    #[serde(flatten)]
+    items: Page<u64>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Shapes>();
+
+  eq!(declarations.to_jtd().unwrap_err(), vec![
+    JtdError { path: String::from("/definitions/Shapes/properties/point"), kind: JtdErrorKind::Tuple },
+    JtdError { path: String::from("/definitions/Id"), kind: JtdErrorKind::Untagged },
+  ]);
+
+  eq!(
+    Shapes::shape(&ShapeOptions::for_serialize()).to_jtd().unwrap_err().iter().map(ToString::to_string).collect::<Vec<_>>(),
+    vec![
+      "/properties/point: tuples can't be represented in JTD",
+      "/properties/id: unions without a discriminator can't be represented in JTD",
+    ]
+  );
+}
+
+/// The instance path and schema path of each error
+fn errors(schema: &Value, instance: Value) -> Vec<(String, String)> {
+  validate_jtd(schema, &instance).into_iter().map(|JtdValidationError { instance_path, schema_path }| (instance_path, schema_path)).collect()
+}
+
+fn paths(paths: &[(&str, &str)]) -> Vec<(String, String)> {
+  paths.iter().map(|(instance, schema)| (instance.to_string(), schema.to_string())).collect()
+}
+
+#[test]
+fn validate_emitted() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Feed>();
+  let schema = declarations.to_jtd().unwrap();
+
+  let user = User { id: 1, tags: HashMap::from([(String::from("a"), true)]), role: Role::Admin };
+  let feed = Feed {
+    events: Page { items: vec![Event::Created { user }, Event::Deleted], next: None },
+    message: Some(Message::Image { url: String::from("a.png") }),
+  };
+  eq!(errors(&schema, serde_json::to_value(&feed).unwrap()), paths(&[]));
+
+  let feed = Feed { events: Page { items: vec![], next: Some(String::from("b")) }, message: Some(Message::Text(String::from("a"))) };
+  eq!(errors(&schema, serde_json::to_value(&feed).unwrap()), paths(&[]));
+  let feed = Feed { events: Page { items: vec![], next: None }, message: None };
+  eq!(errors(&schema, serde_json::to_value(&feed).unwrap()), paths(&[]));
+
+  let instance = json!({
+    "events": { "items": [{ "type": "created", "user": { "id": "1", "tags": {}, "role": "guest" } }, { "type": "updated" }], "next": null },
+    "message": { "t": "Text", "c": "a", "extra": 1 },
+  });
+  eq!(errors(&schema, instance), paths(&[
+    ("/events/items/0/user/id", "/definitions/User/properties/id/type"),
+    ("/events/items/0/user/role", "/definitions/Role/enum"),
+    ("/events/items/1/type", "/definitions/Event/mapping"),
+    ("/message/extra", "/definitions/Message/mapping/Text"),
+  ]));
+}
+
+#[test]
+fn validate_deserialize() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Query {
+    limit: Option<u32>,
+    role: Role,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Query>();
+  let schema = declarations.to_jtd().unwrap();
+
+  eq!(errors(&schema, json!({ "role": "admin", "limit": null, "unknown": 1 })), paths(&[]));
+  eq!(errors(&schema, json!({ "role": "admin" })), paths(&[]));
+  eq!(errors(&schema, json!({ "limit": 1 })), paths(&[("", "/definitions/Query/properties/role")]));
+  eq!(errors(&schema, json!([])), paths(&[("", "/definitions/Query/properties")]));
+}
+
+/// The examples of RFC 8927 section 3.3
+#[test]
+fn validate_rfc() {
+  eq!(errors(&json!({}), json!([null, 1])), paths(&[]));
+
+  let schema = json!({ "type": "string", "nullable": true });
+  eq!(errors(&schema, json!(null)), paths(&[]));
+  eq!(errors(&schema, json!(1)), paths(&[("", "/type")]));
+
+  let schema = json!({ "definitions": { "coordinates": { "properties": { "lat": { "type": "float32" }, "lng": { "type": "float32" } } } },
+    "properties": { "user_location": { "ref": "coordinates" }, "server_location": { "ref": "coordinates" } } });
+  let instance = json!({ "user_location": { "lat": "Hello" }, "server_location": { "lat": 0, "lng": 0 } });
+  eq!(errors(&schema, instance), paths(&[
+    ("/user_location/lat", "/definitions/coordinates/properties/lat/type"),
+    ("/user_location", "/definitions/coordinates/properties/lng"),
+  ]));
+
+  let schema = json!({ "type": "uint8" });
+  eq!(errors(&schema, json!(255)), paths(&[]));
+  eq!(errors(&schema, json!(3.0)), paths(&[]));
+  eq!(errors(&schema, json!(3.5)), paths(&[("", "/type")]));
+  eq!(errors(&schema, json!(256)), paths(&[("", "/type")]));
+  eq!(errors(&schema, json!(-1)), paths(&[("", "/type")]));
+
+  let schema = json!({ "type": "timestamp" });
+  eq!(errors(&schema, json!("1985-04-12T23:20:50.52Z")), paths(&[]));
+  eq!(errors(&schema, json!("1990-12-31T15:59:60-08:00")), paths(&[]));
+  eq!(errors(&schema, json!("1985-04-12")), paths(&[("", "/type")]));
+
+  let schema = json!({ "enum": ["PENDING", "DONE", "CANCELED"] });
+  eq!(errors(&schema, json!("DONE")), paths(&[]));
+  eq!(errors(&schema, json!("UNKNOWN")), paths(&[("", "/enum")]));
+  eq!(errors(&schema, json!(1)), paths(&[("", "/enum")]));
+
+  let schema = json!({ "elements": { "type": "string" } });
+  eq!(errors(&schema, json!({})), paths(&[("", "/elements")]));
+  eq!(errors(&schema, json!(["foo", null, null, "bar"])), paths(&[("/1", "/elements/type"), ("/2", "/elements/type")]));
+
+  let schema = json!({ "properties": { "name": { "type": "string" }, "age": { "type": "uint32" }, "phones": { "elements": { "type": "string" } } } });
+  eq!(errors(&schema, json!("foo")), paths(&[("", "/properties")]));
+  eq!(errors(&schema, json!({ "name": "Jane Doe", "age": 43 })), paths(&[("", "/properties/phones")]));
+  eq!(errors(&schema, json!({ "name": "Jane Doe", "age": 43, "phones": ["+44 1234567", 442345678] })), paths(&[("/phones/1", "/properties/phones/elements/type")]));
+  eq!(errors(&schema, json!({ "name": "Jane Doe", "age": 43, "phones": [], "email": "a" })), paths(&[("/email", "")]));
+
+  let schema = json!({ "values": { "type": "boolean" } });
+  eq!(errors(&schema, json!(null)), paths(&[("", "/values")]));
+  eq!(errors(&schema, json!({ "a": true, "b": false })), paths(&[]));
+  eq!(errors(&schema, json!({ "a": true, "b": "foo", "c": "bar" })), paths(&[("/b", "/values/type"), ("/c", "/values/type")]));
+
+  let schema = json!({ "discriminator": "version", "mapping": {
+    "v1": { "properties": { "a": { "type": "float32" } } },
+    "v2": { "properties": { "a": { "type": "string" } } },
+  } });
+  eq!(errors(&schema, json!("example")), paths(&[("", "/discriminator")]));
+  eq!(errors(&schema, json!({})), paths(&[("", "/discriminator")]));
+  eq!(errors(&schema, json!({ "version": 1 })), paths(&[("/version", "/discriminator")]));
+  eq!(errors(&schema, json!({ "version": "v3" })), paths(&[("/version", "/mapping")]));
+  eq!(errors(&schema, json!({ "version": "v2", "a": 3 })), paths(&[("/a", "/mapping/v2/properties/a/type")]));
+  eq!(errors(&schema, json!({ "version": "v2", "a": "foo" })), paths(&[]));
+}
+
+/// The ranges of the integer types of RFC 8927 section 2.2.3, for hand written schemas
+#[test]
+fn validate_integer_types() {
+  let ranges: [(&str, i64, i64); 6] = [
+    ("int8", -128, 127),
+    ("uint8", 0, 255),
+    ("int16", -32768, 32767),
+    ("uint16", 0, 65535),
+    ("int32", -2147483648, 2147483647),
+    ("uint32", 0, 4294967295),
+  ];
+  for (ty, min, max) in ranges {
+    let schema = json!({ "type": ty });
+    eq!(errors(&schema, json!(min)), paths(&[]));
+    eq!(errors(&schema, json!(max)), paths(&[]));
+    eq!(errors(&schema, json!(max as f64)), paths(&[]));
+    eq!(errors(&schema, json!(min - 1)), paths(&[("", "/type")]));
+    eq!(errors(&schema, json!(max + 1)), paths(&[("", "/type")]));
+    eq!(errors(&schema, json!(max as f64 - 0.5)), paths(&[("", "/type")]));
+    eq!(errors(&schema, json!(max.to_string())), paths(&[("", "/type")]));
+  }
+}
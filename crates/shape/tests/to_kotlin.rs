@@ -0,0 +1,270 @@
+mod common;
+
+use shape::{Declarations, Shape, ShapeOptions, ToKotlin};
+
+/// The Kotlin declaration of `name` after adding `T`
+fn kotlin<T: Shape>(name: &str) -> String {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<T>();
+  declarations.get(name).unwrap().to_kotlin()
+}
+
+#[test]
+fn optionals() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+  }
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Profile {
+    nickname: Option<String>,
+    scores: Vec<Option<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manager: Option<Page<u8>>,
+  }
+
+  eq!(kotlin::<Page<u8>>("Page"), r#"
+@Serializable
+data class Page<T>(
+    val items: List<T>,
+    val next: String?,
+)
+"#.trim());
+
+  eq!(kotlin::<Profile>("Profile"), r#"
+@Serializable
+data class Profile(
+    val nickname: String?,
+    val scores: List<Double?>,
+    val manager: Page<Double>? = null,
+)
+"#.trim());
+}
+
+#[test]
+fn internally_tagged() {
+  #[derive(Shape)]
+  #[serde(tag = "type", rename_all = "snake_case")]
+  #[allow(unused)]
+  enum Event {
+    Created { id: u64 },
+    Deleted,
+  }
+
+  eq!(kotlin::<Event>("Event"), r#"
+@Serializable
+@JsonClassDiscriminator("type")
+sealed class Event {
+    @Serializable
+    @SerialName("created")
+    data class Created(
+        val id: Double,
+    ) : Event()
+
+    @Serializable
+    @SerialName("deleted")
+    data object Deleted : Event()
+}
+"#.trim());
+}
+
+#[test]
+fn adjacently_tagged() {
+  #[derive(Shape)]
+  #[serde(tag = "t", content = "c")]
+  #[allow(unused)]
+  enum Message {
+    Text(String),
+    Image { url: String },
+    Ping,
+  }
+
+  eq!(kotlin::<Message>("Message"), r#"
+@Serializable
+data class MessageImage(
+    val url: String,
+)
+
+@Serializable
+@JsonClassDiscriminator("t")
+sealed class Message {
+    @Serializable
+    @SerialName("Text")
+    data class Text(
+        @SerialName("c") val content: String,
+    ) : Message()
+
+    @Serializable
+    @SerialName("Image")
+    data class Image(
+        @SerialName("c") val content: MessageImage,
+    ) : Message()
+
+    @Serializable
+    @SerialName("Ping")
+    data object Ping : Message()
+}
+"#.trim());
+}
+
+#[test]
+fn externally_tagged() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Status {
+    Active,
+    Banned { until: u64 },
+  }
+
+  eq!(kotlin::<Status>("Status"), r#"
+@Serializable
+data class StatusBanned(
+    val until: Double,
+)
+
+object StatusSerializer : KSerializer<Status> {
+    override val descriptor: SerialDescriptor = JsonElement.serializer().descriptor
+
+    override fun deserialize(decoder: Decoder): Status {
+        val input = decoder as JsonDecoder
+        val element = input.decodeJsonElement()
+        if (element is JsonPrimitive && element.isString) {
+            when (element.content) {
+                "Active" -> return Status.Active
+            }
+        }
+        if (element is JsonObject && element.size == 1) {
+            val (key, value) = element.entries.first()
+            when (key) {
+                "Banned" -> return Status.Banned(input.json.decodeFromJsonElement<StatusBanned>(value))
+            }
+        }
+        throw SerializationException("unknown variant of Status")
+    }
+
+    override fun serialize(encoder: Encoder, value: Status) {
+        val output = encoder as JsonEncoder
+        val element = when (value) {
+            is Status.Active -> JsonPrimitive("Active")
+            is Status.Banned -> buildJsonObject { put("Banned", output.json.encodeToJsonElement(value.value)) }
+        }
+        output.encodeJsonElement(element)
+    }
+}
+
+@Serializable(with = StatusSerializer::class)
+sealed class Status {
+    data object Active : Status()
+    data class Banned(val value: StatusBanned) : Status()
+}
+"#.trim());
+}
+
+#[test]
+fn unit_variants() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Color {
+    Red,
+    Green,
+  }
+
+  eq!(kotlin::<Color>("Color"), r#"
+@Serializable
+enum class Color {
+    @SerialName("Red")
+    Red,
+    @SerialName("Green")
+    Green,
+}
+"#.trim());
+}
+
+#[test]
+fn untagged() {
+  #[derive(Shape)]
+  #[serde(untagged)]
+  #[allow(unused)]
+  enum Id {
+    Number(u64),
+    Text(String),
+  }
+
+  eq!(kotlin::<Id>("Id"), r#"
+object IdSerializer : KSerializer<Id> {
+    override val descriptor: SerialDescriptor = JsonElement.serializer().descriptor
+
+    override fun deserialize(decoder: Decoder): Id {
+        val input = decoder as JsonDecoder
+        val element = input.decodeJsonElement()
+        runCatching { return Id.Variant1(input.json.decodeFromJsonElement<Double>(element)) }
+        runCatching { return Id.Variant2(input.json.decodeFromJsonElement<String>(element)) }
+        throw SerializationException("no variant of Id matches")
+    }
+
+    override fun serialize(encoder: Encoder, value: Id) {
+        val output = encoder as JsonEncoder
+        val element = when (value) {
+            is Id.Variant1 -> output.json.encodeToJsonElement(value.value)
+            is Id.Variant2 -> output.json.encodeToJsonElement(value.value)
+        }
+        output.encodeJsonElement(element)
+    }
+}
+
+@Serializable(with = IdSerializer::class)
+sealed class Id {
+    data class Variant1(val value: Double) : Id()
+    data class Variant2(val value: String) : Id()
+}
+"#.trim());
+}
+
+#[test]
+fn declarations_header() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Feed {
+    items: Vec<String>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Feed>();
+  let out = declarations.to_kotlin();
+  assert!(out.starts_with(r#"@file:OptIn(ExperimentalSerializationApi::class)
+
+import kotlinx.serialization.*"#), "{out}");
+}
+
+#[test]
+fn reserved_words() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Account {
+    display_name: String,
+    #[serde(rename = "class")]
+    group: (u8, bool),
+    #[serde(rename = "is")]
+    active: bool,
+    #[serde(rename = "default")]
+    fallback: Option<String>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Account>();
+
+  // `default` is only a keyword in Swift
+  eq!(declarations.get("Account").unwrap().to_kotlin(), r#"
+@Serializable
+data class Account(
+    @SerialName("display_name") val displayName: String,
+    val `class`: JsonArray,
+    val `is`: Boolean,
+    val default: String?,
+)
+"#.trim());
+}
@@ -0,0 +1,288 @@
+mod common;
+
+use serde::Serialize;
+use shape::{Declarations, PythonStyle, Shape, ShapeOptions, ToPython};
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+  next: Option<String>,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(rename_all = "camelCase")]
+#[allow(unused)]
+struct User {
+  id: u64,
+  display_name: String,
+  #[serde(rename = "class")]
+  group: (u8, bool),
+  address: Address,
+}
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+struct Address {
+  city: String,
+  #[serde(rename = "zip-code")]
+  zip_code: String,
+}
+
+#[derive(Serialize, Shape)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(unused)]
+enum Event {
+  Created { user: User },
+  Deleted { id: u64, reason: Option<String> },
+}
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+enum Status {
+  Active,
+  Banned { until: u64 },
+}
+
+#[derive(Serialize, Shape)]
+#[allow(unused)]
+struct Feed {
+  page: Page<Event>,
+  status: Status,
+}
+
+fn declarations() -> Declarations {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Feed>();
+  declarations
+}
+
+#[test]
+fn typed_dict() {
+  let expected = r#"
+from __future__ import annotations
+
+from typing import Any, Generic, Literal, Never, NotRequired, TypedDict, TypeVar, Union
+
+T = TypeVar("T")
+
+
+class StatusBanned(TypedDict):
+    until: float
+
+
+class StatusBannedVariant(TypedDict):
+    Banned: StatusBanned
+
+
+Status = Union[Literal["Active"], StatusBannedVariant]
+
+
+Address = TypedDict("Address", {"city": str, "zip-code": str})
+
+
+User = TypedDict("User", {"id": float, "displayName": str, "class": tuple[float, bool], "address": Address})
+
+
+class EventCreated(TypedDict):
+    type: Literal["created"]
+    user: User
+
+
+class EventDeleted(TypedDict):
+    type: Literal["deleted"]
+    id: float
+    reason: Union[str, None]
+
+
+Event = Union[EventCreated, EventDeleted]
+
+
+class Page(TypedDict, Generic[T]):
+    items: list[T]
+    next: Union[str, None]
+
+
+class Feed(TypedDict):
+    page: Page[Event]
+    status: Status
+"#;
+
+  assert_eq!(declarations().to_python(PythonStyle::TypedDict), expected.trim_start());
+}
+
+#[test]
+fn pydantic() {
+  let expected = r#"
+from __future__ import annotations
+
+from typing import Annotated, Any, Generic, Literal, Never, Optional, TypeVar, Union
+
+from pydantic import BaseModel, Field
+
+T = TypeVar("T")
+
+
+class StatusBanned(BaseModel):
+    until: float
+
+
+class StatusBannedVariant(BaseModel):
+    Banned: StatusBanned
+
+
+Status = Union[Literal["Active"], StatusBannedVariant]
+
+
+class Address(BaseModel):
+    city: str
+    zip_code: str = Field(alias="zip-code")
+
+
+class User(BaseModel):
+    id: float
+    displayName: str
+    class_: tuple[float, bool] = Field(alias="class")
+    address: Address
+
+
+class EventCreated(BaseModel):
+    type: Literal["created"]
+    user: User
+
+
+class EventDeleted(BaseModel):
+    type: Literal["deleted"]
+    id: float
+    reason: Union[str, None]
+
+
+Event = Annotated[Union[EventCreated, EventDeleted], Field(discriminator="type")]
+
+
+class Page(BaseModel, Generic[T]):
+    items: list[T]
+    next: Union[str, None]
+
+
+class Feed(BaseModel):
+    page: Page[Event]
+    status: Status
+"#;
+
+  assert_eq!(declarations().to_python(PythonStyle::Pydantic), expected.trim_start());
+}
+
+#[test]
+fn declaration() {
+  let declarations = declarations();
+  assert_eq!(
+    declarations.get("Page").unwrap().to_python(PythonStyle::TypedDict),
+    "class Page(TypedDict, Generic[T]):\n    items: list[T]\n    next: Union[str, None]",
+  );
+
+  // options are not required when deserializing
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Page<u8>>();
+  assert_eq!(
+    declarations.get("Page").unwrap().to_python(PythonStyle::TypedDict),
+    "class Page(TypedDict, Generic[T]):\n    items: list[T]\n    next: NotRequired[Union[str, None]]",
+  );
+  assert_eq!(
+    declarations.get("Page").unwrap().to_python(PythonStyle::Pydantic),
+    "class Page(BaseModel, Generic[T]):\n    items: list[T]\n    next: Optional[Union[str, None]] = None",
+  );
+}
+
+#[test]
+fn non_identifier_keys() {
+  #[derive(Serialize, Shape)]
+  #[serde(rename_all = "camelCase")]
+  #[allow(unused)]
+  struct Account {
+    display_name: String,
+    #[serde(rename = "class")]
+    group: (u8, bool),
+    #[serde(rename = "zip-code")]
+    zip_code: String,
+    #[serde(rename = "_id")]
+    id: u64,
+  }
+
+  // a keyword or a key with a dash needs the functional syntax, pydantic aliases them and keeps underscores private
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Account>();
+  assert_eq!(
+    declarations.get("Account").unwrap().to_python(PythonStyle::TypedDict),
+    "Account = TypedDict(\"Account\", {\"displayName\": str, \"class\": tuple[float, bool], \"zip-code\": str, \"_id\": float})",
+  );
+  assert_eq!(
+    declarations.get("Account").unwrap().to_python(PythonStyle::Pydantic),
+    "class Account(BaseModel):\n    displayName: str\n    class_: tuple[float, bool] = Field(alias=\"class\")\n    zip_code: str = Field(alias=\"zip-code\")\n    field_id: float = Field(alias=\"_id\")",
+  );
+}
+
+
+#[test]
+fn generic_non_identifier_keys() {
+  #[derive(Serialize, Shape)]
+  #[allow(unused)]
+  struct Response<T> {
+    status: u16,
+    #[serde(rename = "content-type")]
+    content_type: String,
+    #[serde(rename = "x-data")]
+    data: T,
+  }
+
+  #[derive(Serialize, Shape)]
+  #[allow(unused)]
+  struct Listing<T> {
+    #[serde(flatten)]
+    page: Page<T>,
+    #[serde(rename = "x-total")]
+    total: u64,
+  }
+
+  // the keys that are not identifiers are a functional base, that can't be generic
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Response<u8>>();
+  assert_eq!(
+    declarations.get("Response").unwrap().to_python(PythonStyle::TypedDict),
+    "_ResponseKeys = TypedDict(\"_ResponseKeys\", {\"content-type\": str, \"x-data\": Any})\n\n\nclass Response(_ResponseKeys, Generic[T]):\n    status: float",
+  );
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Listing<u8>>();
+  assert_eq!(
+    declarations.get("Listing").unwrap().to_python(PythonStyle::TypedDict),
+    "_ListingKeys = TypedDict(\"_ListingKeys\", {\"x-total\": float})\n\n\nclass Listing(_ListingKeys, Page[T], Generic[T]):\n    pass",
+  );
+  assert_eq!(
+    declarations.get("Listing").unwrap().to_python(PythonStyle::Pydantic),
+    "class Listing(Page[T], Generic[T]):\n    x_total: float = Field(alias=\"x-total\")",
+  );
+}
+
+#[test]
+fn number_keys() {
+  #[derive(Serialize, Shape)]
+  #[allow(unused)]
+  struct Scores {
+    by_user: std::collections::HashMap<u32, String>,
+    by_ratio: std::collections::BTreeMap<i8, f64>,
+    by_flag: std::collections::HashMap<bool, u8>,
+  }
+
+  // the keys of a `TypedDict` are the strings of the JSON, pydantic coerces them
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Scores>();
+  assert_eq!(
+    declarations.get("Scores").unwrap().to_python(PythonStyle::TypedDict),
+    "class Scores(TypedDict):\n    by_user: dict[str, str]\n    by_ratio: dict[str, float]\n    by_flag: dict[str, float]",
+  );
+  assert_eq!(
+    declarations.get("Scores").unwrap().to_python(PythonStyle::Pydantic),
+    "class Scores(BaseModel):\n    by_user: dict[int, str]\n    by_ratio: dict[int, float]\n    by_flag: dict[bool, float]",
+  );
+}
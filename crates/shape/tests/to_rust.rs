@@ -0,0 +1,84 @@
+mod common;
+
+use shape::{parse_typescript_declarations, Custom, Declarations, Shape, ShapeOptions, ToRust, Type};
+
+#[allow(unused)]
+mod generated {
+  include!("to_rust/declarations.rs");
+}
+
+const DECLARATIONS: &str = r#"
+  type User = {
+    id: number;
+    displayName: string;
+    email?: string;
+    manager: string | null;
+    nickname?: string | null;
+    createdAt: Date;
+    tags: string[];
+    scores: { [key: string]: number };
+    point: [number, number];
+    roles: { [key in Role]?: boolean };
+    status: "active" | "in-progress";
+    address: { street: string; "zip-code": string; };
+    type: "user";
+  };
+
+  type Role = "admin" | "guest";
+
+  type Event = { type: "created"; id: number; } | { type: "deleted"; };
+
+  type Message = { t: "Text"; c: string; } | { t: "Size"; c: number; } | { t: "Ping"; };
+
+  type Value = string | number[] | { a: number };
+
+  type Page<T> = { items: T[]; next: string | null; } & { [key: string]: unknown };
+
+  type Figure = "Unit" | { Circle: { radius: number; }; } | { Square: number; };
+"#;
+
+fn declarations() -> Declarations {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  for declaration in parse_typescript_declarations(DECLARATIONS).unwrap() {
+    let ty = declaration.ty.fold(|ty, _| match ty {
+      Type::Apply(apply) if apply.name == "Date" => Type::Custom(Custom::from("Date")),
+      ty => ty,
+    });
+    declarations.insert(shape::Declaration { ty, ..declaration });
+  }
+  declarations
+}
+
+/// The shape with the declarations of `declarations` expanded
+fn inline(ty: &Type, declarations: &Declarations) -> Type {
+  ty.clone().fold(|ty, _| match ty {
+    Type::Apply(apply) => inline(&declarations.get(&apply.name).unwrap().ty, declarations),
+    ty => ty,
+  })
+}
+
+#[test]
+fn to_rust() {
+  eq!(declarations().to_rust(), include_str!("to_rust/declarations.rs"));
+}
+
+#[test]
+fn derive() {
+  let declarations = declarations();
+  let options = ShapeOptions::for_serialize();
+  let expected = |name: &str| inline(&declarations.get(name).unwrap().ty, &declarations);
+
+  // the nested enums are unions of unions, and the tagged variants are intersections
+  assert!(generated::User::shape(&options).is_equivalent(&expected("User")));
+  assert!(generated::Role::shape(&options).is_equivalent(&expected("Role")));
+  assert!(generated::Event::shape(&options).is_equivalent(&expected("Event")));
+  eq!(generated::Message::shape(&options), expected("Message"));
+  eq!(generated::Value::shape(&options), expected("Value"));
+  eq!(generated::Figure::shape(&options), expected("Figure"));
+
+  let page = expected("Page").fold(|ty, _| match ty {
+    Type::Param(_) => Type::Boolean,
+    ty => ty,
+  });
+  eq!(generated::Page::<bool>::shape(&options), page);
+}
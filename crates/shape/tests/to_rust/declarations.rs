@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use shape::Shape;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Shape)]
+#[serde(rename_all = "kebab-case")]
+pub enum UserStatus {
+    Active,
+    InProgress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+#[serde(rename_all = "kebab-case")]
+pub struct UserAddress {
+    pub street: String,
+    pub zip_code: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Shape)]
+#[serde(rename_all = "lowercase")]
+pub enum UserType {
+    User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: f64,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[shape(with = "String")]
+    pub email: Option<String>,
+    pub manager: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[shape(type = "Date")]
+    pub created_at: serde_json::Value,
+    pub tags: Vec<String>,
+    pub scores: std::collections::HashMap<String, f64>,
+    pub point: (f64, f64),
+    pub roles: std::collections::HashMap<Role, bool>,
+    pub status: UserStatus,
+    pub address: UserAddress,
+    #[serde(rename = "type")]
+    pub type_: UserType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Shape)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Guest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+#[serde(tag = "type")]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Created {
+        id: f64,
+    },
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+#[serde(tag = "t", content = "c")]
+pub enum Message {
+    Text(String),
+    Size(f64),
+    Ping,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+#[serde(untagged)]
+pub enum Value {
+    Variant1(String),
+    Variant2(Vec<f64>),
+    Variant3 {
+        a: f64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<String>,
+    #[serde(flatten)]
+    pub rest: std::collections::HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Shape)]
+pub enum Figure {
+    Unit,
+    Circle {
+        radius: f64,
+    },
+    Square(f64),
+}
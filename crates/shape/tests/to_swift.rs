@@ -0,0 +1,350 @@
+mod common;
+
+use shape::{Declarations, Shape, ShapeOptions, ToSwift};
+
+/// The Swift declaration of `name` after adding `T`
+fn swift<T: Shape>(name: &str) -> String {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<T>();
+  declarations.get(name).unwrap().to_swift()
+}
+
+#[test]
+fn optionals() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+  }
+
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Profile {
+    nickname: Option<String>,
+    scores: Vec<Option<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manager: Option<Page<u8>>,
+  }
+
+  eq!(swift::<Page<u8>>("Page"), r#"
+struct Page<T: Codable>: Codable {
+    let items: [T]
+    let next: String?
+}
+"#.trim());
+
+  eq!(swift::<Profile>("Profile"), r#"
+struct Profile: Codable {
+    let nickname: String?
+    let scores: [Double?]
+    let manager: Page<Double>?
+}
+"#.trim());
+}
+
+#[test]
+fn internally_tagged() {
+  #[derive(Shape)]
+  #[serde(tag = "type", rename_all = "snake_case")]
+  #[allow(unused)]
+  enum Event {
+    Created { id: u64 },
+    Deleted,
+  }
+
+  eq!(swift::<Event>("Event"), r#"
+struct EventCreated: Codable {
+    let id: Double
+}
+
+enum Event: Codable {
+    case created(EventCreated)
+    case deleted
+
+    private enum TagCodingKeys: String, CodingKey {
+        case tag = "type"
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: TagCodingKeys.self)
+        let tag = try container.decode(String.self, forKey: .tag)
+        switch tag {
+        case "created":
+            self = .created(try EventCreated(from: decoder))
+        case "deleted":
+            self = .deleted
+        default:
+            throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "unknown variant \(tag) of Event")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: TagCodingKeys.self)
+        switch self {
+        case .created(let value):
+            try container.encode("created", forKey: .tag)
+            try value.encode(to: encoder)
+        case .deleted:
+            try container.encode("deleted", forKey: .tag)
+        }
+    }
+}
+"#.trim());
+}
+
+#[test]
+fn adjacently_tagged() {
+  #[derive(Shape)]
+  #[serde(tag = "t", content = "c")]
+  #[allow(unused)]
+  enum Message {
+    Text(String),
+    Image { url: String },
+    Ping,
+  }
+
+  eq!(swift::<Message>("Message"), r#"
+struct MessageImage: Codable {
+    let url: String
+}
+
+enum Message: Codable {
+    case text(String)
+    case image(MessageImage)
+    case ping
+
+    private enum CodingKeys: String, CodingKey {
+        case tag = "t"
+        case content = "c"
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        let tag = try container.decode(String.self, forKey: .tag)
+        switch tag {
+        case "Text":
+            self = .text(try container.decode(String.self, forKey: .content))
+        case "Image":
+            self = .image(try container.decode(MessageImage.self, forKey: .content))
+        case "Ping":
+            self = .ping
+        default:
+            throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "unknown variant \(tag) of Message")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: CodingKeys.self)
+        switch self {
+        case .text(let value):
+            try container.encode("Text", forKey: .tag)
+            try container.encode(value, forKey: .content)
+        case .image(let value):
+            try container.encode("Image", forKey: .tag)
+            try container.encode(value, forKey: .content)
+        case .ping:
+            try container.encode("Ping", forKey: .tag)
+        }
+    }
+}
+"#.trim());
+}
+
+#[test]
+fn externally_tagged() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Status {
+    Active,
+    Banned { until: u64 },
+  }
+
+  eq!(swift::<Status>("Status"), r#"
+struct StatusBanned: Codable {
+    let until: Double
+}
+
+enum Status: Codable {
+    case active
+    case banned(StatusBanned)
+
+    private enum CodingKeys: String, CodingKey {
+        case banned = "Banned"
+    }
+
+    init(from decoder: Decoder) throws {
+        if let container = try? decoder.singleValueContainer(), let value = try? container.decode(String.self) {
+            switch value {
+            case "Active":
+                self = .active
+                return
+            default:
+                break
+            }
+        }
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        if container.contains(.banned) {
+            self = .banned(try container.decode(StatusBanned.self, forKey: .banned))
+            return
+        }
+        throw DecodingError.dataCorrupted(DecodingError.Context(codingPath: decoder.codingPath, debugDescription: "unknown variant of Status"))
+    }
+
+    func encode(to encoder: Encoder) throws {
+        switch self {
+        case .active:
+            var container = encoder.singleValueContainer()
+            try container.encode("Active")
+        case .banned(let value):
+            var container = encoder.container(keyedBy: CodingKeys.self)
+            try container.encode(value, forKey: .banned)
+        }
+    }
+}
+"#.trim());
+}
+
+#[test]
+fn unit_variants() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  enum Color {
+    Red,
+    Green,
+  }
+
+  eq!(swift::<Color>("Color"), r#"
+enum Color: String, Codable {
+    case red = "Red"
+    case green = "Green"
+}
+"#.trim());
+}
+
+#[test]
+fn untagged() {
+  #[derive(Shape)]
+  #[serde(untagged)]
+  #[allow(unused)]
+  enum Id {
+    Number(u64),
+    Text(String),
+  }
+
+  eq!(swift::<Id>("Id"), r#"
+enum Id: Codable {
+    case variant1(Double)
+    case variant2(String)
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if let value = try? container.decode(Double.self) {
+            self = .variant1(value)
+            return
+        }
+        if let value = try? container.decode(String.self) {
+            self = .variant2(value)
+            return
+        }
+        throw DecodingError.dataCorruptedError(in: container, debugDescription: "no variant of Id matches")
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .variant1(let value):
+            try container.encode(value)
+        case .variant2(let value):
+            try container.encode(value)
+        }
+    }
+}
+"#.trim());
+}
+
+#[test]
+fn declarations_header() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Feed {
+    items: Vec<String>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Feed>();
+  let out = declarations.to_swift();
+  assert!(out.starts_with(r#"import Foundation
+
+struct Feed: Codable {"#), "{out}");
+}
+
+#[test]
+fn reserved_words() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Account {
+    display_name: String,
+    #[serde(rename = "class")]
+    group: (u8, bool),
+    #[serde(rename = "is")]
+    active: bool,
+    #[serde(rename = "default")]
+    fallback: Option<String>,
+  }
+
+  #[derive(Shape)]
+  #[serde(rename_all = "lowercase")]
+  #[allow(unused)]
+  enum Visibility {
+    Public,
+    Default,
+    Internal,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Account>();
+  declarations.add::<Visibility>();
+
+  eq!(declarations.get("Account").unwrap().to_swift(), r#"
+struct AccountClass: Codable {
+    let _0: Double
+    let _1: Bool
+
+    init(from decoder: Decoder) throws {
+        var container = try decoder.unkeyedContainer()
+        _0 = try container.decode(Double.self)
+        _1 = try container.decode(Bool.self)
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.unkeyedContainer()
+        try container.encode(_0)
+        try container.encode(_1)
+    }
+}
+
+struct Account: Codable {
+    let displayName: String
+    let `class`: AccountClass
+    let `is`: Bool
+    let `default`: String?
+
+    enum CodingKeys: String, CodingKey {
+        case displayName = "display_name"
+        case `class`
+        case `is`
+        case `default`
+    }
+}
+"#.trim());
+
+  // the cases of unit variants are escaped too
+  eq!(declarations.get("Visibility").unwrap().to_swift(), r#"
+enum Visibility: String, Codable {
+    case `public` = "public"
+    case `default` = "default"
+    case `internal` = "internal"
+}
+"#.trim());
+}
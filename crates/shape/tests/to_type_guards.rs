@@ -0,0 +1,100 @@
+mod common;
+
+use std::collections::HashMap;
+
+use shape::{Declarations, Shape, ShapeOptions, ToTypeGuards};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct Page<T> {
+  items: Vec<T>,
+  next: Option<String>,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u64,
+  #[serde(rename = "display-name")]
+  display_name: String,
+  location: (f64, f64),
+  scores: HashMap<u8, bool>,
+}
+
+#[derive(Shape)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[allow(unused)]
+enum Event {
+  Created { user: User },
+  Deleted,
+}
+
+#[test]
+fn declaration() {
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Page<Event>>();
+
+  eq!(declarations.get("Page").unwrap().to_type_guards(), r#"
+export function isPage<T>(v: unknown, isT: (v: unknown) => v is T): v is Page<T> {
+  return (isObject(v) && hasProperty(v, "items", (v1) => (Array.isArray(v1) && v1.every((v2) => isT(v2)))) && hasProperty(v, "next", (v1) => (typeof v1 === "string" || v1 === null)));
+}
+"#.trim());
+
+  eq!(declarations.get("User").unwrap().to_type_guards(), r#"
+export function isUser(v: unknown): v is User {
+  return (isObject(v) && hasProperty(v, "id", (v1) => typeof v1 === "number") && hasProperty(v, "display-name", (v1) => typeof v1 === "string") && hasProperty(v, "location", (v1) => (Array.isArray(v1) && typeof v1[0] === "number" && typeof v1[1] === "number")) && hasProperty(v, "scores", (v1) => (isObject(v1) && Object.entries(v1).every(([k, v2]) => isIntegerKey(k) && typeof v2 === "boolean"))));
+}
+"#.trim());
+
+  eq!(declarations.get("Event").unwrap().to_type_guards(), r#"
+export function isEvent(v: unknown): v is Event {
+  return (((isObject(v) && hasProperty(v, "type", (v1) => v1 === "created")) && (isObject(v) && hasProperty(v, "user", (v1) => isUser(v1)))) || (isObject(v) && hasProperty(v, "type", (v1) => v1 === "deleted")));
+}
+"#.trim());
+}
+
+#[test]
+fn keys_and_tuples() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Account {
+    #[serde(rename = "display-name")]
+    display_name: String,
+    location: (f64, f64),
+    scores: HashMap<u8, bool>,
+    flags: std::collections::BTreeMap<bool, u8>,
+  }
+
+  // the keys of maps are the strings that serde_json reads as their key
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Account>();
+  eq!(declarations.get("Account").unwrap().to_type_guards(), r#"
+export function isAccount(v: unknown): v is Account {
+  return (isObject(v) && hasProperty(v, "display-name", (v1) => typeof v1 === "string") && hasProperty(v, "location", (v1) => (Array.isArray(v1) && typeof v1[0] === "number" && typeof v1[1] === "number")) && hasProperty(v, "scores", (v1) => (isObject(v1) && Object.entries(v1).every(([k, v2]) => isIntegerKey(k) && typeof v2 === "boolean"))) && hasProperty(v, "flags", (v1) => (isObject(v1) && Object.entries(v1).every(([k, v2]) => (k === "true" || k === "false") && typeof v2 === "number"))));
+}
+"#.trim());
+}
+
+#[test]
+fn declarations() {
+  #[derive(Shape)]
+  #[allow(unused)]
+  struct Feed {
+    events: Page<Event>,
+    pages: Vec<Page<Vec<u8>>>,
+  }
+
+  let mut declarations = Declarations::new(ShapeOptions::for_deserialize());
+  declarations.add::<Feed>();
+  let out = declarations.to_type_guards();
+
+  assert!(out.starts_with(shape::TYPE_GUARDS_PRELUDE));
+  assert!(out.contains(r#"
+export type Feed = { events: Page<Event>; pages: Array<Page<Array<number>>>; };
+
+export function isFeed(v: unknown): v is Feed {
+  return (isObject(v) && hasProperty(v, "events", (v1) => isPage(v1, isEvent)) && hasProperty(v, "pages", (v1) => (Array.isArray(v1) && v1.every((v2) => isPage(v2, (v3: unknown): v3 is Array<number> => (Array.isArray(v3) && v3.every((v4) => typeof v4 === "number")))))));
+}
+"#));
+  assert!(out.contains("export function isUser(v: unknown): v is User {"));
+}
@@ -1,4 +1,6 @@
-use shape::{Array, Literal, Object, Property, Record, Shape, ShapeOptions, Type};
+use std::sync::Arc;
+
+use shape::{Array, Literal, Object, Property, Record, RecordKey, Shape, ShapeOptions, Type};
 use shape::ToTypescript;
 use text_diff::print_diff;
 
@@ -84,7 +86,7 @@ fn option() {
 
 #[test]
 fn array() {
-  eq!(Type::Array(Array { item: Box::new(Type::String) }), "Array<string>");
+  eq!(Type::Array(Array { item: Arc::new(Type::String) }), "Array<string>");
 }
 
 #[test]
@@ -95,7 +97,7 @@ fn tuple() {
 
 #[test]
 fn record() {
-  eq!(Type::Record(Record { optional: false, readonly: false, key: Box::new(Type::String), value: Box::new(Type::Number) }), "{[key:string]:number}");
+  eq!(Type::Record(Record { optional: false, readonly: false, key: RecordKey::String, value: Arc::new(Type::Number) }), "{[key:string]:number}");
 }
 
 #[test]
@@ -106,7 +108,7 @@ fn object() {
         (
           "a".into(), 
           Property { 
-            ty: Type::String,
+            ty: Arc::new(Type::String),
             optional: false,
             readonly: false
           }
@@ -114,7 +116,7 @@ fn object() {
         (
           "b".into(), 
           Property { 
-            ty: Type::Number,
+            ty: Arc::new(Type::Number),
             optional: false,
             readonly: false
           }
@@ -128,10 +130,10 @@ fn object() {
 
 #[test]
 fn logical_or_and() {
-  let shape = Type::Or(vec![
+  let shape = Type::or([
     Type::String,
     Type::Number,
-    Type::And(vec![
+    Type::and([
       Type::String,
       Type::Boolean,
     ])
@@ -164,21 +166,21 @@ fn quoted() {
     properties: indexmap::IndexMap::from([(
       "quoted-key".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: false,
       },
     ), (
       "2two".into(),
       Property {
-        ty: Type::Number,
+        ty: Arc::new(Type::Number),
         optional: true,
         readonly: true,
       },
     ), (
       "".into(),
       Property {
-        ty: Type::Boolean,
+        ty: Arc::new(Type::Boolean),
         optional: false,
         readonly: false,
       },
@@ -194,7 +196,7 @@ fn readonly() {
     properties: indexmap::IndexMap::from([(
       "key".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: false,
         readonly: true,
       },
@@ -210,7 +212,7 @@ fn optional() {
     properties: indexmap::IndexMap::from([(
       "key".into(),
       Property {
-        ty: Type::String,
+        ty: Arc::new(Type::String),
         optional: true,
         readonly: false,
       },
@@ -222,12 +224,12 @@ fn optional() {
 
 #[test]
 fn empty_or() {
-  let shape = Type::Or(vec![]);
+  let shape = Type::or([]);
   eq!(shape, "never");
 }
 
 #[test]
 fn empty_and() {
-  let shape = Type::And(vec![]);
+  let shape = Type::and([]);
   eq!(shape, "never");
 }
\ No newline at end of file
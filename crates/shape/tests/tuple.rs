@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use shape::{Shape, ShapeOptions, Tuple, Type};
 
 mod common;
@@ -5,7 +7,7 @@ mod common;
 #[test]
 fn tuple() {
   let expected = Type::Tuple(Tuple {
-    items: vec![Type::Number, Type::String],
+    items: vec![Arc::new(Type::Number), Arc::new(Type::String)],
     rest: None,
   });
 
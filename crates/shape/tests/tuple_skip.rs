@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use serde::Serialize;
 use serde_json::json;
 use shape::{Shape, ShapeOptions, Tuple, Type};
@@ -32,12 +34,12 @@ fn tuple_skip_if() {
 
   eq!(
     Ty::shape(&ShapeOptions::for_serialize()),
-    Type::Or(vec![
+    Type::or([
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Boolean,
+            Arc::new(Type::String),
+            Arc::new(Type::Boolean),
           ],
           rest: None,
         }
@@ -45,9 +47,9 @@ fn tuple_skip_if() {
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
-            Type::Boolean,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
+            Arc::new(Type::Boolean),
           ],
           rest: None,
         }
@@ -64,18 +66,18 @@ fn tuple_skip_if_two() {
 
   eq!(
     Ty::shape(&ShapeOptions::for_serialize()),
-    Type::Or(vec![
+    Type::or([
       Type::Tuple(
         Tuple {
-          items: vec![ Type::String ],
+          items: vec![ Arc::new(Type::String) ],
           rest: None,
         }
       ),
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
           ],
           rest: None,
         }
@@ -83,8 +85,8 @@ fn tuple_skip_if_two() {
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Boolean,
+            Arc::new(Type::String),
+            Arc::new(Type::Boolean),
           ],
           rest: None,
         }
@@ -92,9 +94,9 @@ fn tuple_skip_if_two() {
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
-            Type::Boolean,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
+            Arc::new(Type::Boolean),
           ],
           rest: None,
         }
@@ -113,8 +115,8 @@ fn tuple_skip_deserializing() {
     Type::Tuple(
       Tuple {
         items: vec![
-          Type::String,
-          Type::Boolean,
+          Arc::new(Type::String),
+          Arc::new(Type::Boolean),
         ],
         rest: None,
       }
@@ -129,12 +131,12 @@ fn tuple_default() {
 
   eq!(
     Ty::shape(&ShapeOptions::for_deserialize()),
-    Type::Or(vec![
+    Type::or([
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
           ],
           rest: None,
         }
@@ -142,9 +144,9 @@ fn tuple_default() {
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
-            Type::Boolean,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
+            Arc::new(Type::Boolean),
           ],
           rest: None,
         }
@@ -161,18 +163,18 @@ fn tuple_default_two() {
 
   eq!(
     Ty::shape(&ShapeOptions::for_deserialize()),
-    Type::Or(vec![
+    Type::or([
       Type::Tuple(
         Tuple {
-          items: vec![ Type::String ],
+          items: vec![ Arc::new(Type::String) ],
           rest: None,
         }
       ),
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
           ],
           rest: None,
         }
@@ -180,9 +182,9 @@ fn tuple_default_two() {
       Type::Tuple(
         Tuple {
           items: vec![
-            Type::String,
-            Type::Number,
-            Type::Boolean,
+            Arc::new(Type::String),
+            Arc::new(Type::Number),
+            Arc::new(Type::Boolean),
           ],
           rest: None,
         }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use shape::{Shape, ShapeOptions, Tuple, Type};
 
 mod common;
@@ -9,7 +11,7 @@ fn tuple_primitive() {
   struct TupleStruct(String, i32);
 
   let expected = Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
     rest: None,
   });
 
@@ -24,7 +26,7 @@ fn tuple_skip() {
   struct TupleStruct(String, #[serde(skip)] i32);
 
   let expected = Type::Tuple(Tuple {
-    items: vec![Type::String],
+    items: vec![Arc::new(Type::String)],
     rest: None,
   });
 
@@ -39,12 +41,12 @@ fn tuple_skip_serializing() {
   struct TupleStruct(String, #[serde(skip_serializing)] i32);
 
   let ser = Type::Tuple(Tuple {
-    items: vec![Type::String],
+    items: vec![Arc::new(Type::String)],
     rest: None,
   });
 
   let de = Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
     rest: None,
   });
 
@@ -59,12 +61,12 @@ fn tuple_skip_deserializing() {
   struct TupleStruct(String, #[serde(skip_deserializing)] i32);
 
   let de = Type::Tuple(Tuple {
-    items: vec![Type::String],
+    items: vec![Arc::new(Type::String)],
     rest: None,
   });
 
   let ser = Type::Tuple(Tuple {
-    items: vec![Type::String, Type::Number],
+    items: vec![Arc::new(Type::String), Arc::new(Type::Number)],
     rest: None,
   });
 
@@ -0,0 +1,84 @@
+mod common;
+
+use std::sync::Arc;
+
+use indexmap::indexmap;
+use serde::Serialize;
+use serde_json::{json, value::RawValue, Map, Number, Value};
+use shape::{check, validate_slice, Declarations, IsAsignable, Object, Property, Record, RecordKey, Shape, ShapeOptions, ToJtd, ToSwift, ToTypescript, Type};
+
+#[derive(Serialize, Shape)]
+struct Event {
+  name: String,
+  payload: Value,
+  meta: Map<String, Value>,
+  raw: Box<RawValue>,
+}
+
+#[test]
+fn json_values() {
+  let options = ShapeOptions::for_serialize();
+  eq!(Value::shape(&options), Type::Unknown);
+  eq!(Number::shape(&options), Type::Number);
+  eq!(Box::<RawValue>::shape(&options), Type::Unknown);
+  eq!(Map::<String, Value>::shape(&options), Type::Record(Record {
+    optional: false,
+    readonly: false,
+    key: RecordKey::String,
+    value: Arc::new(Type::Unknown)
+  }));
+
+  eq!(Event::shape(&options), Type::Object(Object {
+    properties: indexmap! {
+      "name".into() => Property { optional: false, readonly: false, ty: Arc::new(Type::String) },
+      "payload".into() => Property { optional: false, readonly: false, ty: Arc::new(Type::Unknown) },
+      "meta".into() => Property { optional: false, readonly: false, ty: Arc::new(Map::<String, Value>::shape(&options)) },
+      "raw".into() => Property { optional: false, readonly: false, ty: Arc::new(Type::Unknown) }
+    }
+  }));
+}
+
+#[test]
+fn emitters() {
+  let ty = Event::shape(&ShapeOptions::for_serialize());
+  eq!(ty.to_typescript(), "{ name: string; payload: unknown; meta: { [key: string]: unknown }; raw: unknown; }");
+  eq!(ty.to_jtd().unwrap(), json!({
+    "properties": {
+      "name": { "type": "string" },
+      "payload": {},
+      "meta": { "values": {} },
+      "raw": {}
+    }
+  }));
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<Event>();
+  assert!(declarations.to_swift().contains("let payload: JSONValue"));
+  assert!(declarations.to_swift().contains("indirect enum JSONValue: Codable, Hashable"));
+}
+
+#[test]
+fn assignable() {
+  let ty = Event::shape(&ShapeOptions::for_serialize());
+  for payload in [json!(null), json!(1), json!("a"), json!([1, { "a": [] }]), json!({ "b": false })] {
+    assert!(Type::Unknown.is_assignable(&payload));
+    assert!(ty.is_assignable(&json!({ "name": "a", "payload": payload, "meta": { "c": payload }, "raw": payload })));
+  }
+  assert!(!ty.is_assignable(&json!({ "name": "a", "meta": {}, "raw": 1 })));
+
+  let event = Event {
+    name: String::from("a"),
+    payload: json!([1, { "a": null }]),
+    meta: Map::from_iter([(String::from("b"), json!(true))]),
+    raw: RawValue::from_string(String::from("[1, 2]")).unwrap(),
+  };
+  check(&event, &ShapeOptions::for_serialize()).unwrap();
+
+  let json = serde_json::to_string(&event).unwrap();
+  validate_slice::<Value>(json.as_bytes(), &ty).unwrap();
+  // unknown doesn't remove the other types of an intersection
+  let ty = Type::and([Type::Unknown, Type::String]);
+  assert!(validate_slice::<Value>(b"1", &ty).is_err());
+  assert!(!ty.is_assignable(&json!(1)));
+  assert!(ty.is_satisfiable());
+}
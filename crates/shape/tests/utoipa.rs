@@ -0,0 +1,93 @@
+#![cfg(feature = "utoipa")]
+
+mod common;
+
+use serde_json::json;
+use shape::utoipa::{parse_utoipa, ToUtoipa, Utoipa};
+use shape::{shape, Declarations, Shape, ShapeOptions, Type};
+use utoipa::{PartialSchema, ToSchema};
+
+#[derive(ToSchema)]
+#[allow(unused)]
+struct Address {
+  street: String,
+  zip: Option<u32>,
+}
+
+#[derive(ToSchema)]
+#[allow(unused)]
+struct Customer {
+  name: String,
+  address: Address,
+}
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u32,
+  #[shape(with = "Utoipa<Customer>")]
+  customer: Customer,
+}
+
+#[derive(ToSchema)]
+#[allow(unused)]
+struct Order {
+  #[schema(schema_with = shape::utoipa::schema::<User>)]
+  user: User,
+}
+
+#[test]
+fn to_utoipa() {
+  let ty = shape!{ {
+    readonly id: number,
+    name?: string | undefined,
+    role: "admin" | "guest",
+    point: [number, number],
+    tags: Record<string, boolean | null>
+  } & User };
+
+  eq!(serde_json::to_value(ty.to_utoipa()).unwrap(), json!({
+    "allOf": [
+      {
+        "type": "object",
+        "properties": {
+          "id": { "type": "number", "readOnly": true },
+          "name": { "type": "string" },
+          "role": { "enum": ["admin", "guest"] },
+          "point": { "type": "array", "prefixItems": [{ "type": "number" }, { "type": "number" }], "items": false, "minItems": 2 },
+          "tags": {
+            "type": "object",
+            "additionalProperties": { "anyOf": [{ "type": "boolean" }, { "type": "null" }] }
+          }
+        },
+        "required": ["id", "role", "point", "tags"]
+      },
+      { "$ref": "#/components/schemas/User" }
+    ]
+  }));
+
+  eq!(serde_json::to_value(Type::Never.to_utoipa()).unwrap(), json!({ "enum": [] }));
+}
+
+#[test]
+fn utoipa_in_shape() {
+  eq!(User::shape(&ShapeOptions::for_serialize()), shape!{{
+    id: number,
+    customer: { name: string, address: { street: string, zip?: number | null } }
+  }});
+
+  let mut declarations = Declarations::new(ShapeOptions::for_serialize());
+  declarations.add::<User>();
+  eq!(declarations.iter().map(|declaration| &*declaration.name).collect::<Vec<_>>(), vec!["User", "Address", "Customer"]);
+  eq!(declarations.get("Customer").unwrap().ty, shape!{{ name: string, address: Address }});
+}
+
+#[test]
+fn shape_in_utoipa() {
+  let schema = serde_json::to_value(Order::schema()).unwrap();
+  eq!(schema["properties"]["user"], serde_json::to_value(User::shape(&ShapeOptions::for_serialize()).to_utoipa()).unwrap());
+
+  // the shape of the schema is the shape of the type
+  let options = ShapeOptions::for_serialize();
+  eq!(parse_utoipa::<Order>(&options).unwrap(), shape!{{ user: #(User::shape(&options)) }});
+}
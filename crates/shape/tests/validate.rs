@@ -0,0 +1,205 @@
+mod common;
+
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::de::{DeserializeSeed, IgnoredAny};
+use serde::Deserialize;
+use serde_json::Value;
+use shape::{
+  validate_ndjson, validate_reader, validate_slice, IsAsignable, Literal, Object, Property, Record, RecordKey, Shape, ShapeOptions, Tuple, Type,
+  Validate, ValidateError, ValidateErrorKind,
+};
+
+#[derive(Debug, PartialEq, Deserialize, Shape)]
+struct User {
+  id: u64,
+  name: String,
+  #[serde(default)]
+  tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Shape)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event {
+  Created { user: User },
+  Deleted,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Shape)]
+enum Status {
+  Active,
+  Banned { until: u64 },
+  Moved(String, u32),
+}
+
+#[derive(Debug, PartialEq, Deserialize, Shape)]
+struct Feed<'a> {
+  title: &'a str,
+  events: Vec<Event>,
+  statuses: Vec<Status>,
+  scores: HashMap<u8, f64>,
+}
+
+fn object(properties: Vec<(&str, bool, Type)>) -> Type {
+  Type::Object(Object {
+    properties: properties.into_iter().map(|(key, optional, ty)| (key.to_string().into(), Property { optional, readonly: false, ty: Arc::new(ty) })).collect(),
+  })
+}
+
+fn array(item: Type) -> Type {
+  Type::Tuple(Tuple { items: vec![], rest: Some(Arc::new(item)) })
+}
+
+/// The pointer, offset and whether the error is a mismatch
+fn error(result: Result<IgnoredAny, ValidateError>) -> (String, usize, bool) {
+  let error = result.unwrap_err();
+  (error.pointer, error.offset, matches!(error.kind, ValidateErrorKind::Mismatch))
+}
+
+#[test]
+fn derived() {
+  let json = r#"{
+    "title": "feed",
+    "events": [{ "type": "created", "user": { "id": 1, "name": "a" } }, { "type": "deleted" }],
+    "statuses": ["Active", { "Active": null }, { "Banned": { "until": 2 } }, { "Moved": ["b", 3] }],
+    "scores": { "1": 2.5 },
+    "unknown": [1, 2]
+  }"#;
+
+  let ty = Feed::shape(&ShapeOptions::for_deserialize());
+  let feed = validate_slice::<Feed>(json.as_bytes(), &ty).unwrap();
+  eq!(feed, Feed {
+    title: "feed",
+    events: vec![Event::Created { user: User { id: 1, name: String::from("a"), tags: vec![] } }, Event::Deleted],
+    statuses: vec![Status::Active, Status::Active, Status::Banned { until: 2 }, Status::Moved(String::from("b"), 3)],
+    scores: HashMap::from([(1, 2.5)])
+  });
+
+  let events = Vec::<Event>::shape(&ShapeOptions::for_deserialize());
+  let json = r#"[{ "type": "created", "user": { "id": 1, "name": "a", "tags": ["b", 2] } }]"#;
+  let error = validate_reader::<_, Vec<Event>>(json.as_bytes(), &events).unwrap_err();
+  eq!(error.pointer, "/0/user/tags/1");
+  // readers peek the byte after a number
+  eq!(error.offset, json.find("2]").unwrap() + 1);
+  eq!(error.to_string(), format!("\"/0/user/tags/1\" at byte {}: the value is not assignable to the type", error.offset));
+
+  let statuses = Vec::<Status>::shape(&ShapeOptions::for_deserialize());
+  let json = r#"[{ "Moved": ["b", "c"] }]"#;
+  let error = validate_slice::<Vec<Status>>(json.as_bytes(), &statuses).unwrap_err();
+  eq!(error.pointer, "/0/Moved/1");
+}
+
+#[test]
+fn errors() {
+  let ty = object(vec![
+    ("items", false, array(object(vec![("name", false, Type::String), ("note", true, Type::Null)]))),
+    ("count", false, Type::Literal(Literal::Number(2.0))),
+  ]);
+
+  let json = r#"{"items": [{"name": "a"}, {"name": 1}], "count": 2}"#;
+  eq!(error(validate_slice(json.as_bytes(), &ty)), (String::from("/items/1/name"), json.find("1}").unwrap(), true));
+
+  // a missing property is reported at the end of the object
+  let json = r#"{"items": [{"name": "a"}, {"note": null}], "count": 2}"#;
+  eq!(error(validate_slice(json.as_bytes(), &ty)), (String::from("/items/1"), json.find("}]").unwrap(), true));
+
+  let json = r#"{"items": [], "count": 3}"#;
+  eq!(error(validate_slice(json.as_bytes(), &ty)), (String::from("/count"), json.find("3}").unwrap(), true));
+
+  // the value is rejected when it starts
+  let json = r#"{"items": {"a": 1}, "count": 2}"#;
+  eq!(error(validate_slice(json.as_bytes(), &ty)), (String::from("/items"), json.find("{\"a").unwrap(), true));
+
+  // syntax errors have a pointer too
+  let json = "{\"items\": [{\"name\": \"a\"},\n  {\"name\" \"b\"}]}";
+  eq!(error(validate_slice(json.as_bytes(), &ty)), (String::from("/items/1/name"), json.find(" \"b").unwrap() + 1, false));
+  eq!(error(validate_reader(json.as_bytes(), &ty)), (String::from("/items/1/name"), json.find(" \"b").unwrap() + 1, false));
+  eq!(error(validate_slice(br#"{"items": [], "count": 2} []"#, &ty)).2, false);
+
+  let record = |key| Type::Record(Record { optional: false, readonly: false, key: RecordKey::from(key), value: Arc::new(Type::Boolean) });
+  let json = r#"{"1": true, "x/y": true}"#;
+  eq!(error(validate_slice(json.as_bytes(), &record(Type::Number))), (String::from("/x~1y"), json.find(": true}").unwrap() - 1, true));
+
+  // the seed reports the pointer in the message
+  let mut deserializer = serde_json::Deserializer::from_str(r#"{"items": [true]}"#);
+  let error = Validate::new(&ty, PhantomData::<IgnoredAny>).deserialize(&mut deserializer).unwrap_err();
+  eq!(error.to_string(), "the value at \"/items/0\" is not assignable to the type at line 1 column 15");
+
+  // and in the errors of the input and of the visitor
+  let json = "{\"items\": [{\"name\": \"a\"},\n  {\"name\" \"b\"}]}";
+  let mut deserializer = serde_json::Deserializer::from_str(json);
+  let error = Validate::new(&ty, PhantomData::<IgnoredAny>).deserialize(&mut deserializer).unwrap_err();
+  eq!(error.to_string(), "the value at \"/items/1/name\" failed: expected `:` at line 2 column 11");
+  let ty = User::shape(&ShapeOptions::for_deserialize());
+  let mut deserializer = serde_json::Deserializer::from_str(r#"{"id": -1, "name": "a"}"#);
+  let error = Validate::new(&ty, PhantomData::<User>).deserialize(&mut deserializer).unwrap_err();
+  eq!(error.to_string(), "the value at \"/id\" failed: invalid value: integer `-1`, expected u64 at line 1 column 9");
+}
+
+#[test]
+fn ndjson() {
+  let ty = object(vec![("id", false, Type::Number)]);
+  let input = "{\"id\": 1}\n\n{\"id\": \"2\"}\r\n{\"id\": 3}\n{\"id\"\n";
+  let results = validate_ndjson::<_, HashMap<String, u64>>(BufReader::new(input.as_bytes()), &ty).collect::<Vec<_>>();
+
+  eq!(results.len(), 4);
+  eq!(results[0].as_ref().unwrap(), &HashMap::from([(String::from("id"), 1)]));
+  let error = results[1].as_ref().unwrap_err();
+  eq!((error.pointer.as_str(), error.offset), ("/id", input.find("\"2\"").unwrap() + 2));
+  eq!(results[2].as_ref().unwrap(), &HashMap::from([(String::from("id"), 3)]));
+  let error = results[3].as_ref().unwrap_err();
+  assert!(matches!(error.kind, ValidateErrorKind::Json(_)));
+  eq!(error.offset, input.len() - 1);
+}
+
+#[test]
+fn same_as_is_assignable() {
+  let literal = |value: &'static str| Type::Literal(Literal::String(value.into()));
+  let record = |key| Type::Record(Record { optional: false, readonly: false, key: RecordKey::from(key), value: Arc::new(Type::Boolean) });
+  let a = object(vec![("a", false, Type::Number)]);
+  let b = object(vec![("b", true, Type::String)]);
+
+  let cases = vec![
+    (Type::Tuple(Tuple { items: vec![Arc::new(Type::String), Arc::new(Type::Number)], rest: Some(Arc::new(Type::Boolean)) }), vec![r#"["a", 1, true]"#, r#"["a", 1, 2]"#, r#"["a"]"#]),
+    (Type::and([a.clone(), b.clone()]), vec![r#"{"a": 1}"#, r#"{"b": "c"}"#, r#"{"a": 1, "b": 2}"#]),
+    (Type::or([a, b, Type::Null]), vec![r#"{"b": "c"}"#, "null", r#"{"b": 1}"#, "1"]),
+    (record(Type::Number), vec![r#"{"1.5": true, "2": false}"#, r#"{"x": true}"#]),
+    (record(Type::or([literal("a"), literal("b")])), vec![r#"{"a": true, "b": true}"#, r#"{"a": true}"#]),
+    (record(Type::and([Type::String, literal("a")])), vec![r#"{"a": true, "b": 1}"#, r#"{"a": 1}"#]),
+    (Type::Never, vec!["1"]),
+    (Type::and([]), vec!["1", "[{}]"]),
+  ];
+
+  for (ty, values) in cases {
+    for json in values {
+      let value = serde_json::from_str::<Value>(json).unwrap();
+      let validated = validate_slice::<IgnoredAny>(json.as_bytes(), &ty).is_ok();
+      assert_eq!(validated, ty.is_assignable(&value), "{json}");
+    }
+  }
+}
+
+#[test]
+fn wide_intersections() {
+  // 2^12 alternatives, the document is checked on a `serde_json::Value`
+  let members = (0..12).map(|i| {
+    let key = format!("k{i}");
+    Type::or([object(vec![(&key, false, Type::Number)]), object(vec![(&key, false, Type::String)])])
+  });
+  let ty = Type::and(members);
+
+  let json = format!("{{{}}}", (0..12).map(|i| format!("\"k{i}\": {}", if i % 2 == 0 { "1" } else { "\"a\"" })).collect::<Vec<_>>().join(", "));
+  let value = validate_slice::<HashMap<&str, Value>>(json.as_bytes(), &ty).unwrap();
+  eq!(value.len(), 12);
+  assert!(validate_reader::<_, HashMap<String, Value>>(json.as_bytes(), &ty).is_ok());
+  let mut deserializer = serde_json::Deserializer::from_str(&json);
+  assert!(Validate::new(&ty, PhantomData::<IgnoredAny>).deserialize(&mut deserializer).is_ok());
+
+  let json = json.replace("\"k3\": \"a\"", "\"k3\": true");
+  eq!(error(validate_slice(json.as_bytes(), &ty)), (String::new(), json.len(), true));
+  let mut deserializer = serde_json::Deserializer::from_str(&json);
+  assert!(Validate::new(&ty, PhantomData::<IgnoredAny>).deserialize(&mut deserializer).is_err());
+}
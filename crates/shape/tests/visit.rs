@@ -0,0 +1,129 @@
+mod common;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use shape::visit::{walk_object_mut, walk_record};
+use shape::{Custom, Fold, Object, Pointer, Property, Record, Shape, ShapeOptions, ToTypescript, Type, Visit, VisitMut};
+
+#[derive(Shape)]
+#[allow(unused)]
+struct User {
+  id: u64,
+  display_name: String,
+  password: String,
+  #[shape(type = "Date")]
+  created_at: String,
+  tags: Vec<(String, u8)>,
+  settings: HashMap<String, Option<String>>,
+}
+
+fn user() -> Type {
+  User::shape(&ShapeOptions::for_serialize())
+}
+
+#[test]
+fn walk() {
+  let mut strings = vec![];
+  user().walk(|ty, pointer| {
+    if *ty == Type::String {
+      strings.push(pointer.to_string());
+    }
+  });
+  eq!(strings, vec!["/display_name", "/password", "/tags/*/0", "/settings/*"]);
+
+  let mut pointer = Pointer::new();
+  pointer.push("a/b");
+  pointer.push("~c");
+  eq!(pointer.to_string(), "/a~1b/~0c");
+  eq!(pointer.segments(), &[String::from("a/b"), String::from("~c")]);
+  eq!(Pointer::new().to_string(), "");
+}
+
+#[test]
+fn visit() {
+  /// The pointers of the records, the values are not visited
+  #[derive(Default)]
+  struct Records(Vec<String>);
+
+  impl Visit for Records {
+    fn visit_record(&mut self, record: &Record, pointer: &mut Pointer) {
+      self.0.push(pointer.to_string());
+      walk_record(self, record, pointer);
+    }
+
+    fn visit_property(&mut self, key: &str, property: &Property, pointer: &mut Pointer) {
+      if key != "tags" {
+        self.visit_type(&property.ty, pointer);
+      }
+    }
+  }
+
+  let mut records = Records::default();
+  records.visit_type(&Type::Array(shape::Array { item: Arc::new(user()) }), &mut Pointer::new());
+  eq!(records.0, vec!["/*/settings"]);
+}
+
+#[test]
+fn visit_mut() {
+  struct CamelCase;
+
+  impl VisitMut for CamelCase {
+    fn visit_object_mut(&mut self, object: &mut Object, pointer: &mut Pointer) {
+      object.properties = object.properties.drain(..).map(|(key, property)| {
+        let mut parts = key.split('_');
+        let first = parts.next().unwrap_or_default().to_string();
+        let key = parts.fold(first, |key, part| key + &part[..1].to_uppercase() + &part[1..]);
+        (key.into(), property)
+      }).collect();
+      walk_object_mut(self, object, pointer);
+    }
+  }
+
+  let mut ty = user();
+  CamelCase.visit_type_mut(&mut ty, &mut Pointer::new());
+  eq!(
+    ty.to_typescript(),
+    "{ id: number; displayName: string; password: string; createdAt: Date; tags: Array<[string, number]>; settings: { [key: string]: (string | null) }; }"
+  );
+}
+
+#[test]
+fn fold() {
+  struct Strip<'a>(&'a str);
+
+  impl Fold for Strip<'_> {
+    fn fold_property(&mut self, key: Cow<'static, str>, property: Property, pointer: &mut Pointer) -> Option<(Cow<'static, str>, Property)> {
+      if key == self.0 {
+        return None;
+      }
+      let ty = self.fold_type(Arc::unwrap_or_clone(property.ty), pointer);
+      Some((key, Property { ty: Arc::new(ty), ..property }))
+    }
+  }
+
+  let ty = Strip("password").fold_type(user(), &mut Pointer::new());
+  assert!(!ty.to_typescript().contains("password"));
+
+  // replace the custom types
+  let ty = ty.fold(|ty, pointer| match ty {
+    Type::Custom(custom) if custom.name() == "Date" => {
+      eq!(pointer.to_string(), "/created_at");
+      Type::String
+    },
+    ty => ty,
+  });
+  eq!(
+    ty.to_typescript(),
+    "{ id: number; display_name: string; created_at: string; tags: Array<[string, number]>; settings: { [key: string]: (string | null) }; }"
+  );
+
+  // the children are folded before their parents
+  let mut order = vec![];
+  Type::or([Type::Custom(Custom::from("A")), Type::Null]).fold(|ty, _| {
+    order.push(ty.to_typescript());
+    ty
+  });
+  eq!(order, vec!["A", "null", "(A | null)"]);
+}